@@ -0,0 +1,29 @@
+//! Parallel vs sequential reduction benchmarks, run with `cargo bench`.
+
+use parallel_operations::bench::{benchmark, generate_data};
+
+const ITERATIONS: u32 = 20;
+
+fn report<T: Copy + Send + Sync>(name: &str, data: &[T], identity: T, operation: fn(T, T) -> T) {
+    let report = benchmark(data, identity, operation, ITERATIONS);
+    println!(
+        "{:<24} {:>10} elements  parallel {:>12?}  sequential {:>12?}  speedup {:>6.2}x  {:>6.2} GB/s",
+        name,
+        data.len(),
+        report.mean_parallel(),
+        report.mean_sequential(),
+        report.speedup(),
+        report.parallel_throughput().gb_per_sec()
+    );
+}
+
+fn main() {
+    for size in [10_000, 1_000_000, 10_000_000] {
+        let ints = generate_data(size, |i| i as i64);
+        report("i64 sum", &ints, 0, |a, b| a.wrapping_add(b));
+        report("i64 max", &ints, i64::MIN, i64::max);
+
+        let floats = generate_data(size, |i| i as f64);
+        report("f64 sum", &floats, 0.0, |a, b| a + b);
+    }
+}
@@ -0,0 +1,95 @@
+use rayon::prelude::*;
+
+use crate::{get_initial_value, Bounded, ReduceIdentity};
+
+/// Extension methods that bring the crate's identity handling to any rayon pipeline.
+///
+/// Implemented for every [`ParallelIterator`], so the reductions compose with existing
+/// rayon code instead of requiring a `Vec` up front.
+pub trait ParallelOps: ParallelIterator {
+    /// Reduces the iterator, probing the identity from the operation the same way as
+    /// [`parallel_binary_operation`](crate::parallel_binary_operation).
+    ///
+    /// # Returns
+    /// The result of applying the operation to all items, or `T::default()` if there are none.
+    fn reduce_with_op(self, operation: fn(Self::Item, Self::Item) -> Self::Item) -> Self::Item
+    where
+        Self::Item: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+    {
+        let identity = get_initial_value(operation);
+        self.fold(|| identity, operation)
+            .reduce(|| identity, operation)
+    }
+
+    /// Reduces the iterator with an explicitly supplied identity.
+    ///
+    /// # Returns
+    /// The result of applying the operation to all items, or `identity` if there are none.
+    fn reduce_with_identity(
+        self,
+        identity: Self::Item,
+        operation: fn(Self::Item, Self::Item) -> Self::Item,
+    ) -> Self::Item
+    where
+        Self::Item: Copy + Send + Sync,
+    {
+        self.fold(|| identity, operation)
+            .reduce(|| identity, operation)
+    }
+
+    /// Reduces the iterator using the item type's [`ReduceIdentity`].
+    ///
+    /// # Returns
+    /// The result of applying the operation to all items, or `T::identity()` if there are none.
+    fn reduce_identity(self, operation: fn(Self::Item, Self::Item) -> Self::Item) -> Self::Item
+    where
+        Self::Item: ReduceIdentity + Send,
+    {
+        self.fold(Self::Item::identity, operation)
+            .reduce(Self::Item::identity, operation)
+    }
+}
+
+impl<I: ParallelIterator> ParallelOps for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that a probed identity works inside a rayon pipeline
+    #[test]
+    fn test_reduce_with_op() {
+        let data: Vec<i32> = (1..=10).collect();
+        let product = data.par_iter().copied().reduce_with_op(|a, b| a * b);
+        assert_eq!(product, 3_628_800);
+    }
+
+    // Test with an explicit identity after a filter
+    #[test]
+    fn test_reduce_with_identity() {
+        let max_even = (0..1_000u32)
+            .into_par_iter()
+            .filter(|x| x % 2 == 0)
+            .reduce_with_identity(0, u32::max);
+        assert_eq!(max_even, 998);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Count(usize);
+
+    impl ReduceIdentity for Count {
+        fn identity() -> Self {
+            Count(0)
+        }
+    }
+
+    // Test with a ReduceIdentity type
+    #[test]
+    fn test_reduce_identity() {
+        let count = (0..500)
+            .into_par_iter()
+            .map(|_| Count(1))
+            .reduce_identity(|a, b| Count(a.0 + b.0));
+        assert_eq!(count, Count(500));
+    }
+}
@@ -0,0 +1,67 @@
+use std::borrow::Borrow;
+
+use crate::identity::reduce_slice_with_identity;
+use crate::{get_initial_value, Bounded};
+
+/// Arrays with fewer elements than this are always reduced sequentially.
+pub const ARRAY_PARALLEL_LEN: usize = 4096;
+
+/// Decides at compile time how an array of `N` elements is reduced.
+struct ArrayLen<const N: usize>;
+
+impl<const N: usize> ArrayLen<N> {
+    const SEQUENTIAL: bool = N < ARRAY_PARALLEL_LEN;
+}
+
+/// Performs a binary operation on a fixed-size array, in parallel if it is large enough.
+///
+/// Accepts the array by value or by reference, so stack-allocated data needs no
+/// conversion to a `Vec`. Whether the array is reduced sequentially is decided from `N`
+/// at compile time: arrays shorter than [`ARRAY_PARALLEL_LEN`] are folded in place and
+/// never touch the thread pool, longer ones are reduced like
+/// [`parallel_binary_operation`](crate::parallel_binary_operation).
+///
+/// # Parameters
+/// - `data`: An array of type `T` that contains the data to operate on, or a reference to one.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the binary operation to all elements, or `T::default()` for an
+/// empty array.
+pub fn parallel_reduce_array<T, A, const N: usize>(data: A, operation: fn(T, T) -> T) -> T
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+    A: Borrow<[T; N]>,
+{
+    let data = data.borrow();
+    if ArrayLen::<N>::SEQUENTIAL {
+        return data.iter().copied().reduce(operation).unwrap_or_default();
+    }
+    reduce_slice_with_identity(data, get_initial_value(operation), operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test a small array by value and by reference
+    #[test]
+    fn test_reduce_small_array() {
+        let data = [3, 1, 4, 1, 5];
+        assert_eq!(parallel_reduce_array(data, |a, b| a + b), 14);
+        let borrowed: &[i32; 5] = &data;
+        assert_eq!(parallel_reduce_array(borrowed, |a, b| a * b), 60);
+        assert_eq!(parallel_reduce_array([0u8; 0], |a, b| a + b), 0);
+    }
+
+    // Test an array above the parallel threshold
+    #[test]
+    fn test_reduce_large_array() {
+        let mut data = [0u64; 10_000];
+        for (i, x) in data.iter_mut().enumerate() {
+            *x = i as u64;
+        }
+        const { assert!(!ArrayLen::<10_000>::SEQUENTIAL) };
+        assert_eq!(parallel_reduce_array(data, |a, b| a + b), 49_995_000);
+    }
+}
@@ -0,0 +1,134 @@
+use rayon::prelude::*;
+
+use crate::chunk_size_for;
+use crate::platform::available_threads;
+
+/// Number of work units per thread to aim for, so uneven batches still balance.
+const UNITS_PER_THREAD: usize = 4;
+
+/// Reduces many vectors concurrently, one result per vector.
+///
+/// All batches are cut into work units of roughly equal size: large vectors are split
+/// across several units and small ones stay whole, so a few huge batches do not leave
+/// cores idle and many tiny ones do not each pay for a parallel reduction. Every
+/// batch is folded starting from its first element, in order.
+///
+/// # Parameters
+/// - `batches`: The vectors to reduce.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// One result per batch, in the same order; `T::default()` for an empty batch.
+pub fn parallel_reduce_batch<T>(batches: &[Vec<T>], operation: fn(T, T) -> T) -> Vec<T>
+where
+    T: Copy + Send + Sync + Default,
+{
+    let partials: Vec<(usize, T)> = work_units(batches)
+        .into_par_iter()
+        .map(|(index, unit)| (index, fold_unit(unit, operation)))
+        .collect();
+
+    let mut results: Vec<Option<T>> = vec![None; batches.len()];
+    for (index, partial) in partials {
+        let slot = &mut results[index];
+        *slot = Some(match *slot {
+            Some(acc) => operation(acc, partial),
+            None => partial,
+        });
+    }
+    results.into_iter().map(Option::unwrap_or_default).collect()
+}
+
+/// Reduces a jagged vector of vectors as if it were flattened, without copying it.
+///
+/// The inner vectors are cut into the same balanced work units as
+/// [`parallel_reduce_batch`], which is the per-inner-vector counterpart, and the units'
+/// results are combined in order, so `operation` only needs to be associative.
+///
+/// # Parameters
+/// - `data`: The vectors to reduce together.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The reduction of every element in order; `T::default()` if there are none.
+pub fn parallel_reduce_nested<T>(data: &[Vec<T>], operation: fn(T, T) -> T) -> T
+where
+    T: Copy + Send + Sync + Default,
+{
+    work_units(data)
+        .into_par_iter()
+        .map(|(_, unit)| fold_unit(unit, operation))
+        .reduce_with(operation)
+        .unwrap_or_default()
+}
+
+/// Cuts all batches into work units of roughly equal size.
+///
+/// # Returns
+/// `(batch index, slice)` pairs in batch order; empty batches have no units.
+fn work_units<T>(batches: &[Vec<T>]) -> Vec<(usize, &[T])> {
+    let total: usize = batches.iter().map(Vec::len).sum();
+    let unit_size = chunk_size_for(total, available_threads() * UNITS_PER_THREAD);
+    batches
+        .iter()
+        .enumerate()
+        .flat_map(|(index, batch)| batch.chunks(unit_size).map(move |unit| (index, unit)))
+        .collect()
+}
+
+/// Folds a non-empty work unit starting from its first element.
+fn fold_unit<T: Copy>(unit: &[T], operation: fn(T, T) -> T) -> T {
+    let (first, rest) = unit.split_first().expect("chunks are never empty");
+    rest.iter().copied().fold(*first, operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test uneven batch sizes, including an empty batch
+    #[test]
+    fn test_reduce_batch_uneven() {
+        let batches: Vec<Vec<i64>> = vec![
+            (1..=100_000).collect(),
+            vec![5],
+            Vec::new(),
+            (1..=10).collect(),
+        ];
+        let results = parallel_reduce_batch(&batches, |a, b| a + b);
+        assert_eq!(results, vec![5_000_050_000, 5, 0, 55]);
+    }
+
+    // Test that each batch is combined in order
+    #[test]
+    fn test_reduce_batch_order() {
+        const P: u64 = 1_000_000_007;
+        // Digit concatenation as (value, 10^len) pairs: associative but not commutative
+        let op = |(a, pa): (u64, u64), (b, pb): (u64, u64)| ((a * pb + b) % P, (pa * pb) % P);
+        let batches: Vec<Vec<(u64, u64)>> = (0..50)
+            .map(|i| (0..(i * 997) % 5_000).map(|x| (x % 10, 10)).collect())
+            .collect();
+        let expected: Vec<(u64, u64)> = batches
+            .iter()
+            .map(|b| b.iter().copied().reduce(op).unwrap_or_default())
+            .collect();
+        assert_eq!(parallel_reduce_batch(&batches, op), expected);
+    }
+
+    // Test that a nested reduction equals the reduction of the flattened data
+    #[test]
+    fn test_reduce_nested() {
+        let data: Vec<Vec<i64>> = (0..300).map(|i| (0..(i * 131) % 2_000).collect()).collect();
+        let flat: Vec<i64> = data.concat();
+        assert_eq!(
+            parallel_reduce_nested(&data, |a, b| a + b),
+            flat.iter().sum()
+        );
+        // An order-sensitive operation that keeps the first element
+        assert_eq!(parallel_reduce_nested(&data, |a, _| a), flat[0]);
+        assert_eq!(
+            parallel_reduce_nested::<u8>(&[vec![], vec![]], |a, b| a + b),
+            0
+        );
+    }
+}
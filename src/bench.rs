@@ -0,0 +1,657 @@
+//! Reusable benchmarking harness: time a parallel reduction against a sequential fold.
+//!
+//! This is the methodology the `bench` subcommand uses, exposed so downstream crates can
+//! benchmark their own operations the same way.
+
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::identity::reduce_slice_with_identity;
+use crate::platform::Stopwatch;
+use crate::{parallel_duration_mean, parallel_duration_percentiles};
+
+/// Untimed iterations [`benchmark`] runs before measuring, to warm caches and the thread pool.
+pub const DEFAULT_WARMUP: u32 = 3;
+
+/// Two-sided 95% quantile of the standard normal distribution.
+const Z_95: f64 = 1.96;
+
+/// Timings of one benchmark iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    /// Wall time of the parallel reduction.
+    pub parallel: Duration,
+    /// Wall time of the sequential fold.
+    pub sequential: Duration,
+}
+
+impl Sample {
+    /// Returns how many times faster the parallel run was than the sequential one.
+    pub fn speedup(&self) -> f64 {
+        speedup(self.sequential, self.parallel)
+    }
+}
+
+/// Descriptive statistics of one series of timings.
+///
+/// Outliers are rejected with Tukey's fences: samples further than 1.5 interquartile
+/// ranges outside the first or third quartile are dropped before anything else is computed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    /// Number of samples kept after outlier rejection.
+    pub count: usize,
+    /// Number of samples rejected as outliers.
+    pub outliers: usize,
+    /// Mean of the kept samples.
+    pub mean: Duration,
+    /// Median of the kept samples.
+    pub median: Duration,
+    /// Sample standard deviation of the kept samples.
+    pub std_dev: Duration,
+    /// 95% confidence interval of the mean, using a normal approximation.
+    pub ci95: (Duration, Duration),
+}
+
+impl Summary {
+    /// Summarizes a series of timings.
+    ///
+    /// # Returns
+    /// The summary, or `None` for an empty series.
+    pub fn of(durations: &[Duration]) -> Option<Summary> {
+        let quartiles = parallel_duration_percentiles(durations, &[25.0, 75.0]);
+        let (q1, q3) = (*quartiles.first()?, quartiles[1]);
+        let fence = (q3 - q1).mul_f64(1.5);
+        let (low, high) = (q1.saturating_sub(fence), q3.saturating_add(fence));
+        let kept: Vec<Duration> = durations
+            .iter()
+            .copied()
+            .filter(|d| (low..=high).contains(d))
+            .collect();
+
+        let mean = parallel_duration_mean(&kept)?;
+        let median = parallel_duration_percentiles(&kept, &[50.0])[0];
+        let variance = if kept.len() > 1 {
+            kept.iter()
+                .map(|d| (d.as_secs_f64() - mean.as_secs_f64()).powi(2))
+                .sum::<f64>()
+                / (kept.len() - 1) as f64
+        } else {
+            0.0
+        };
+        let std_dev = Duration::from_secs_f64(variance.sqrt());
+        let margin = std_dev.mul_f64(Z_95 / (kept.len() as f64).sqrt());
+
+        Some(Summary {
+            count: kept.len(),
+            outliers: durations.len() - kept.len(),
+            mean,
+            median,
+            std_dev,
+            ci95: (mean.saturating_sub(margin), mean.saturating_add(margin)),
+        })
+    }
+}
+
+/// How much data a reduction processed per second.
+///
+/// Comparing `bytes_per_sec` with the machine's memory bandwidth shows whether a
+/// reduction is bandwidth-bound, in which case more threads will not make it faster.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Throughput {
+    /// Elements reduced per second.
+    pub elements_per_sec: f64,
+    /// Bytes of input read per second.
+    pub bytes_per_sec: f64,
+}
+
+impl Throughput {
+    /// Computes the throughput of reducing `elements` elements of `element_size` bytes in `time`.
+    ///
+    /// Both rates are infinite if `time` is zero.
+    pub fn of(elements: usize, element_size: usize, time: Duration) -> Throughput {
+        let secs = time.as_secs_f64();
+        let rate = |amount: usize| {
+            if secs == 0.0 {
+                f64::INFINITY
+            } else {
+                amount as f64 / secs
+            }
+        };
+        Throughput {
+            elements_per_sec: rate(elements),
+            bytes_per_sec: rate(elements.saturating_mul(element_size)),
+        }
+    }
+
+    /// Returns the byte rate in gigabytes (10^9 bytes) per second.
+    pub fn gb_per_sec(&self) -> f64 {
+        self.bytes_per_sec / 1e9
+    }
+}
+
+/// The timing of one chunk size in a [`sweep_chunk_sizes`] run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkSizeResult {
+    /// The chunk size that was measured.
+    pub chunk_size: usize,
+    /// Number of elements reduced per iteration.
+    pub elements: usize,
+    /// Size of one element in bytes.
+    pub element_size: usize,
+    /// Statistics of the parallel timings.
+    pub summary: Summary,
+}
+
+impl ChunkSizeResult {
+    /// Returns the throughput at the median time.
+    pub fn throughput(&self) -> Throughput {
+        Throughput::of(self.elements, self.element_size, self.summary.median)
+    }
+}
+
+/// The timing of one thread count in a [`sweep_threads`] run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThreadScalingResult {
+    /// The number of worker threads.
+    pub threads: usize,
+    /// Statistics of the parallel timings.
+    pub summary: Summary,
+    /// Median speedup over the first thread count in the sweep.
+    pub speedup: f64,
+    /// Parallel efficiency over the first thread count: speedup divided by the thread ratio.
+    pub efficiency: f64,
+    /// Parallel efficiency of this step over the previous thread count.
+    pub step_efficiency: f64,
+}
+
+/// The outcome of [`benchmark`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport<T> {
+    /// One sample per iteration, in order.
+    pub samples: Vec<Sample>,
+    /// Number of elements reduced per iteration.
+    pub elements: usize,
+    /// Size of one element in bytes.
+    pub element_size: usize,
+    /// The result of the parallel reduction.
+    pub parallel_result: T,
+    /// The result of the sequential fold.
+    pub sequential_result: T,
+}
+
+impl<T> BenchmarkReport<T> {
+    /// Returns the mean parallel time.
+    pub fn mean_parallel(&self) -> Duration {
+        mean(self.samples.iter().map(|s| s.parallel))
+    }
+
+    /// Returns the mean sequential time.
+    pub fn mean_sequential(&self) -> Duration {
+        mean(self.samples.iter().map(|s| s.sequential))
+    }
+
+    /// Returns the speedup of the mean parallel time over the mean sequential time.
+    pub fn speedup(&self) -> f64 {
+        speedup(self.mean_sequential(), self.mean_parallel())
+    }
+
+    /// Summarizes the parallel timings, or returns `None` if there are no samples.
+    pub fn parallel_summary(&self) -> Option<Summary> {
+        let times: Vec<Duration> = self.samples.iter().map(|s| s.parallel).collect();
+        Summary::of(&times)
+    }
+
+    /// Summarizes the sequential timings, or returns `None` if there are no samples.
+    pub fn sequential_summary(&self) -> Option<Summary> {
+        let times: Vec<Duration> = self.samples.iter().map(|s| s.sequential).collect();
+        Summary::of(&times)
+    }
+
+    /// Returns the throughput of the parallel reduction at the mean parallel time.
+    pub fn parallel_throughput(&self) -> Throughput {
+        Throughput::of(self.elements, self.element_size, self.mean_parallel())
+    }
+
+    /// Returns the throughput of the sequential fold at the mean sequential time.
+    pub fn sequential_throughput(&self) -> Throughput {
+        Throughput::of(self.elements, self.element_size, self.mean_sequential())
+    }
+
+    /// Returns the number of iterations in which the parallel run was faster.
+    pub fn parallel_wins(&self) -> usize {
+        self.samples
+            .iter()
+            .filter(|s| s.parallel < s.sequential)
+            .count()
+    }
+}
+
+/// Generates `size` elements by applying `generator` to the indices `1..=size`.
+pub fn generate_data<T>(size: usize, generator: impl Fn(usize) -> T) -> Vec<T> {
+    (1..=size).map(generator).collect()
+}
+
+/// Runs `work` once and measures its wall time, or zero on targets without a clock.
+pub fn time<R>(work: impl FnOnce() -> R) -> (R, Duration) {
+    let start = Stopwatch::start();
+    let result = work();
+    (result, start.elapsed())
+}
+
+/// Computes the speedup of `parallel` over `sequential`.
+///
+/// Returns infinity if the parallel time is zero.
+pub fn speedup(sequential: Duration, parallel: Duration) -> f64 {
+    if parallel.is_zero() {
+        f64::INFINITY
+    } else {
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    }
+}
+
+/// Times a parallel reduction against a sequential fold of the same data.
+///
+/// Runs [`DEFAULT_WARMUP`] untimed iterations first; see [`benchmark_with_warmup`].
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `iterations`: The number of timed iterations.
+///
+/// # Returns
+/// The per-iteration samples and the results of the last iteration.
+pub fn benchmark<T>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    iterations: u32,
+) -> BenchmarkReport<T>
+where
+    T: Copy + Send + Sync,
+{
+    benchmark_with_warmup(data, identity, operation, DEFAULT_WARMUP, iterations)
+}
+
+/// Times a parallel reduction against a sequential fold, after untimed warmup runs.
+///
+/// Both sides reduce the borrowed data in place, so only the reductions themselves are
+/// measured.
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `warmup`: The number of untimed iterations run first.
+/// - `iterations`: The number of timed iterations.
+///
+/// # Returns
+/// The per-iteration samples and the results of the last iteration.
+pub fn benchmark_with_warmup<T>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    warmup: u32,
+    iterations: u32,
+) -> BenchmarkReport<T>
+where
+    T: Copy + Send + Sync,
+{
+    for _ in 0..warmup {
+        reduce_slice_with_identity(data, identity, operation);
+        data.iter().copied().fold(identity, operation);
+    }
+
+    let mut samples = Vec::with_capacity(iterations as usize);
+    let mut parallel_result = identity;
+    let mut sequential_result = identity;
+
+    for _ in 0..iterations {
+        let (result, parallel) = time(|| reduce_slice_with_identity(data, identity, operation));
+        parallel_result = result;
+
+        let (result, sequential) = time(|| data.iter().copied().fold(identity, operation));
+        sequential_result = result;
+
+        samples.push(Sample {
+            parallel,
+            sequential,
+        });
+    }
+
+    BenchmarkReport {
+        samples,
+        elements: data.len(),
+        element_size: mem::size_of::<T>(),
+        parallel_result,
+        sequential_result,
+    }
+}
+
+/// Throughput results saved for later comparison, one per benchmark key.
+///
+/// Stored as a text file with one `key elements_per_sec` line per benchmark. Keys must
+/// not contain whitespace; include everything that affects the result (operation, type,
+/// size, threads) so different configurations are never compared with each other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineStore {
+    path: PathBuf,
+    entries: BTreeMap<String, f64>,
+}
+
+impl BaselineStore {
+    /// Opens the baseline file at `path`; a missing file is an empty store.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<BaselineStore> {
+        let path = path.as_ref().to_path_buf();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+
+        let mut entries = BTreeMap::new();
+        for (number, line) in text.lines().enumerate() {
+            let parsed = line
+                .split_once(' ')
+                .and_then(|(key, value)| Some((key, value.trim().parse::<f64>().ok()?)));
+            match parsed {
+                Some((key, value)) => entries.insert(key.to_string(), value),
+                None if line.trim().is_empty() => continue,
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid baseline line {}: {}", number + 1, line),
+                    ))
+                }
+            };
+        }
+        Ok(BaselineStore { path, entries })
+    }
+
+    /// Returns the saved throughput, in elements per second, for `key`.
+    pub fn get(&self, key: &str) -> Option<f64> {
+        self.entries.get(key).copied()
+    }
+
+    /// Records the throughput, in elements per second, for `key`.
+    pub fn insert(&mut self, key: impl Into<String>, elements_per_sec: f64) {
+        self.entries.insert(key.into(), elements_per_sec);
+    }
+
+    /// Writes the store back to its file, creating the directory if needed.
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text: String = self
+            .entries
+            .iter()
+            .map(|(key, value)| format!("{} {}\n", key, value))
+            .collect();
+        fs::write(&self.path, text)
+    }
+}
+
+/// Returns the relative change from `baseline` to `current` throughput.
+///
+/// `-0.1` means the current run is 10% slower than the baseline.
+pub fn throughput_change(baseline: f64, current: f64) -> f64 {
+    current / baseline - 1.0
+}
+
+/// Returns power-of-two chunk sizes from `min` up to `len`, plus `len` itself.
+///
+/// # Parameters
+/// - `len`: The number of elements that will be reduced.
+/// - `min`: The smallest chunk size to try, rounded up to a power of two.
+pub fn chunk_size_candidates(len: usize, min: usize) -> Vec<usize> {
+    let len = len.max(1);
+    let mut sizes: Vec<usize> =
+        std::iter::successors(Some(min.max(1).next_power_of_two()), |&size| {
+            size.checked_mul(2)
+        })
+        .take_while(|&size| size < len)
+        .collect();
+    sizes.push(len);
+    sizes
+}
+
+/// Times a parallel reduction with each of the given chunk sizes.
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `chunk_sizes`: The chunk sizes to measure.
+/// - `warmup`: The number of untimed iterations per chunk size.
+/// - `iterations`: The number of timed iterations per chunk size (at least one is run).
+///
+/// # Returns
+/// One result per chunk size, in the order given.
+pub fn sweep_chunk_sizes<T>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    chunk_sizes: &[usize],
+    warmup: u32,
+    iterations: u32,
+) -> Vec<ChunkSizeResult>
+where
+    T: Copy + Send + Sync,
+{
+    chunk_sizes
+        .iter()
+        .map(|&chunk_size| {
+            let chunk_size = chunk_size.max(1);
+            let run = || {
+                data.par_chunks(chunk_size)
+                    .map(|chunk| chunk.iter().copied().fold(identity, operation))
+                    .reduce(|| identity, operation)
+            };
+            for _ in 0..warmup {
+                run();
+            }
+            let times: Vec<Duration> = (0..iterations.max(1)).map(|_| time(run).1).collect();
+            ChunkSizeResult {
+                chunk_size,
+                elements: data.len(),
+                element_size: mem::size_of::<T>(),
+                summary: Summary::of(&times).expect("at least one iteration"),
+            }
+        })
+        .collect()
+}
+
+/// Returns the thread counts `1, 2, 4, ...` below `max`, followed by `max` itself.
+pub fn thread_counts(max: usize) -> Vec<usize> {
+    let max = max.max(1);
+    std::iter::successors(Some(1), |&n: &usize| n.checked_mul(2))
+        .take_while(|&n| n < max)
+        .chain([max])
+        .collect()
+}
+
+/// Times a parallel reduction on dedicated pools of each of the given sizes.
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `threads`: The thread counts to measure, usually ascending and starting at 1.
+/// - `warmup`: The number of untimed iterations per thread count.
+/// - `iterations`: The number of timed iterations per thread count (at least one is run).
+///
+/// # Returns
+/// One result per thread count for which a pool could be built, in the order given.
+pub fn sweep_threads<T>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    threads: &[usize],
+    warmup: u32,
+    iterations: u32,
+) -> Vec<ThreadScalingResult>
+where
+    T: Copy + Send + Sync,
+{
+    let mut results: Vec<ThreadScalingResult> = Vec::with_capacity(threads.len());
+    for &count in threads {
+        let pool = match rayon::ThreadPoolBuilder::new().num_threads(count).build() {
+            Ok(pool) => pool,
+            Err(_) => continue,
+        };
+        let report = pool.install(|| {
+            benchmark_with_warmup(data, identity, operation, warmup, iterations.max(1))
+        });
+        let summary = report.parallel_summary().expect("at least one iteration");
+
+        let efficiency_over = |base: &ThreadScalingResult| {
+            let speedup = speedup(base.summary.median, summary.median);
+            (speedup, speedup * base.threads as f64 / count as f64)
+        };
+        let (speedup, efficiency) = results.first().map_or((1.0, 1.0), efficiency_over);
+        let step_efficiency = results.last().map_or(1.0, |prev| efficiency_over(prev).1);
+
+        results.push(ThreadScalingResult {
+            threads: count,
+            summary,
+            speedup,
+            efficiency,
+            step_efficiency,
+        });
+    }
+    results
+}
+
+/// Picks the chunk size with the highest throughput from a sweep.
+///
+/// # Returns
+/// The recommended chunk size, or `None` for an empty sweep.
+pub fn recommend_chunk_size(results: &[ChunkSizeResult]) -> Option<usize> {
+    results
+        .iter()
+        .min_by_key(|result| result.summary.median)
+        .map(|result| result.chunk_size)
+}
+
+fn mean(durations: impl ExactSizeIterator<Item = Duration>) -> Duration {
+    let count = durations.len() as u32;
+    if count == 0 {
+        return Duration::ZERO;
+    }
+    durations.sum::<Duration>() / count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that the benchmark produces one sample per iteration and matching results
+    #[test]
+    fn test_benchmark_report() {
+        let data = generate_data(10_000, |i| i as u64);
+        let report = benchmark(&data, 0, |a, b| a + b, 3);
+        assert_eq!(report.samples.len(), 3);
+        assert_eq!(report.parallel_result, 50_005_000);
+        assert_eq!(report.parallel_result, report.sequential_result);
+        assert!(report.parallel_wins() <= 3);
+    }
+
+    // Test the speedup calculation
+    #[test]
+    fn test_speedup() {
+        let sample = Sample {
+            parallel: Duration::from_millis(25),
+            sequential: Duration::from_millis(100),
+        };
+        assert_eq!(sample.speedup(), 4.0);
+        assert_eq!(
+            speedup(Duration::from_millis(1), Duration::ZERO),
+            f64::INFINITY
+        );
+    }
+
+    // Test that the summary rejects outliers before computing statistics
+    #[test]
+    fn test_summary_rejects_outliers() {
+        let mut times: Vec<Duration> = [10, 11, 9, 10, 12, 10, 9, 11]
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect();
+        times.push(Duration::from_secs(5));
+
+        let summary = Summary::of(&times).unwrap();
+        assert_eq!(summary.outliers, 1);
+        assert_eq!(summary.count, 8);
+        assert_eq!(summary.mean, Duration::from_micros(10_250));
+        assert_eq!(summary.median, Duration::from_millis(10));
+        assert!(summary.ci95.0 < summary.mean && summary.mean < summary.ci95.1);
+        assert_eq!(Summary::of(&[]), None);
+    }
+
+    // Test the candidate chunk sizes and that a sweep reports every one of them
+    #[test]
+    fn test_sweep_chunk_sizes() {
+        assert_eq!(
+            chunk_size_candidates(1_000, 100),
+            vec![128, 256, 512, 1_000]
+        );
+        assert_eq!(chunk_size_candidates(10, 64), vec![10]);
+
+        let data = generate_data(1_000, |i| i as i32);
+        let results = sweep_chunk_sizes(&data, i32::MIN, i32::max, &[128, 1_000], 0, 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].chunk_size, 128);
+        assert_eq!(results[1].elements, 1_000);
+        assert_eq!(results[1].element_size, 4);
+        let best = recommend_chunk_size(&results).unwrap();
+        assert!(best == 128 || best == 1_000);
+    }
+
+    // Test the throughput calculation
+    #[test]
+    fn test_throughput() {
+        let throughput = Throughput::of(1_000_000, 8, Duration::from_millis(2));
+        assert_eq!(throughput.elements_per_sec, 500_000_000.0);
+        assert_eq!(throughput.gb_per_sec(), 4.0);
+        assert_eq!(
+            Throughput::of(1, 1, Duration::ZERO).bytes_per_sec,
+            f64::INFINITY
+        );
+    }
+
+    // Test the thread counts and that a thread sweep starts at unit efficiency
+    #[test]
+    fn test_sweep_threads() {
+        assert_eq!(thread_counts(6), vec![1, 2, 4, 6]);
+        assert_eq!(thread_counts(1), vec![1]);
+
+        let data = generate_data(10_000, |i| i as u64);
+        let results = sweep_threads(&data, 0, |a, b| a + b, &[1, 2], 0, 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].speedup, 1.0);
+        assert_eq!(results[0].efficiency, 1.0);
+        assert_eq!(results[1].threads, 2);
+        assert!(results[1].efficiency > 0.0);
+    }
+
+    // Test that baselines survive a save and reload
+    #[test]
+    fn test_baseline_store() {
+        let path = std::env::temp_dir().join(format!("baseline-{}", std::process::id()));
+        let mut store = BaselineStore::open(&path).unwrap();
+        assert_eq!(store.get("sum/i64/1000"), None);
+        store.insert("sum/i64/1000", 2.5e8);
+        store.save().unwrap();
+
+        let reloaded = BaselineStore::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reloaded.get("sum/i64/1000"), Some(2.5e8));
+        assert!((throughput_change(2.0e8, 1.8e8) + 0.1).abs() < 1e-12);
+    }
+}
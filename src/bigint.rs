@@ -0,0 +1,254 @@
+use rayon::prelude::*;
+use std::fmt;
+use std::ops::Mul;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// An arbitrary-precision unsigned integer, as produced by [`parallel_product_exact`].
+///
+/// Only what exact products need is implemented: multiplication, comparison, conversion
+/// from and to `u64`, and decimal formatting.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    /// Little-endian base-2^64 digits without trailing zeros; zero has none.
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    /// Returns the value if it fits in a `u64`.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.as_slice() {
+            [] => Some(0),
+            [limb] => Some(*limb),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of significant bits (0 for zero).
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() as u64 * 64 - u64::from(top.leading_zeros()),
+            None => 0,
+        }
+    }
+
+    /// Multiplies in place by a machine integer.
+    fn mul_u64(&mut self, factor: u64) {
+        if factor == 0 {
+            self.limbs.clear();
+            return;
+        }
+        let mut carry = 0u64;
+        for limb in &mut self.limbs {
+            let wide = u128::from(*limb) * u128::from(factor) + u128::from(carry);
+            *limb = wide as u64;
+            carry = (wide >> 64) as u64;
+        }
+        if carry != 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    /// Divides in place by a machine integer and returns the remainder.
+    fn div_rem_u64(&mut self, divisor: u64) -> u64 {
+        let mut rem = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let wide = (u128::from(rem) << 64) | u128::from(*limb);
+            *limb = (wide / u128::from(divisor)) as u64;
+            rem = (wide % u128::from(divisor)) as u64;
+        }
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        rem
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint {
+            limbs: if value == 0 { Vec::new() } else { vec![value] },
+        }
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    /// Schoolbook multiplication.
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.limbs.is_empty() || other.limbs.is_empty() {
+            return BigUint::default();
+        }
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let wide =
+                    u128::from(a) * u128::from(b) + u128::from(limbs[i + j]) + u128::from(carry);
+                limbs[i + j] = wide as u64;
+                carry = (wide >> 64) as u64;
+            }
+            limbs[i + other.limbs.len()] = carry;
+        }
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Peel off base-10^19 digits, the largest power of ten in a u64
+        const BASE: u64 = 10_000_000_000_000_000_000;
+        let mut rest = self.clone();
+        let mut digits = Vec::new();
+        while !rest.limbs.is_empty() {
+            digits.push(rest.div_rem_u64(BASE));
+        }
+        let mut text = match digits.pop() {
+            Some(top) => top.to_string(),
+            None => String::from("0"),
+        };
+        for digit in digits.iter().rev() {
+            text.push_str(&format!("{:019}", digit));
+        }
+        f.pad_integral(true, "", &text)
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// A partial product, kept in a machine integer for as long as it fits.
+enum Partial {
+    Small(u64),
+    Big(BigUint),
+}
+
+impl Partial {
+    fn mul_u64(self, factor: u64) -> Partial {
+        match self {
+            Partial::Small(acc) => match acc.checked_mul(factor) {
+                Some(product) => Partial::Small(product),
+                None => {
+                    let mut big = BigUint::from(acc);
+                    big.mul_u64(factor);
+                    Partial::Big(big)
+                }
+            },
+            Partial::Big(mut big) => {
+                big.mul_u64(factor);
+                Partial::Big(big)
+            }
+        }
+    }
+
+    fn mul(self, other: Partial) -> Partial {
+        match (self, other) {
+            (acc, Partial::Small(factor)) | (Partial::Small(factor), acc) => acc.mul_u64(factor),
+            (Partial::Big(a), Partial::Big(b)) => Partial::Big(&a * &b),
+        }
+    }
+
+    fn into_big(self) -> BigUint {
+        match self {
+            Partial::Small(value) => BigUint::from(value),
+            Partial::Big(big) => big,
+        }
+    }
+}
+
+/// Folds a chunk, multiplying a big partial only once per `u64` worth of factors.
+fn fold_chunk<T: Copy + Into<u64>>(chunk: &[T]) -> Partial {
+    let mut big: Option<BigUint> = None;
+    let mut small = 1u64;
+    for &x in chunk {
+        let x: u64 = x.into();
+        match small.checked_mul(x) {
+            Some(product) => small = product,
+            None => {
+                let big = big.get_or_insert_with(|| BigUint::from(1));
+                big.mul_u64(small);
+                small = x;
+            }
+        }
+    }
+    match big {
+        Some(big) => Partial::Big(big).mul_u64(small),
+        None => Partial::Small(small),
+    }
+}
+
+/// Computes the exact product of unsigned integers in parallel, growing past `u64` as needed.
+///
+/// Each chunk multiplies in machine integers until a checked multiplication overflows,
+/// and only then promotes its partial product to a [`BigUint`]; partials are combined
+/// the same way. Products that fit in a `u64` never allocate, and larger ones need no
+/// type chosen up front.
+///
+/// # Parameters
+/// - `data`: The factors.
+///
+/// # Returns
+/// The exact product; `1` for an empty slice.
+pub fn parallel_product_exact<T>(data: &[T]) -> BigUint
+where
+    T: Copy + Send + Sync + Into<u64>,
+{
+    if data.is_empty() {
+        return BigUint::from(1);
+    }
+    data.par_chunks(aligned_chunk_size(data, available_threads()))
+        .map(fold_chunk)
+        .reduce(|| Partial::Small(1), Partial::mul)
+        .into_big()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test a product that fits in a u64 and one that does not
+    #[test]
+    fn test_product_exact_factorials() {
+        let small: Vec<u64> = (1..=20).collect();
+        assert_eq!(
+            parallel_product_exact(&small).to_u64(),
+            Some(2_432_902_008_176_640_000)
+        );
+
+        let large: Vec<u32> = (1..=30).collect();
+        assert_eq!(
+            parallel_product_exact(&large).to_string(),
+            "265252859812191058636308480000000"
+        );
+    }
+
+    // Test a product spanning many limbs against repeated squaring
+    #[test]
+    fn test_product_exact_large() {
+        let data = vec![u64::MAX; 10_000];
+        let product = parallel_product_exact(&data);
+        assert_eq!(product.bits(), 640_000);
+        let mut expected = BigUint::from(u64::MAX);
+        for _ in 1..10_000 {
+            expected.mul_u64(u64::MAX);
+        }
+        assert_eq!(product, expected);
+    }
+
+    // Test the empty product and a zero factor
+    #[test]
+    fn test_product_exact_edge_cases() {
+        assert_eq!(parallel_product_exact::<u8>(&[]).to_u64(), Some(1));
+        let mut data = vec![u64::MAX; 100];
+        data[50] = 0;
+        assert_eq!(parallel_product_exact(&data).to_string(), "0");
+    }
+}
@@ -0,0 +1,162 @@
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::f64::consts::LN_2;
+use std::hash::{Hash, Hasher};
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// A Bloom filter: a set that may report false positives but never false negatives.
+///
+/// Build one from a slice with [`parallel_bloom_build`] and query many items at once
+/// with [`parallel_bloom_query`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    words: Vec<u64>,
+    bits: u64,
+    hashes: u32,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter sized to hold `items` items at a false-positive rate of `fpr`.
+    ///
+    /// # Panics
+    /// Panics if `fpr` is not strictly between 0 and 1.
+    pub fn new(items: usize, fpr: f64) -> Self {
+        assert!(
+            fpr > 0.0 && fpr < 1.0,
+            "false-positive rate must be between 0 and 1"
+        );
+        let n = items.max(1) as f64;
+        let bits = (-n * fpr.ln() / (LN_2 * LN_2)).ceil().max(64.0) as u64;
+        let hashes = ((bits as f64 / n) * LN_2).round().max(1.0) as u32;
+        BloomFilter {
+            words: vec![0; bits.div_ceil(64) as usize],
+            bits,
+            hashes,
+        }
+    }
+
+    /// Returns the number of bits in the filter.
+    pub fn num_bits(&self) -> u64 {
+        self.bits
+    }
+
+    /// Returns the number of bits set per item.
+    pub fn num_hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Adds an item.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        for bit in self.positions(item) {
+            self.words[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns `true` if the item may have been added, `false` if it certainly was not.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        self.positions(item)
+            .all(|bit| self.words[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    /// The bits an item maps to, by double hashing one 64-bit hash.
+    fn positions<T: Hash + ?Sized>(&self, item: &T) -> impl Iterator<Item = u64> {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+        let (h1, h2) = (hash, hash.rotate_left(32) | 1);
+        let bits = self.bits;
+        (0..u64::from(self.hashes)).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bits)
+    }
+
+    /// ORs another filter of the same shape into this one.
+    fn union(mut self, other: BloomFilter) -> BloomFilter {
+        for (word, other) in self.words.iter_mut().zip(other.words) {
+            *word |= other;
+        }
+        self
+    }
+}
+
+/// Builds a Bloom filter of `items` in parallel.
+///
+/// The filter is sized for the number of items and the requested false-positive rate.
+/// Each chunk sets bits in its own copy of the bit array, and the copies are ORed
+/// together, so threads never contend on shared words.
+///
+/// # Parameters
+/// - `items`: The items to add.
+/// - `fpr`: The target false-positive rate, e.g. `0.01`.
+///
+/// # Returns
+/// A filter that contains every item.
+///
+/// # Panics
+/// Panics if `fpr` is not strictly between 0 and 1.
+pub fn parallel_bloom_build<T>(items: &[T], fpr: f64) -> BloomFilter
+where
+    T: Hash + Sync,
+{
+    let empty = BloomFilter::new(items.len(), fpr);
+    items
+        .par_chunks(aligned_chunk_size(items, available_threads()))
+        .map(|chunk| {
+            let mut filter = empty.clone();
+            for item in chunk {
+                filter.insert(item);
+            }
+            filter
+        })
+        .reduce(|| empty.clone(), BloomFilter::union)
+}
+
+/// Checks many items against a Bloom filter in parallel.
+///
+/// # Parameters
+/// - `filter`: The filter to query.
+/// - `items`: The items to look up.
+///
+/// # Returns
+/// One result per item, in order, as from [`BloomFilter::contains`].
+pub fn parallel_bloom_query<T>(filter: &BloomFilter, items: &[T]) -> Vec<bool>
+where
+    T: Hash + Sync,
+{
+    items.par_iter().map(|item| filter.contains(item)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that every added item is found and the false-positive rate is near the target
+    #[test]
+    fn test_bloom_build_and_query() {
+        let items: Vec<u64> = (0..100_000).collect();
+        let filter = parallel_bloom_build(&items, 0.01);
+        assert!(parallel_bloom_query(&filter, &items)
+            .into_iter()
+            .all(|hit| hit));
+
+        let others: Vec<u64> = (100_000..200_000).collect();
+        let false_positives = parallel_bloom_query(&filter, &others)
+            .into_iter()
+            .filter(|&hit| hit)
+            .count();
+        assert!(false_positives < 2_000, "{}", false_positives);
+    }
+
+    // Test sizing and single-item use
+    #[test]
+    fn test_bloom_filter_sizing() {
+        let filter = BloomFilter::new(1_000, 0.01);
+        assert_eq!(filter.num_bits(), 9_586);
+        assert_eq!(filter.num_hashes(), 7);
+
+        let mut filter = parallel_bloom_build::<&str>(&[], 0.5);
+        assert!(!filter.contains("x"));
+        filter.insert("x");
+        assert!(filter.contains("x"));
+    }
+}
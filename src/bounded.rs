@@ -0,0 +1,34 @@
+/// Element types with a least and a greatest value, the identities of `max` and `min`.
+///
+/// Implemented for the primitive integers and floats; for floats the bounds are the
+/// infinities. Lets [`parallel_binary_operation`](crate::parallel_binary_operation)
+/// infer the identity of comparison operations instead of starting them from `0`.
+pub trait Bounded: Copy {
+    /// The least value, the identity for `max`.
+    const MIN_VALUE: Self;
+    /// The greatest value, the identity for `min`.
+    const MAX_VALUE: Self;
+}
+
+macro_rules! impl_bounded {
+    ($($t:ty),*) => {
+        $(
+            impl Bounded for $t {
+                const MIN_VALUE: Self = <$t>::MIN;
+                const MAX_VALUE: Self = <$t>::MAX;
+            }
+        )*
+    };
+}
+
+impl_bounded!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Bounded for f32 {
+    const MIN_VALUE: Self = f32::NEG_INFINITY;
+    const MAX_VALUE: Self = f32::INFINITY;
+}
+
+impl Bounded for f64 {
+    const MIN_VALUE: Self = f64::NEG_INFINITY;
+    const MAX_VALUE: Self = f64::INFINITY;
+}
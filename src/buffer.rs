@@ -0,0 +1,146 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::Mutex;
+
+/// Default number of idle buffers a [`BufferPool`] keeps.
+const DEFAULT_MAX_IDLE: usize = 16;
+
+/// Recycles the vectors reductions need for output and scratch space.
+///
+/// Install one with [`ParallelConfig::with_buffer_pool`](crate::ParallelConfig::with_buffer_pool)
+/// and the functions taking that configuration draw their buffers from the pool, so
+/// steady-state workloads stop going through the global allocator:
+/// - [`parallel_map_with_config`](crate::parallel_map_with_config) and
+///   [`parallel_zip_with_config`](crate::parallel_zip_with_config) build their output in a
+///   pooled vector;
+/// - [`parallel_sort_by_key_with_config`](crate::parallel_sort_by_key_with_config),
+///   [`parallel_sort_by_keys_with_config`](crate::parallel_sort_by_keys_with_config), and
+///   [`parallel_shuffle_with_config`](crate::parallel_shuffle_with_config) take their
+///   scratch space from the pool and return it when done;
+/// - [`parallel_binary_operation_with_config`](crate::parallel_binary_operation_with_config)
+///   collects iterator input that has to be seen as a slice into a pooled vector.
+///
+/// Other functions allocate as usual. Buffers of any element type can share one pool.
+/// Callers can also [`take`](BufferPool::take) and [`give`](BufferPool::give) buffers
+/// themselves, e.g. to hand back a vector a map returned.
+pub struct BufferPool {
+    idle: Mutex<Vec<Box<dyn Any + Send>>>,
+    max_idle: usize,
+}
+
+impl BufferPool {
+    /// Creates an empty pool that keeps up to 16 idle buffers.
+    pub fn new() -> Self {
+        Self::with_max_idle(DEFAULT_MAX_IDLE)
+    }
+
+    /// Creates an empty pool that keeps up to `max_idle` idle buffers.
+    pub fn with_max_idle(max_idle: usize) -> Self {
+        BufferPool {
+            idle: Mutex::new(Vec::new()),
+            max_idle,
+        }
+    }
+
+    /// Takes an empty buffer with room for at least `capacity` elements.
+    ///
+    /// The smallest idle `Vec<T>` that is large enough is reused; if there is none, a new
+    /// one is allocated.
+    pub fn take<T: Send + 'static>(&self, capacity: usize) -> Vec<T> {
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        let best = idle
+            .iter()
+            .enumerate()
+            .filter_map(|(index, buffer)| {
+                buffer
+                    .downcast_ref::<Vec<T>>()
+                    .filter(|buffer| buffer.capacity() >= capacity)
+                    .map(|buffer| (buffer.capacity(), index))
+            })
+            .min();
+        match best {
+            Some((_, index)) => *idle
+                .swap_remove(index)
+                .downcast::<Vec<T>>()
+                .expect("the buffer was checked to be a Vec<T>"),
+            None => Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns a buffer to the pool for reuse, dropping its elements.
+    ///
+    /// If the pool is full, the buffer is freed instead.
+    pub fn give<T: Send + 'static>(&self, mut buffer: Vec<T>) {
+        buffer.clear();
+        if buffer.capacity() == 0 {
+            return;
+        }
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.len() < self.max_idle {
+            idle.push(Box::new(buffer));
+        }
+    }
+
+    /// Returns the number of idle buffers in the pool.
+    pub fn idle(&self) -> usize {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for BufferPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferPool")
+            .field("idle", &self.idle())
+            .field("max_idle", &self.max_idle)
+            .finish()
+    }
+}
+
+/// Pools are compared by identity: two configurations are equal only if they share a pool.
+impl PartialEq for BufferPool {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that a returned buffer is reused for a request it can hold
+    #[test]
+    fn test_buffer_reuse() {
+        let pool = BufferPool::new();
+        let mut buffer: Vec<u64> = pool.take(1_000);
+        buffer.extend(0..1_000);
+        let address = buffer.as_ptr();
+        pool.give(buffer);
+        assert_eq!(pool.idle(), 1);
+
+        let again: Vec<u64> = pool.take(500);
+        assert!(again.is_empty());
+        assert_eq!(again.as_ptr(), address);
+        assert_eq!(pool.idle(), 0);
+    }
+
+    // Test that buffers of other types or too little capacity are not handed out
+    #[test]
+    fn test_buffer_type_and_capacity() {
+        let pool = BufferPool::with_max_idle(1);
+        pool.give(Vec::<u32>::with_capacity(10));
+        pool.give(Vec::<u32>::with_capacity(10));
+        assert_eq!(pool.idle(), 1);
+
+        let other: Vec<f32> = pool.take(4);
+        assert!(other.capacity() >= 4);
+        let larger: Vec<u32> = pool.take(100);
+        assert!(larger.capacity() >= 100);
+        assert_eq!(pool.idle(), 1);
+    }
+}
@@ -0,0 +1,167 @@
+use rayon::prelude::*;
+use std::ops::Range;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// A reduction that keeps one partial result per chunk of its data.
+///
+/// After the data is modified through [`data_mut`](CachedReducer::data_mut), passing the
+/// changed index ranges to [`refresh`](CachedReducer::refresh) refolds only the chunks
+/// they touch and re-combines the partials, instead of reducing the whole vector again.
+pub struct CachedReducer<T> {
+    data: Vec<T>,
+    operation: fn(T, T) -> T,
+    chunk_size: usize,
+    partials: Vec<T>,
+    result: Option<T>,
+}
+
+impl<T> CachedReducer<T>
+where
+    T: Copy + Send + Sync,
+{
+    /// Reduces `data` and caches the per-chunk partial results.
+    ///
+    /// The data is split into one chunk per available core.
+    pub fn new(data: Vec<T>, operation: fn(T, T) -> T) -> Self {
+        let chunk_size = aligned_chunk_size(&data, available_threads());
+        CachedReducer::with_chunk_size(data, operation, chunk_size)
+    }
+
+    /// Reduces `data` with a fixed chunk size and caches the per-chunk partial results.
+    ///
+    /// Smaller chunks make each refresh cheaper at the cost of more partials to combine.
+    pub fn with_chunk_size(data: Vec<T>, operation: fn(T, T) -> T, chunk_size: usize) -> Self {
+        let chunk_size = chunk_size.max(1);
+        let partials: Vec<T> = data
+            .par_chunks(chunk_size)
+            .map(|chunk| fold_chunk(chunk, operation))
+            .collect();
+        let result = partials.iter().copied().reduce(operation);
+        CachedReducer {
+            data,
+            operation,
+            chunk_size,
+            partials,
+            result,
+        }
+    }
+
+    /// Returns the cached result, or `None` if the data is empty.
+    pub fn result(&self) -> Option<T> {
+        self.result
+    }
+
+    /// Returns the underlying data.
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Returns the underlying data for modification.
+    ///
+    /// The cached result is stale until the changed ranges are passed to
+    /// [`refresh`](CachedReducer::refresh).
+    pub fn data_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// Recomputes the chunks overlapping `changed` and re-combines all partials.
+    ///
+    /// # Parameters
+    /// - `changed`: The index ranges of the data that were modified.
+    ///
+    /// # Returns
+    /// The updated result, or `None` if the data is empty.
+    ///
+    /// # Panics
+    /// Panics if a range extends past the end of the data.
+    pub fn refresh(&mut self, changed: &[Range<usize>]) -> Option<T> {
+        let mut dirty: Vec<usize> = Vec::new();
+        for range in changed {
+            assert!(
+                range.end <= self.data.len(),
+                "changed range {:?} is out of bounds for length {}",
+                range,
+                self.data.len()
+            );
+            if range.is_empty() {
+                continue;
+            }
+            dirty.extend(range.start / self.chunk_size..=(range.end - 1) / self.chunk_size);
+        }
+        dirty.sort_unstable();
+        dirty.dedup();
+
+        let (data, chunk_size, operation) = (&self.data, self.chunk_size, self.operation);
+        let updated: Vec<(usize, T)> = dirty
+            .into_par_iter()
+            .map(|index| {
+                let start = index * chunk_size;
+                let end = (start + chunk_size).min(data.len());
+                (index, fold_chunk(&data[start..end], operation))
+            })
+            .collect();
+        for (index, partial) in updated {
+            self.partials[index] = partial;
+        }
+
+        self.result = self.partials.iter().copied().reduce(self.operation);
+        self.result
+    }
+
+    /// Consumes the reducer and returns the underlying data.
+    pub fn into_inner(self) -> Vec<T> {
+        self.data
+    }
+}
+
+/// Folds a non-empty chunk starting from its first element.
+fn fold_chunk<T: Copy>(chunk: &[T], operation: fn(T, T) -> T) -> T {
+    let (first, rest) = chunk.split_first().expect("chunks are never empty");
+    rest.iter().copied().fold(*first, operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that refreshing changed ranges matches a full reduction
+    #[test]
+    fn test_cached_refresh() {
+        let mut reducer =
+            CachedReducer::with_chunk_size((1..=1_000).collect(), |a: i64, b| a + b, 64);
+        assert_eq!(reducer.result(), Some(500_500));
+
+        reducer.data_mut()[0] = 0;
+        for value in &mut reducer.data_mut()[500..700] {
+            *value = 1;
+        }
+        let expected: i64 = reducer.data().iter().sum();
+        assert_eq!(reducer.refresh(&[0..1, 500..700, 10..10]), Some(expected));
+        assert_eq!(reducer.result(), Some(expected));
+    }
+
+    // Test a non-commutative operation keeps its order across refreshes
+    #[test]
+    fn test_cached_order() {
+        // (digits, 10^len) pairs concatenate like decimal strings
+        type Digits = (u64, u64);
+        let data: Vec<Digits> = (0..50).map(|i| (i % 10, 10)).collect();
+        let concat: fn(Digits, Digits) -> Digits =
+            |(a, pa), (b, pb)| ((a * pb + b) % 1_000_003, (pa * pb) % 1_000_003);
+        let mut reducer = CachedReducer::with_chunk_size(data, concat, 8);
+        reducer.data_mut()[17] = (3, 10);
+        reducer.data_mut()[40] = (7, 10);
+        let expected = reducer.data().iter().copied().reduce(concat);
+        assert_eq!(reducer.refresh(&[17..18, 40..41]), expected);
+    }
+
+    // Test an empty reducer
+    #[test]
+    fn test_cached_empty() {
+        let mut reducer = CachedReducer::new(Vec::new(), i32::max);
+        assert_eq!(reducer.result(), None);
+        assert_eq!(reducer.refresh(&[]), None);
+    }
+}
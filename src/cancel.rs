@@ -0,0 +1,215 @@
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::error::catch_panic;
+use crate::platform::Stopwatch;
+use crate::Error;
+
+/// Number of elements folded between two checks for cancellation or timeout.
+const CHECK_INTERVAL: usize = 16 * 1024;
+
+/// Performs a parallel binary operation that stops early when `cancel` is set.
+///
+/// The flag is checked before every block of elements, so a cancelled reduction returns
+/// soon after the flag is set instead of after the whole input.
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `cancel`: Set from any thread to cancel the reduction.
+///
+/// # Returns
+/// The result of applying the operation to all elements, [`Error::Cancelled`] if the
+/// flag was set before the reduction finished, or [`Error::OperationPanicked`].
+pub fn try_parallel_reduce_cancellable<T>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    cancel: &AtomicBool,
+) -> Result<T, Error>
+where
+    T: Copy + Send + Sync,
+{
+    reduce_checked(data, identity, operation, || {
+        if cancel.load(Ordering::Relaxed) {
+            Err(Error::Cancelled)
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// Performs a parallel binary operation that gives up after `timeout`.
+///
+/// On targets without a clock (WebAssembly without `std::time`) the timeout never fires.
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `timeout`: How long the reduction may run.
+///
+/// # Returns
+/// The result of applying the operation to all elements, [`Error::TimedOut`] if the
+/// timeout passed before the reduction finished, or [`Error::OperationPanicked`].
+pub fn try_parallel_reduce_with_timeout<T>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    timeout: Duration,
+) -> Result<T, Error>
+where
+    T: Copy + Send + Sync,
+{
+    let stopwatch = Stopwatch::start();
+    reduce_checked(data, identity, operation, || {
+        if stopwatch.elapsed() >= timeout {
+            Err(Error::TimedOut)
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// The result of a reduction that may have stopped at its deadline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PartialResult<T> {
+    /// The combination of every block that finished, in input order.
+    pub value: T,
+    /// The share of the elements that were folded into `value`, from 0.0 to 1.0.
+    pub completed_fraction: f64,
+}
+
+impl<T> PartialResult<T> {
+    /// Returns `true` if every element was folded into the value.
+    pub fn is_complete(&self) -> bool {
+        self.completed_fraction >= 1.0
+    }
+}
+
+/// Performs a parallel binary operation that returns whatever it has at the deadline.
+///
+/// Blocks of elements that start after `deadline` has passed are skipped; the blocks
+/// that finished are combined in input order. This gives approximate answers for uses
+/// such as progressive dashboards, which can scale or refine them later. On targets
+/// without a clock the deadline never passes.
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `deadline`: How long after the call blocks may still be started.
+///
+/// # Returns
+/// The combined value of the finished blocks and the fraction of the elements they
+/// cover (1.0 for an empty slice).
+pub fn parallel_reduce_with_deadline<T>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    deadline: Duration,
+) -> PartialResult<T>
+where
+    T: Copy + Send + Sync,
+{
+    let stopwatch = Stopwatch::start();
+    let (value, completed) = data
+        .par_chunks(CHECK_INTERVAL)
+        .map(|block| {
+            if stopwatch.elapsed() >= deadline {
+                (identity, 0)
+            } else {
+                (block.iter().copied().fold(identity, operation), block.len())
+            }
+        })
+        .reduce(
+            || (identity, 0),
+            |(a, a_len), (b, b_len)| (operation(a, b), a_len + b_len),
+        );
+
+    let completed_fraction = if data.is_empty() {
+        1.0
+    } else {
+        completed as f64 / data.len() as f64
+    };
+    PartialResult {
+        value,
+        completed_fraction,
+    }
+}
+
+/// Folds `data` in blocks, calling `check` before every block and stopping at its error.
+fn reduce_checked<T, C>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    check: C,
+) -> Result<T, Error>
+where
+    T: Copy + Send + Sync,
+    C: Fn() -> Result<(), Error> + Sync,
+{
+    let result = catch_panic(|| {
+        data.par_chunks(CHECK_INTERVAL)
+            .map(|block| {
+                check()?;
+                Ok(block.iter().copied().fold(identity, operation))
+            })
+            .try_reduce(|| identity, |a, b| Ok(operation(a, b)))
+    });
+    result?.and_then(|value| check().map(|()| value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that an unset flag and a generous timeout do not interfere
+    #[test]
+    fn test_uninterrupted() {
+        let data: Vec<i64> = (1..=100_000).collect();
+        let cancel = AtomicBool::new(false);
+        let sum = |a, b| a + b;
+        assert_eq!(
+            try_parallel_reduce_cancellable(&data, 0, sum, &cancel),
+            Ok(5_000_050_000)
+        );
+        let timeout = Duration::from_secs(60);
+        assert_eq!(
+            try_parallel_reduce_with_timeout(&data, 0, sum, timeout),
+            Ok(5_000_050_000)
+        );
+    }
+
+    // Test that a set flag and an elapsed timeout stop the reduction
+    #[test]
+    fn test_interrupted() {
+        let data: Vec<i64> = (1..=100_000).collect();
+        let cancel = AtomicBool::new(true);
+        assert_eq!(
+            try_parallel_reduce_cancellable(&data, 0, |a, b| a + b, &cancel),
+            Err(Error::Cancelled)
+        );
+        let result = try_parallel_reduce_with_timeout(&data, 0, |a, b| a + b, Duration::ZERO);
+        assert_eq!(result, Err(Error::TimedOut));
+    }
+
+    // Test partial results before and after the deadline
+    #[test]
+    fn test_deadline_partial() {
+        let data = vec![1u64; 100_000];
+        let full = parallel_reduce_with_deadline(&data, 0, |a, b| a + b, Duration::from_secs(60));
+        assert!(full.is_complete());
+        assert_eq!(full.value, 100_000);
+
+        let none = parallel_reduce_with_deadline(&data, 0, |a, b| a + b, Duration::ZERO);
+        assert_eq!(none.completed_fraction, 0.0);
+        assert_eq!(none.value, 0);
+        assert!(
+            parallel_reduce_with_deadline(&[] as &[u64], 0, |a, b| a + b, Duration::ZERO)
+                .is_complete()
+        );
+    }
+}
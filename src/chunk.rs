@@ -0,0 +1,75 @@
+use rayon::prelude::*;
+
+use crate::error::catch_panic;
+use crate::platform::available_threads;
+use crate::{aligned_chunk_size, get_initial_value, probe_identity, Bounded, Error};
+
+/// Reduces each chunk of the data in parallel without combining the chunk results.
+///
+/// The partial results can be combined later with the same operation, possibly on a
+/// different machine (see [`distributed`](crate::distributed)).
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// One partial result per chunk, in data order. Empty for an empty vector.
+pub fn parallel_chunk_reduce<T>(data: Vec<T>, operation: fn(T, T) -> T) -> Vec<T>
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+{
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let initial = get_initial_value(operation);
+    let chunk_size = aligned_chunk_size(&data, available_threads());
+    data.par_chunks(chunk_size)
+        .map(|chunk| chunk.iter().copied().fold(initial, operation))
+        .collect()
+}
+
+/// Reduces each chunk of the data in parallel without combining the chunk results,
+/// reporting failures as errors.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// One partial result per chunk, in data order (empty for an empty vector),
+/// [`Error::OperationPanicked`], or [`Error::UnknownIdentity`].
+pub fn try_parallel_chunk_reduce<T>(data: Vec<T>, operation: fn(T, T) -> T) -> Result<Vec<T>, Error>
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+{
+    if probe_identity(operation).is_none() {
+        return Err(Error::UnknownIdentity);
+    }
+    catch_panic(|| parallel_chunk_reduce(data, operation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that combining the partials gives the full result
+    #[test]
+    fn test_chunk_reduce_partials() {
+        let data: Vec<i64> = (1..=1_000).collect();
+        let partials = parallel_chunk_reduce(data, |a, b| a + b);
+        assert!(!partials.is_empty());
+        assert_eq!(partials.iter().sum::<i64>(), 500_500);
+    }
+
+    // Test for empty vector
+    #[test]
+    fn test_chunk_reduce_empty() {
+        assert!(parallel_chunk_reduce(Vec::<i32>::new(), |a, b| a + b).is_empty());
+        assert_eq!(
+            try_parallel_chunk_reduce(vec![1, 2, 3], |a, b| a + b + 1),
+            Err(Error::UnknownIdentity)
+        );
+    }
+}
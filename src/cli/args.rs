@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use super::CliError;
+
+/// Minimal `--name value` / positional argument parser for the subcommands.
+pub struct Args {
+    options: HashMap<String, String>,
+    positionals: Vec<String>,
+}
+
+impl Args {
+    /// Splits `args` into `--name value` options and positionals.
+    ///
+    /// Fails if an option has no value or is given more than once.
+    pub fn parse(args: &[String]) -> Result<Args, CliError> {
+        let mut options = HashMap::new();
+        let mut positionals = Vec::new();
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.strip_prefix("--") {
+                Some(name) => {
+                    let value = iter
+                        .next()
+                        .ok_or_else(|| CliError::Usage(format!("--{} needs a value", name)))?;
+                    if options.insert(name.to_string(), value.clone()).is_some() {
+                        return Err(CliError::Usage(format!("--{} given more than once", name)));
+                    }
+                }
+                None => positionals.push(arg.clone()),
+            }
+        }
+        Ok(Args {
+            options,
+            positionals,
+        })
+    }
+
+    /// Returns the raw value of `--name`, if given.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    /// Parses the value of `--name`, falling back to `default` when it is absent.
+    pub fn parse_or<T: FromStr>(&self, name: &str, default: T) -> Result<T, CliError> {
+        match self.value(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| CliError::Usage(format!("invalid value for --{}: {}", name, value))),
+            None => Ok(default),
+        }
+    }
+
+    /// Parses the comma-separated values of `--name`, falling back to `default` when it is absent.
+    pub fn parse_list_or<T: FromStr>(
+        &self,
+        name: &str,
+        default: Vec<T>,
+    ) -> Result<Vec<T>, CliError> {
+        match self.value(name) {
+            Some(value) => value
+                .split(',')
+                .map(|item| {
+                    item.trim().parse().map_err(|_| {
+                        CliError::Usage(format!("invalid value for --{}: {}", name, item))
+                    })
+                })
+                .collect(),
+            None => Ok(default),
+        }
+    }
+
+    /// Returns the positional arguments.
+    pub fn positionals(&self) -> &[String] {
+        &self.positionals
+    }
+
+    /// Fails if any option outside `known` was given.
+    pub fn reject_unknown(&self, known: &[&str]) -> Result<(), CliError> {
+        match self
+            .options
+            .keys()
+            .find(|name| !known.contains(&name.as_str()))
+        {
+            Some(name) => Err(CliError::Usage(format!("unknown option: --{}", name))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    // Test splitting options from positionals
+    #[test]
+    fn test_parse() {
+        let args = Args::parse(&strings(&["--op", "sum", "input.txt"])).unwrap();
+        assert_eq!(args.value("op"), Some("sum"));
+        assert_eq!(args.value("type"), None);
+        assert_eq!(args.positionals(), ["input.txt"]);
+    }
+
+    // Test that a trailing option without a value is a usage error
+    #[test]
+    fn test_parse_missing_value() {
+        assert_eq!(
+            Args::parse(&strings(&["--size", "10", "--x"])).err(),
+            Some(CliError::usage("--x needs a value"))
+        );
+    }
+
+    // Test that repeating an option is a usage error instead of keeping the last value
+    #[test]
+    fn test_parse_repeated_option() {
+        assert_eq!(
+            Args::parse(&strings(&["--size", "10", "--size", "20"])).err(),
+            Some(CliError::usage("--size given more than once"))
+        );
+    }
+
+    // Test rejecting options a command does not know
+    #[test]
+    fn test_reject_unknown() {
+        let args = Args::parse(&strings(&["--op", "sum", "--sise", "10"])).unwrap();
+        assert_eq!(args.reject_unknown(&["op", "sise"]), Ok(()));
+        assert_eq!(
+            args.reject_unknown(&["op", "size"]),
+            Err(CliError::usage("unknown option: --sise"))
+        );
+    }
+
+    // Test parsing single values and lists, with defaults and bad items
+    #[test]
+    fn test_parse_values() {
+        let args = Args::parse(&strings(&["--size", "x", "--threads", "1, 2,z"])).unwrap();
+        assert_eq!(args.parse_or("iterations", 10u32), Ok(10));
+        assert_eq!(
+            args.parse_or("size", 0usize),
+            Err(CliError::usage("invalid value for --size: x"))
+        );
+        assert_eq!(args.parse_list_or("sizes", vec![5usize]), Ok(vec![5]));
+        assert_eq!(
+            args.parse_list_or("threads", Vec::<usize>::new()),
+            Err(CliError::usage("invalid value for --threads: z"))
+        );
+    }
+}
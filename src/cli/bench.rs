@@ -0,0 +1,165 @@
+use std::env;
+use std::path::PathBuf;
+
+use parallel_operations::bench::{
+    benchmark_with_warmup, generate_data, throughput_change, BaselineStore, BenchmarkReport,
+    Throughput, DEFAULT_WARMUP,
+};
+
+use super::report::{self, Format, RunInfo};
+use super::{in_pool, Args, CliError, CliNumber, NumberType, Op};
+
+/// Settings for one benchmark run.
+struct BenchOptions {
+    op: Op,
+    number_type: NumberType,
+    size: usize,
+    warmup: u32,
+    iterations: u32,
+    threads: Option<usize>,
+    format: Format,
+    save_baseline: Option<String>,
+    compare_baseline: Option<String>,
+    threshold: f64,
+}
+
+/// Environment variable overriding where baselines are stored.
+const BASELINE_DIR_ENV: &str = "PARALLEL_OPERATIONS_BASELINE_DIR";
+
+/// `bench`: times the parallel reduction against a sequential fold.
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let args = Args::parse(args)?;
+    args.reject_unknown(&[
+        "op",
+        "type",
+        "size",
+        "warmup",
+        "iterations",
+        "threads",
+        "format",
+        "save-baseline",
+        "compare-baseline",
+        "threshold",
+    ])?;
+    if let Some(extra) = args.positionals().first() {
+        return Err(CliError::Usage(format!("unexpected argument: {}", extra)));
+    }
+
+    let number_type: NumberType = args.parse_or("type", NumberType::I64)?;
+    let options = BenchOptions {
+        op: args.parse_or("op", Op::Sum)?,
+        number_type,
+        size: args.parse_or("size", 10_000)?,
+        warmup: args.parse_or("warmup", DEFAULT_WARMUP)?,
+        iterations: args.parse_or("iterations", 10)?,
+        threads: args
+            .value("threads")
+            .map(str::parse)
+            .transpose()
+            .map_err(|_| CliError::usage("invalid value for --threads"))?,
+        format: args.parse_or("format", Format::Text)?,
+        save_baseline: args.value("save-baseline").map(String::from),
+        compare_baseline: args.value("compare-baseline").map(String::from),
+        threshold: args.parse_or("threshold", 0.1)?,
+    };
+    if options.iterations == 0 {
+        return Err(CliError::usage("--iterations must be at least 1"));
+    }
+
+    Ok(in_pool(options.threads, || dispatch(&options))??)
+}
+
+fn dispatch(options: &BenchOptions) -> Result<(), String> {
+    match options.number_type {
+        NumberType::I64 => bench::<i64>(options),
+        NumberType::I128 => bench::<i128>(options),
+        NumberType::F64 => bench::<f64>(options),
+    }
+}
+
+fn bench<T: CliNumber>(options: &BenchOptions) -> Result<(), String> {
+    let (identity, operation) = T::operation(options.op);
+    let data = generate_data(options.size, T::from_index);
+    let report = benchmark_with_warmup(
+        &data,
+        identity,
+        operation,
+        options.warmup,
+        options.iterations,
+    );
+
+    if !T::approx_eq(report.parallel_result, report.sequential_result) {
+        return Err(format!(
+            "the results do not match: parallel {} vs sequential {}",
+            report.parallel_result, report.sequential_result
+        ));
+    }
+
+    let info = RunInfo {
+        op: options.op,
+        number_type: options.number_type,
+        size: options.size,
+        threads: rayon::current_num_threads(),
+    };
+    report::print(options.format, &info, &report).map_err(|err| err.to_string())?;
+
+    let key = format!(
+        "{}/{}/{}/{}t",
+        info.op, info.number_type, info.size, info.threads
+    );
+    baselines(options, &key, &report)
+}
+
+/// Saves the run as a baseline and/or compares it against one.
+///
+/// Messages go to stderr so that CSV and JSON output stay parseable.
+fn baselines<T>(
+    options: &BenchOptions,
+    key: &str,
+    report: &BenchmarkReport<T>,
+) -> Result<(), String> {
+    let current = match report.parallel_summary() {
+        Some(summary) => {
+            Throughput::of(report.elements, report.element_size, summary.median).elements_per_sec
+        }
+        None => return Ok(()),
+    };
+
+    if let Some(name) = &options.compare_baseline {
+        let store = BaselineStore::open(baseline_path(name)).map_err(|err| err.to_string())?;
+        let baseline = store
+            .get(key)
+            .ok_or_else(|| format!("baseline `{}` has no result for {}", name, key))?;
+        let change = throughput_change(baseline, current);
+        eprintln!(
+            "{}: {:.0} elements/s vs. baseline `{}` {:.0} elements/s ({:+.1}%)",
+            key,
+            current,
+            name,
+            baseline,
+            change * 100.0
+        );
+        if change < -options.threshold {
+            return Err(format!(
+                "throughput regressed by {:.1}%, more than the allowed {:.1}%",
+                -change * 100.0,
+                options.threshold * 100.0
+            ));
+        }
+    }
+
+    if let Some(name) = &options.save_baseline {
+        let path = baseline_path(name);
+        let mut store = BaselineStore::open(&path).map_err(|err| err.to_string())?;
+        store.insert(key, current);
+        store.save().map_err(|err| err.to_string())?;
+        eprintln!("Saved {} to baseline `{}` ({})", key, name, path.display());
+    }
+    Ok(())
+}
+
+fn baseline_path(name: &str) -> PathBuf {
+    let dir = env::var_os(BASELINE_DIR_ENV)
+        .map_or_else(|| PathBuf::from("target").join("baselines"), PathBuf::from);
+    dir.join(format!("{}.baseline", name))
+}
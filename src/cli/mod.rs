@@ -0,0 +1,330 @@
+//! Command line interface: argument parsing and the `bench` and `run` subcommands.
+
+mod args;
+mod bench;
+#[cfg(feature = "plots")]
+mod plot;
+mod report;
+mod run;
+mod scale;
+mod selftest;
+mod tune;
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+pub use args::Args;
+
+pub const USAGE: &str = "\
+usage: parallel_operations <command> [options]
+
+commands:
+  bench   time a parallel reduction against a sequential one
+          --op OP  --type TYPE  --size N (10000)  --warmup N (3)  --iterations N (10)  --threads N
+          --format FORMAT (text)  --save-baseline NAME  --compare-baseline NAME
+          --threshold FRACTION (0.1)
+  run     reduce newline-delimited numbers from a file, or stdin with `-`
+          --op OP  --type TYPE  --batch-size N (65536)  FILE|-
+  scale   run a reduction at 1, 2, 4, ... threads and report parallel efficiency
+          --op OP  --type TYPE  --size N (10000000)  --threads N,...  --iterations N (10)
+  selftest
+          compare every built-in reduction with a sequential fold on random inputs
+          --seed N  --rounds N (1)
+  tune chunk-size
+          sweep chunk sizes and recommend the fastest
+          --op OP  --type TYPE  --size N (10000000)  --min-chunk N (1024)
+          --iterations N (10)  --threads N
+  tune profile
+          benchmark this machine and save the tuning profile reductions load
+          --out FILE (default: $PARALLEL_OPERATIONS_PROFILE or ~/.config)
+  plot    write speedup-vs-size and speedup-vs-threads SVG charts
+          (requires the `plots` feature)
+          --op OP,...  --type TYPE  --sizes N,...  --threads N,...  --iterations N (10)
+          --out DIR (.)
+  help    print this message
+
+OP is one of sum, product, min, max; TYPE is one of i64 (default), i128, f64;
+FORMAT is one of text, csv, json.
+`parallel_operations --op OP ... -` is shorthand for `run`.";
+
+/// Why a command failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    /// The arguments are invalid; worth showing [`USAGE`] after the message.
+    Usage(String),
+    /// The command ran and failed (I/O, a mismatch, a regression, ...).
+    Failed(String),
+}
+
+impl CliError {
+    /// Returns a [`CliError::Usage`] with the given message.
+    pub fn usage(message: &str) -> Self {
+        CliError::Usage(String::from(message))
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) | CliError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::Failed(message)
+    }
+}
+
+/// Runs the command line with the given arguments (without the program name).
+pub fn run(args: Vec<String>) -> Result<(), CliError> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => ("help", &[][..]),
+    };
+    match command {
+        "bench" => bench::run(rest),
+        "run" => run::run(rest),
+        "scale" => scale::run(rest),
+        "selftest" => selftest::run(rest),
+        "tune" => tune::run(rest),
+        #[cfg(feature = "plots")]
+        "plot" => plot::run(rest),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ if command.starts_with("--") => run::run(&args),
+        other => Err(CliError::Usage(format!("unknown command: {}", other))),
+    }
+}
+
+/// Runs `work` on a dedicated pool of `threads` threads, or on the global pool.
+pub fn in_pool<R: Send>(
+    threads: Option<usize>,
+    work: impl FnOnce() -> R + Send,
+) -> Result<R, String> {
+    match threads {
+        Some(threads) => {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(|err| err.to_string())?;
+            Ok(pool.install(work))
+        }
+        None => Ok(work()),
+    }
+}
+
+/// The reductions available from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Sum,
+    Product,
+    Min,
+    Max,
+}
+
+impl FromStr for Op {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Op, String> {
+        match name {
+            "sum" => Ok(Op::Sum),
+            "product" => Ok(Op::Product),
+            "min" => Ok(Op::Min),
+            "max" => Ok(Op::Max),
+            other => Err(format!("unknown operation: {}", other)),
+        }
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Op::Sum => "sum",
+            Op::Product => "product",
+            Op::Min => "min",
+            Op::Max => "max",
+        })
+    }
+}
+
+/// The element types available from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberType {
+    I64,
+    I128,
+    F64,
+}
+
+impl FromStr for NumberType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<NumberType, String> {
+        match name {
+            "i64" => Ok(NumberType::I64),
+            "i128" => Ok(NumberType::I128),
+            "f64" => Ok(NumberType::F64),
+            other => Err(format!("unknown type: {}", other)),
+        }
+    }
+}
+
+impl Display for NumberType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            NumberType::I64 => "i64",
+            NumberType::I128 => "i128",
+            NumberType::F64 => "f64",
+        })
+    }
+}
+
+/// Number types the command line can reduce.
+pub trait CliNumber: FromStr + Copy + Send + Sync + PartialEq + Display + 'static {
+    const ZERO: Self;
+    const ONE: Self;
+    const LOWEST: Self;
+    const HIGHEST: Self;
+
+    /// Converts a generated data index into an element.
+    fn from_index(index: usize) -> Self;
+    fn add(a: Self, b: Self) -> Self;
+    fn mul(a: Self, b: Self) -> Self;
+    fn min(a: Self, b: Self) -> Self;
+    fn max(a: Self, b: Self) -> Self;
+
+    /// Adds, or returns `None` on overflow.
+    fn checked_add(a: Self, b: Self) -> Option<Self> {
+        Some(Self::add(a, b))
+    }
+
+    /// Multiplies, or returns `None` on overflow.
+    fn checked_mul(a: Self, b: Self) -> Option<Self> {
+        Some(Self::mul(a, b))
+    }
+
+    /// Compares a parallel and a sequential result.
+    fn approx_eq(a: Self, b: Self) -> bool {
+        a == b
+    }
+
+    /// Returns the identity and function pointer implementing `op`.
+    fn operation(op: Op) -> (Self, fn(Self, Self) -> Self) {
+        match op {
+            Op::Sum => (Self::ZERO, Self::add),
+            Op::Product => (Self::ONE, Self::mul),
+            Op::Min => (Self::HIGHEST, Self::min),
+            Op::Max => (Self::LOWEST, Self::max),
+        }
+    }
+}
+
+macro_rules! impl_cli_int {
+    ($($t:ty),*) => {
+        $(
+            impl CliNumber for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const LOWEST: Self = <$t>::MIN;
+                const HIGHEST: Self = <$t>::MAX;
+
+                fn from_index(index: usize) -> Self {
+                    index as $t
+                }
+                fn add(a: Self, b: Self) -> Self {
+                    a.wrapping_add(b)
+                }
+                fn mul(a: Self, b: Self) -> Self {
+                    a.wrapping_mul(b)
+                }
+                fn min(a: Self, b: Self) -> Self {
+                    Ord::min(a, b)
+                }
+                fn max(a: Self, b: Self) -> Self {
+                    Ord::max(a, b)
+                }
+                fn checked_add(a: Self, b: Self) -> Option<Self> {
+                    a.checked_add(b)
+                }
+                fn checked_mul(a: Self, b: Self) -> Option<Self> {
+                    a.checked_mul(b)
+                }
+            }
+        )*
+    };
+}
+
+impl_cli_int!(i64, i128);
+
+impl CliNumber for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    const LOWEST: Self = f64::NEG_INFINITY;
+    const HIGHEST: Self = f64::INFINITY;
+
+    fn from_index(index: usize) -> Self {
+        index as f64
+    }
+    fn add(a: Self, b: Self) -> Self {
+        a + b
+    }
+    fn mul(a: Self, b: Self) -> Self {
+        a * b
+    }
+    fn min(a: Self, b: Self) -> Self {
+        f64::min(a, b)
+    }
+    fn max(a: Self, b: Self) -> Self {
+        f64::max(a, b)
+    }
+
+    /// Parallel float sums add in a different order, so allow a small relative error.
+    fn approx_eq(a: Self, b: Self) -> bool {
+        a == b || (a - b).abs() <= 1e-9 * a.abs().max(b.abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test parsing and printing operation and type names
+    #[test]
+    fn test_names() {
+        for op in [Op::Sum, Op::Product, Op::Min, Op::Max] {
+            assert_eq!(op.to_string().parse(), Ok(op));
+        }
+        for number_type in [NumberType::I64, NumberType::I128, NumberType::F64] {
+            assert_eq!(number_type.to_string().parse(), Ok(number_type));
+        }
+        assert_eq!(
+            "mean".parse::<Op>(),
+            Err(String::from("unknown operation: mean"))
+        );
+        assert_eq!(
+            "u8".parse::<NumberType>(),
+            Err(String::from("unknown type: u8"))
+        );
+    }
+
+    // Test that argument errors ask for the usage text and runtime failures do not
+    #[test]
+    fn test_error_kinds() {
+        let run_with = |args: &[&str]| run(args.iter().map(|arg| arg.to_string()).collect());
+        assert_eq!(
+            run_with(&["frobnicate"]),
+            Err(CliError::usage("unknown command: frobnicate"))
+        );
+        assert_eq!(
+            run_with(&["run", "--op", "mean", "-"]),
+            Err(CliError::usage("unknown operation: mean"))
+        );
+        assert!(matches!(
+            run_with(&["run", "--op", "sum", "/nonexistent/input.txt"]),
+            Err(CliError::Failed(_))
+        ));
+    }
+}
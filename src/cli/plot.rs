@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::Path;
+
+use parallel_operations::bench::{benchmark, generate_data, thread_counts};
+use parallel_operations::plot::{Chart, Series};
+
+use super::{in_pool, Args, CliError, CliNumber, NumberType, Op};
+
+/// Settings for one plotting run.
+struct PlotOptions {
+    ops: Vec<Op>,
+    number_type: NumberType,
+    sizes: Vec<usize>,
+    threads: Vec<usize>,
+    iterations: u32,
+}
+
+/// `plot`: benchmarks across data sizes and thread counts and writes SVG charts.
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let args = Args::parse(args)?;
+    args.reject_unknown(&["op", "type", "sizes", "threads", "iterations", "out"])?;
+    if let Some(extra) = args.positionals().first() {
+        return Err(CliError::Usage(format!("unexpected argument: {}", extra)));
+    }
+
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let options = PlotOptions {
+        ops: args.parse_list_or("op", vec![Op::Sum, Op::Max])?,
+        number_type: args.parse_or("type", NumberType::I64)?,
+        sizes: args.parse_list_or("sizes", vec![1_000, 10_000, 100_000, 1_000_000, 10_000_000])?,
+        threads: args.parse_list_or("threads", thread_counts(cpus))?,
+        iterations: args.parse_or("iterations", 10)?,
+    };
+    if options.iterations == 0 || options.sizes.is_empty() || options.threads.is_empty() {
+        return Err(CliError::usage(
+            "--iterations, --sizes, and --threads must not be empty",
+        ));
+    }
+    let out = args.value("out").unwrap_or(".");
+    fs::create_dir_all(out).map_err(|err| err.to_string())?;
+
+    let (by_size, by_threads) = match options.number_type {
+        NumberType::I64 => collect::<i64>(&options)?,
+        NumberType::I128 => collect::<i128>(&options)?,
+        NumberType::F64 => collect::<f64>(&options)?,
+    };
+
+    let largest = options.sizes.iter().max().copied().unwrap_or_default();
+    let size_path = Path::new(out).join("speedup-vs-size.svg");
+    Chart::new("Speedup vs. data size", "elements", "speedup")
+        .with_log_x()
+        .write(&size_path, &by_size)
+        .map_err(|err| err.to_string())?;
+    let threads_path = Path::new(out).join("speedup-vs-threads.svg");
+    Chart::new(
+        format!("Speedup vs. threads ({} elements)", largest),
+        "threads",
+        "speedup",
+    )
+    .write(&threads_path, &by_threads)
+    .map_err(|err| err.to_string())?;
+
+    println!("Wrote {}", size_path.display());
+    println!("Wrote {}", threads_path.display());
+    Ok(())
+}
+
+/// Measures the speedup of every op by data size, and by thread count at the largest size.
+fn collect<T: CliNumber>(options: &PlotOptions) -> Result<(Vec<Series>, Vec<Series>), String> {
+    let largest = options.sizes.iter().max().copied().unwrap_or_default();
+    let data = generate_data(largest, T::from_index);
+
+    let mut by_size = Vec::new();
+    let mut by_threads = Vec::new();
+    for &op in &options.ops {
+        let (identity, operation) = T::operation(op);
+        let speedup = |size: usize| {
+            benchmark(&data[..size], identity, operation, options.iterations).speedup()
+        };
+
+        let points = options
+            .sizes
+            .iter()
+            .map(|&size| (size as f64, speedup(size)))
+            .collect();
+        by_size.push(Series::new(op.to_string(), points));
+
+        let points = options
+            .threads
+            .iter()
+            .map(|&threads| Ok((threads as f64, in_pool(Some(threads), || speedup(largest))?)))
+            .collect::<Result<_, String>>()?;
+        by_threads.push(Series::new(op.to_string(), points));
+    }
+    Ok((by_size, by_threads))
+}
@@ -0,0 +1,458 @@
+//! Output formats for benchmark results.
+
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::thread;
+
+use parallel_operations::bench::{BenchmarkReport, Summary, Throughput};
+
+use super::{NumberType, Op};
+
+/// How benchmark results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable progress and summary.
+    Text,
+    /// One row per iteration, with the configuration repeated on every row.
+    Csv,
+    /// A single object with configuration, hardware, iterations, and summary.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Format, String> {
+        match name {
+            "text" => Ok(Format::Text),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            other => Err(format!("unknown format: {}", other)),
+        }
+    }
+}
+
+/// The settings a benchmark ran with, recorded next to its results.
+pub struct RunInfo {
+    pub op: Op,
+    pub number_type: NumberType,
+    pub size: usize,
+    pub threads: usize,
+}
+
+/// The machine a benchmark ran on.
+struct Hardware {
+    cpus: usize,
+    os: &'static str,
+    arch: &'static str,
+}
+
+impl Hardware {
+    fn detect() -> Hardware {
+        Hardware {
+            cpus: thread::available_parallelism().map_or(1, |n| n.get()),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+        }
+    }
+}
+
+/// Prints a benchmark report in the given format.
+pub fn print<T: Display>(
+    format: Format,
+    info: &RunInfo,
+    report: &BenchmarkReport<T>,
+) -> io::Result<()> {
+    match format {
+        Format::Text => {
+            print_text(report);
+            Ok(())
+        }
+        Format::Csv => write_csv(&mut io::stdout().lock(), info, report),
+        Format::Json => write_json(&mut io::stdout().lock(), info, report),
+    }
+}
+
+fn print_text<T: Display>(report: &BenchmarkReport<T>) {
+    for sample in &report.samples {
+        println!("Parallel computation took: {:?}", sample.parallel);
+        println!("Sequential computation took: {:?}", sample.sequential);
+        if sample.parallel < sample.sequential {
+            println!(
+                "Parallel computation is faster by: {:?}",
+                sample.sequential - sample.parallel
+            );
+        } else {
+            println!(
+                "Sequential computation is faster by: {:?}",
+                sample.parallel - sample.sequential
+            );
+        }
+    }
+    println!("Parallel result: {}", report.parallel_result);
+    println!("Sequential result: {}", report.sequential_result);
+
+    let par_win = report.parallel_wins();
+    let seq_win = report.samples.len() - par_win;
+    let avg_par_time = report.mean_parallel();
+    let avg_seq_time = report.mean_sequential();
+
+    println!("\n--- Final Results ---");
+    if seq_win > par_win {
+        println!(
+            "Sequential computation won {} times out of {}",
+            seq_win,
+            seq_win + par_win
+        );
+        println!(
+            "On average, sequential computation was faster by: {:?}",
+            avg_par_time.saturating_sub(avg_seq_time)
+        );
+    } else {
+        println!(
+            "Parallel computation won {} times out of {}",
+            par_win,
+            seq_win + par_win
+        );
+        println!(
+            "On average, parallel computation was faster by: {:?}",
+            avg_seq_time.saturating_sub(avg_par_time)
+        );
+    }
+    println!("Average speedup: {:.2}x", report.speedup());
+    print_throughput("Parallel", &report.parallel_throughput());
+    print_throughput("Sequential", &report.sequential_throughput());
+
+    if let (Some(parallel), Some(sequential)) =
+        (report.parallel_summary(), report.sequential_summary())
+    {
+        println!("\n--- Statistics (outliers rejected) ---");
+        print_summary("Parallel", &parallel);
+        print_summary("Sequential", &sequential);
+    }
+}
+
+fn print_throughput(name: &str, throughput: &Throughput) {
+    println!(
+        "{} throughput: {:.0} elements/s, {:.2} GB/s",
+        name,
+        throughput.elements_per_sec,
+        throughput.gb_per_sec()
+    );
+}
+
+fn print_summary(name: &str, summary: &Summary) {
+    println!(
+        "{}: mean {:?}, median {:?}, stddev {:?}, 95% CI [{:?}, {:?}], {} outlier(s) rejected",
+        name,
+        summary.mean,
+        summary.median,
+        summary.std_dev,
+        summary.ci95.0,
+        summary.ci95.1,
+        summary.outliers
+    );
+}
+
+/// Writes one CSV row per iteration, after a header row.
+fn write_csv<T, W: Write>(
+    out: &mut W,
+    info: &RunInfo,
+    report: &BenchmarkReport<T>,
+) -> io::Result<()> {
+    let hardware = Hardware::detect();
+    writeln!(out, "{}", CSV_HEADER)?;
+    for (iteration, sample) in report.samples.iter().enumerate() {
+        let parallel = Throughput::of(report.elements, report.element_size, sample.parallel);
+        let sequential = Throughput::of(report.elements, report.element_size, sample.sequential);
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            info.op,
+            info.number_type,
+            info.size,
+            info.threads,
+            hardware.cpus,
+            hardware.os,
+            hardware.arch,
+            iteration,
+            sample.parallel.as_nanos(),
+            sample.sequential.as_nanos(),
+            sample.speedup(),
+            parallel.elements_per_sec,
+            sequential.elements_per_sec,
+            parallel.bytes_per_sec,
+            sequential.bytes_per_sec
+        )?;
+    }
+    Ok(())
+}
+
+/// The columns [`write_csv`] writes.
+const CSV_HEADER: &str = "op,type,size,threads,cpus,os,arch,iteration,parallel_ns,sequential_ns,speedup,parallel_elements_per_sec,sequential_elements_per_sec,parallel_bytes_per_sec,sequential_bytes_per_sec";
+
+/// Writes the report as a single JSON object.
+fn write_json<T: Display, W: Write>(
+    out: &mut W,
+    info: &RunInfo,
+    report: &BenchmarkReport<T>,
+) -> io::Result<()> {
+    let hardware = Hardware::detect();
+    let iterations: Vec<String> = report
+        .samples
+        .iter()
+        .map(|sample| {
+            format!(
+                "{{\"parallel_ns\":{},\"sequential_ns\":{},\"speedup\":{}}}",
+                sample.parallel.as_nanos(),
+                sample.sequential.as_nanos(),
+                json_number(sample.speedup())
+            )
+        })
+        .collect();
+
+    writeln!(out, "{{")?;
+    writeln!(
+        out,
+        "  \"config\": {{\"op\":{},\"type\":{},\"size\":{},\"threads\":{}}},",
+        json_string(info.op),
+        json_string(info.number_type),
+        info.size,
+        info.threads
+    )?;
+    writeln!(
+        out,
+        "  \"hardware\": {{\"cpus\":{},\"os\":{},\"arch\":{}}},",
+        hardware.cpus,
+        json_string(hardware.os),
+        json_string(hardware.arch)
+    )?;
+    writeln!(out, "  \"iterations\": [{}],", iterations.join(","))?;
+    writeln!(
+        out,
+        "  \"summary\": {{\"mean_parallel_ns\":{},\"mean_sequential_ns\":{},\"speedup\":{},\"parallel_wins\":{}}},",
+        report.mean_parallel().as_nanos(),
+        report.mean_sequential().as_nanos(),
+        json_number(report.speedup()),
+        report.parallel_wins()
+    )?;
+    writeln!(
+        out,
+        "  \"throughput\": {{\"parallel\":{},\"sequential\":{}}},",
+        json_throughput(&report.parallel_throughput()),
+        json_throughput(&report.sequential_throughput())
+    )?;
+    if let (Some(parallel), Some(sequential)) =
+        (report.parallel_summary(), report.sequential_summary())
+    {
+        writeln!(
+            out,
+            "  \"statistics\": {{\"parallel\":{},\"sequential\":{}}},",
+            json_summary(&parallel),
+            json_summary(&sequential)
+        )?;
+    }
+    writeln!(
+        out,
+        "  \"result\": {}",
+        json_string(&report.parallel_result)
+    )?;
+    writeln!(out, "}}")
+}
+
+/// Formats a throughput as a JSON object.
+fn json_throughput(throughput: &Throughput) -> String {
+    format!(
+        "{{\"elements_per_sec\":{},\"bytes_per_sec\":{}}}",
+        json_number(throughput.elements_per_sec),
+        json_number(throughput.bytes_per_sec)
+    )
+}
+
+/// Formats a summary as a JSON object of nanosecond values.
+fn json_summary(summary: &Summary) -> String {
+    format!(
+        "{{\"count\":{},\"outliers\":{},\"mean_ns\":{},\"median_ns\":{},\"std_dev_ns\":{},\"ci95_ns\":[{},{}]}}",
+        summary.count,
+        summary.outliers,
+        summary.mean.as_nanos(),
+        summary.median.as_nanos(),
+        summary.std_dev.as_nanos(),
+        summary.ci95.0.as_nanos(),
+        summary.ci95.1.as_nanos()
+    )
+}
+
+/// Formats a value as a JSON string, escaping quotes, backslashes, and control characters.
+fn json_string(value: impl Display) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.to_string().chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats a float as a JSON number, or `null` if it is not finite.
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::from("null")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parallel_operations::bench::Sample;
+    use std::time::Duration;
+
+    fn info() -> RunInfo {
+        RunInfo {
+            op: Op::Sum,
+            number_type: NumberType::F64,
+            size: 3,
+            threads: 2,
+        }
+    }
+
+    fn report<T>(result: T, samples: Vec<Sample>) -> BenchmarkReport<T>
+    where
+        T: Clone,
+    {
+        BenchmarkReport {
+            samples,
+            elements: 3,
+            element_size: 8,
+            parallel_result: result.clone(),
+            sequential_result: result,
+        }
+    }
+
+    fn sample(parallel: u64, sequential: u64) -> Sample {
+        Sample {
+            parallel: Duration::from_nanos(parallel),
+            sequential: Duration::from_nanos(sequential),
+        }
+    }
+
+    /// Checks that `text` is exactly one JSON value, surrounded only by whitespace.
+    fn is_valid_json(text: &str) -> bool {
+        let mut chars = text.chars().peekable();
+        value(&mut chars) && {
+            skip_whitespace(&mut chars);
+            chars.next().is_none()
+        }
+    }
+
+    type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+    fn skip_whitespace(chars: &mut Chars) {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn value(chars: &mut Chars) -> bool {
+        skip_whitespace(chars);
+        match chars.peek() {
+            Some('{') => sequence(chars, '}', |chars| {
+                skip_whitespace(chars);
+                string(chars)
+                    && {
+                        skip_whitespace(chars);
+                        chars.next() == Some(':')
+                    }
+                    && value(chars)
+            }),
+            Some('[') => sequence(chars, ']', value),
+            Some('"') => string(chars),
+            Some(_) => {
+                let mut literal = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || "+-.".contains(*c)) {
+                    literal.push(c);
+                }
+                ["true", "false", "null"].contains(&literal.as_str())
+                    || (!literal.starts_with(['+', '.'])
+                        && literal.parse::<f64>().is_ok_and(f64::is_finite))
+            }
+            None => false,
+        }
+    }
+
+    fn sequence(chars: &mut Chars, close: char, item: fn(&mut Chars) -> bool) -> bool {
+        chars.next();
+        skip_whitespace(chars);
+        if chars.next_if_eq(&close).is_some() {
+            return true;
+        }
+        loop {
+            if !item(chars) {
+                return false;
+            }
+            skip_whitespace(chars);
+            match chars.next() {
+                Some(',') => {}
+                Some(c) => return c == close,
+                None => return false,
+            }
+        }
+    }
+
+    fn string(chars: &mut Chars) -> bool {
+        if chars.next() != Some('"') {
+            return false;
+        }
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return true,
+                '\\' if chars.next().is_none() => return false,
+                c if c.is_control() => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    // Test that every CSV row has as many columns as the header
+    #[test]
+    fn test_csv_columns() {
+        let report = report(6.0, vec![sample(10, 30), sample(0, 30), sample(0, 0)]);
+        let mut out = Vec::new();
+        write_csv(&mut out, &info(), &report).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        let columns = lines[0].split(',').count();
+        for line in &lines {
+            assert_eq!(line.split(',').count(), columns, "{}", line);
+        }
+    }
+
+    // Test that JSON stays valid with a non-finite speedup and quotes in strings
+    #[test]
+    fn test_json_valid() {
+        let mut out = Vec::new();
+        let infinite = report(6.0, vec![sample(0, 30), sample(0, 30)]);
+        write_json(&mut out, &info(), &infinite).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(is_valid_json(&text), "{}", text);
+        assert!(text.contains("\"speedup\":null"));
+
+        let mut out = Vec::new();
+        let quoted = report("say \"hi\"\\\n", vec![]);
+        write_json(&mut out, &info(), &quoted).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(is_valid_json(&text), "{}", text);
+        assert!(text.contains(r#""result": "say \"hi\"\\\u000a""#));
+
+        assert_eq!(json_number(f64::NAN), "null");
+        assert!(!is_valid_json("{\"speedup\":inf}"));
+        assert!(!is_valid_json("{\"result\":\"a\"b\"}"));
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::str::FromStr;
+
+use parallel_operations::parallel_reduce_lines;
+
+use super::{Args, CliError, CliNumber, NumberType, Op};
+
+/// `run`: reduces newline-delimited numbers from a file or stdin and prints the result.
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let args = Args::parse(args)?;
+    args.reject_unknown(&["op", "type", "batch-size"])?;
+
+    let op: Op = args
+        .value("op")
+        .ok_or_else(|| CliError::usage("--op is required"))?
+        .parse()
+        .map_err(CliError::Usage)?;
+    let number_type: NumberType = args.parse_or("type", NumberType::I64)?;
+    let batch_size: usize = args.parse_or("batch-size", 65_536)?;
+    let input = match args.positionals() {
+        [input] => input.as_str(),
+        [] => return Err(CliError::usage("missing input file (use `-` for stdin)")),
+        _ => return Err(CliError::usage("expected a single input file")),
+    };
+
+    let output = match number_type {
+        NumberType::I64 => reduce_input::<i64>(input, op, batch_size),
+        NumberType::I128 => reduce_input::<i128>(input, op, batch_size),
+        NumberType::F64 => reduce_input::<f64>(input, op, batch_size),
+    }
+    .map_err(|err| err.to_string())?;
+    println!("{}", output);
+    Ok(())
+}
+
+fn reduce_input<T: CliNumber>(input: &str, op: Op, batch_size: usize) -> io::Result<String> {
+    let reader: Box<dyn BufRead> = if input == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(input)?))
+    };
+    let identity = Checked(Some(T::operation(op).0));
+    match parallel_reduce_lines(reader, batch_size, identity, checked_operation::<T>(op))? {
+        Checked(Some(result)) => Ok(result.to_string()),
+        Checked(None) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("overflow: the {} does not fit in the element type", op),
+        )),
+    }
+}
+
+/// A value read from the input, or `None` once a result including it has overflowed.
+#[derive(Clone, Copy)]
+struct Checked<T>(Option<T>);
+
+impl<T: FromStr> FromStr for Checked<T> {
+    type Err = T::Err;
+
+    fn from_str(line: &str) -> Result<Self, T::Err> {
+        line.parse().map(|value| Checked(Some(value)))
+    }
+}
+
+impl<T: Copy> Checked<T> {
+    fn combine(self, other: Self, operation: fn(T, T) -> Option<T>) -> Self {
+        Checked(self.0.zip(other.0).and_then(|(a, b)| operation(a, b)))
+    }
+}
+
+/// Returns `op` on [`Checked`] values, so integer sums and products overflow to `None`
+/// instead of wrapping.
+fn checked_operation<T: CliNumber>(op: Op) -> fn(Checked<T>, Checked<T>) -> Checked<T> {
+    match op {
+        Op::Sum => |a, b| a.combine(b, T::checked_add),
+        Op::Product => |a, b| a.combine(b, T::checked_mul),
+        Op::Min => |a, b| a.combine(b, |a, b| Some(T::min(a, b))),
+        Op::Max => |a, b| a.combine(b, |a, b| Some(T::max(a, b))),
+    }
+}
@@ -0,0 +1,82 @@
+use parallel_operations::bench::{generate_data, sweep_threads, thread_counts, DEFAULT_WARMUP};
+
+use super::{Args, CliError, CliNumber, NumberType, Op};
+
+/// Settings for one scalability sweep.
+struct ScaleOptions {
+    op: Op,
+    size: usize,
+    threads: Vec<usize>,
+    iterations: u32,
+}
+
+/// `scale`: runs the same reduction at increasing thread counts and reports efficiency.
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let args = Args::parse(args)?;
+    args.reject_unknown(&["op", "type", "size", "threads", "iterations"])?;
+    if let Some(extra) = args.positionals().first() {
+        return Err(CliError::Usage(format!("unexpected argument: {}", extra)));
+    }
+
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let number_type: NumberType = args.parse_or("type", NumberType::I64)?;
+    let options = ScaleOptions {
+        op: args.parse_or("op", Op::Sum)?,
+        size: args.parse_or("size", 10_000_000)?,
+        threads: args.parse_list_or("threads", thread_counts(cpus))?,
+        iterations: args.parse_or("iterations", 10)?,
+    };
+    if options.iterations == 0 || options.threads.contains(&0) {
+        return Err(CliError::usage(
+            "--iterations and every --threads value must be at least 1",
+        ));
+    }
+
+    match number_type {
+        NumberType::I64 => sweep::<i64>(&options),
+        NumberType::I128 => sweep::<i128>(&options),
+        NumberType::F64 => sweep::<f64>(&options),
+    }
+    Ok(())
+}
+
+fn sweep<T: CliNumber>(options: &ScaleOptions) {
+    let (identity, operation) = T::operation(options.op);
+    let data = generate_data(options.size, T::from_index);
+    let results = sweep_threads(
+        &data,
+        identity,
+        operation,
+        &options.threads,
+        DEFAULT_WARMUP,
+        options.iterations,
+    );
+
+    println!(
+        "{:>8}  {:>12}  {:>8}  {:>10}  {:>10}",
+        "threads", "median", "speedup", "efficiency", "step eff."
+    );
+    for result in &results {
+        println!(
+            "{:>8}  {:>12?}  {:>7.2}x  {:>9.0}%  {:>9.0}%",
+            result.threads,
+            result.summary.median,
+            result.speedup,
+            result.efficiency * 100.0,
+            result.step_efficiency * 100.0
+        );
+    }
+
+    // Scaling stops paying off once adding threads keeps less than half its value
+    if let Some(knee) = results.iter().skip(1).find(|r| r.step_efficiency < 0.5) {
+        println!(
+            "\nScaling flattens at {} threads; consider ParallelConfig::new().with_threads({})",
+            knee.threads,
+            results
+                .iter()
+                .take_while(|r| r.threads < knee.threads)
+                .last()
+                .map_or(1, |r| r.threads)
+        );
+    }
+}
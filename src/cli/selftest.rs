@@ -0,0 +1,37 @@
+use parallel_operations::selftest;
+
+use super::{Args, CliError};
+
+/// `selftest`: checks every built-in reduction against a sequential fold on random inputs.
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let args = Args::parse(args)?;
+    args.reject_unknown(&["seed", "rounds"])?;
+    if let Some(extra) = args.positionals().first() {
+        return Err(CliError::Usage(format!("unexpected argument: {}", extra)));
+    }
+
+    let seed: u64 = args.parse_or("seed", 0x5EED)?;
+    let rounds: u64 = args.parse_or("rounds", 1)?;
+
+    let mut checks = 0;
+    let mut failures = 0;
+    for round in 0..rounds {
+        // Seeds of consecutive rounds are spaced so their inputs do not overlap
+        let report = selftest(seed.wrapping_add(round.wrapping_mul(1_000)));
+        checks += report.checks;
+        failures += report.mismatches.len();
+        for mismatch in &report.mismatches {
+            println!("MISMATCH {}", mismatch);
+        }
+    }
+
+    println!("{} checks, {} mismatches", checks, failures);
+    if failures == 0 {
+        Ok(())
+    } else {
+        Err(CliError::Failed(format!(
+            "{} parallel results differ from sequential ones",
+            failures
+        )))
+    }
+}
@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use parallel_operations::bench::{
+    chunk_size_candidates, generate_data, recommend_chunk_size, sweep_chunk_sizes, DEFAULT_WARMUP,
+};
+
+use parallel_operations::{autotune, autotune_to, default_profile_path};
+
+use super::{in_pool, Args, CliError, CliNumber, NumberType, Op};
+
+/// Settings for one chunk-size sweep.
+struct TuneOptions {
+    op: Op,
+    number_type: NumberType,
+    size: usize,
+    min_chunk: usize,
+    iterations: u32,
+}
+
+/// `tune`: sweeps a tuning parameter and recommends a setting.
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let (target, rest) = args.split_first().ok_or_else(|| {
+        CliError::usage("missing tuning target (expected `chunk-size` or `profile`)")
+    })?;
+    match target.as_str() {
+        "chunk-size" => chunk_size(rest),
+        "profile" => profile(rest),
+        other => Err(CliError::Usage(format!("unknown tuning target: {}", other))),
+    }
+}
+
+fn profile(args: &[String]) -> Result<(), CliError> {
+    let args = Args::parse(args)?;
+    args.reject_unknown(&["out"])?;
+    if let Some(extra) = args.positionals().first() {
+        return Err(CliError::Usage(format!("unexpected argument: {}", extra)));
+    }
+
+    let (profile, path) = match args.value("out") {
+        Some(path) => (autotune_to(path), PathBuf::from(path)),
+        None => (
+            autotune(),
+            default_profile_path()
+                .ok_or_else(|| CliError::usage("no profile location: pass --out"))?,
+        ),
+    };
+    let profile = profile.map_err(|err| err.to_string())?;
+    print!("{}", profile);
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+fn chunk_size(args: &[String]) -> Result<(), CliError> {
+    let args = Args::parse(args)?;
+    args.reject_unknown(&["op", "type", "size", "min-chunk", "iterations", "threads"])?;
+    if let Some(extra) = args.positionals().first() {
+        return Err(CliError::Usage(format!("unexpected argument: {}", extra)));
+    }
+
+    let options = TuneOptions {
+        op: args.parse_or("op", Op::Sum)?,
+        number_type: args.parse_or("type", NumberType::I64)?,
+        size: args.parse_or("size", 10_000_000)?,
+        min_chunk: args.parse_or("min-chunk", 1_024)?,
+        iterations: args.parse_or("iterations", 10)?,
+    };
+    if options.iterations == 0 {
+        return Err(CliError::usage("--iterations must be at least 1"));
+    }
+    let threads = args
+        .value("threads")
+        .map(str::parse)
+        .transpose()
+        .map_err(|_| CliError::usage("invalid value for --threads"))?;
+
+    Ok(in_pool(threads, || match options.number_type {
+        NumberType::I64 => sweep::<i64>(&options),
+        NumberType::I128 => sweep::<i128>(&options),
+        NumberType::F64 => sweep::<f64>(&options),
+    })?)
+}
+
+fn sweep<T: CliNumber>(options: &TuneOptions) {
+    let (identity, operation) = T::operation(options.op);
+    let data = generate_data(options.size, T::from_index);
+    let candidates = chunk_size_candidates(data.len(), options.min_chunk);
+    let results = sweep_chunk_sizes(
+        &data,
+        identity,
+        operation,
+        &candidates,
+        DEFAULT_WARMUP,
+        options.iterations,
+    );
+
+    println!(
+        "{:>12}  {:>12}  {:>12}  {:>16}  {:>8}",
+        "chunk size", "median", "stddev", "elements/sec", "GB/s"
+    );
+    for result in &results {
+        let throughput = result.throughput();
+        println!(
+            "{:>12}  {:>12?}  {:>12?}  {:>16.0}  {:>8.2}",
+            result.chunk_size,
+            result.summary.median,
+            result.summary.std_dev,
+            throughput.elements_per_sec,
+            throughput.gb_per_sec()
+        );
+    }
+    if let Some(best) = recommend_chunk_size(&results) {
+        println!(
+            "\nRecommended: ParallelConfig::new().with_chunk_size({}) on {} thread(s)",
+            best,
+            rayon::current_num_threads()
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use rayon::prelude::*;
+use std::sync::{Arc, OnceLock};
+
+use crate::identity::reduce_slice_with_identity;
+use crate::{parallel_binary_operation, Bounded};
+
+/// Runs several reductions over borrowed data concurrently, in one fork-join scope.
+///
+/// Each reduction registered with [`ReductionScope::reduce`] starts immediately as a
+/// task on the global rayon pool, and all of them share its threads; `parallel_scope`
+/// returns once every one has finished. `f` returns the [`Reduction`] handles, e.g.
+/// `parallel_scope(|s| (s.reduce(&data, add), s.reduce(&data, max)))`, and their
+/// results are read from them afterwards.
+///
+/// # Parameters
+/// - `f`: Registers the reductions, and returns their handles.
+///
+/// # Returns
+/// What `f` returns, once all reductions registered in the scope have completed.
+///
+/// # Panics
+/// Propagates a panic from any of the reductions.
+pub fn parallel_scope<'data, R, F>(f: F) -> R
+where
+    R: Send,
+    F: for<'scope> FnOnce(&ReductionScope<'scope, 'data>) -> R + Send,
+{
+    rayon::scope(|scope| f(&ReductionScope { scope }))
+}
+
+/// Schedules reductions inside [`parallel_scope`].
+pub struct ReductionScope<'scope, 'data> {
+    scope: &'scope rayon::Scope<'data>,
+}
+
+impl<'data> ReductionScope<'_, 'data> {
+    /// Starts reducing `data`, inferring the operation's identity like
+    /// [`parallel_binary_operation`].
+    ///
+    /// # Parameters
+    /// - `data`: The elements to reduce, borrowed for the whole scope.
+    /// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+    ///
+    /// # Returns
+    /// A handle to the result, available once the scope has returned.
+    ///
+    /// # Panics
+    /// The scope panics if the identity cannot be inferred.
+    pub fn reduce<T>(&self, data: &'data [T], operation: fn(T, T) -> T) -> Reduction<T>
+    where
+        T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+    {
+        self.spawn(move || parallel_binary_operation(data.par_iter().copied(), operation))
+    }
+
+    /// Starts reducing `data` from an explicit identity.
+    ///
+    /// # Parameters
+    /// - `data`: The elements to reduce, borrowed for the whole scope.
+    /// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+    /// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+    ///
+    /// # Returns
+    /// A handle to the result, available once the scope has returned.
+    pub fn reduce_with_identity<T>(
+        &self,
+        data: &'data [T],
+        identity: T,
+        operation: fn(T, T) -> T,
+    ) -> Reduction<T>
+    where
+        T: Copy + Send + Sync + 'data,
+    {
+        self.spawn(move || reduce_slice_with_identity(data, identity, operation))
+    }
+
+    fn spawn<T, W>(&self, work: W) -> Reduction<T>
+    where
+        T: Send + Sync + 'data,
+        W: FnOnce() -> T + Send + 'data,
+    {
+        let result = Arc::new(OnceLock::new());
+        let slot = Arc::clone(&result);
+        self.scope.spawn(move |_| {
+            // Each slot has exactly one writer, so it is always empty here
+            let _ = slot.set(work());
+        });
+        Reduction { result }
+    }
+}
+
+/// The pending result of a reduction started in a [`parallel_scope`].
+#[derive(Debug)]
+pub struct Reduction<T> {
+    result: Arc<OnceLock<T>>,
+}
+
+impl<T> Reduction<T> {
+    /// Returns the result.
+    ///
+    /// # Panics
+    /// Panics if called inside the scope, before the reduction has finished.
+    pub fn into_inner(self) -> T {
+        Arc::try_unwrap(self.result)
+            .ok()
+            .and_then(OnceLock::into_inner)
+            .expect("reduction results are available once parallel_scope returns")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test several reductions over one buffer, returned together
+    #[test]
+    fn test_scope_several_reductions() {
+        let data: Vec<i64> = (1..=100_000).map(|i| (i * 7_919) % 100_003).collect();
+        let (sum, min, max, product) = parallel_scope(|s| {
+            (
+                s.reduce(&data, |a, b| a + b),
+                s.reduce(&data, i64::min),
+                s.reduce(&data, i64::max),
+                s.reduce_with_identity(&data[..3], 1, |a, b| a * b),
+            )
+        });
+        assert_eq!(sum.into_inner(), data.iter().sum::<i64>());
+        assert_eq!(min.into_inner(), *data.iter().min().unwrap());
+        assert_eq!(max.into_inner(), *data.iter().max().unwrap());
+        assert_eq!(product.into_inner(), data[..3].iter().product::<i64>());
+    }
+
+    // Test reductions over different buffers and element types
+    #[test]
+    fn test_scope_mixed_types() {
+        let counts: Vec<u32> = (0..1_000).collect();
+        let prices: Vec<f64> = (0..1_000).map(|i| f64::from(i) * 0.5).collect();
+        let handles =
+            parallel_scope(|s| (s.reduce(&counts, |a, b| a + b), s.reduce(&prices, f64::max)));
+        assert_eq!(handles.0.into_inner(), 499_500);
+        assert_eq!(handles.1.into_inner(), 499.5);
+    }
+
+    // Test that a panicking reduction propagates out of the scope
+    #[test]
+    #[should_panic(expected = "cannot infer the identity")]
+    fn test_scope_panic() {
+        let data = [1i32, 2, 3];
+        parallel_scope(|s| {
+            s.reduce(&data, |a, b| a + b + 1);
+        });
+    }
+}
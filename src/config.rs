@@ -0,0 +1,408 @@
+use rayon::ThreadPool;
+use std::collections::HashMap;
+use std::env;
+use std::mem;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+use crate::buffer::BufferPool;
+use crate::platform::{available_threads, performance_cores, physical_cores};
+use crate::tuning::active_profile;
+use crate::Throttle;
+
+/// Environment variable that forces deterministic execution of every reduction.
+///
+/// Any value other than empty or `0` has the same effect as
+/// [`ParallelConfig::with_deterministic`] on every configuration. It is read once, on
+/// first use.
+pub const DETERMINISTIC_ENV: &str = "PARALLEL_OPERATIONS_DETERMINISTIC";
+
+/// Number of threads deterministic reductions split their input for, unless set explicitly.
+///
+/// Fixed instead of the core count so the chunking is the same on every machine.
+const DETERMINISTIC_THREADS: usize = 4;
+
+/// Relative difference [`ParallelConfig::with_verify`] allows between float results, to
+/// absorb the rounding differences of a different summation order (which for long `f32`
+/// sums exceed `1e-6`).
+const DEFAULT_VERIFY_TOLERANCE: f64 = 1e-4;
+
+/// How [`parallel_variance_with_config`](crate::parallel_variance_with_config) computes a
+/// variance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VarianceAlgorithm {
+    /// One pass accumulating the sum and the sum of squares. Fastest, but inaccurate when
+    /// the mean is large compared with the spread.
+    #[default]
+    OnePass,
+    /// A pass for the mean and a second one over the squared deviations from it.
+    TwoPass,
+}
+
+/// Which cores a reduction spreads its threads across when no thread count is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoreSelection {
+    /// One thread per logical core, SMT siblings included.
+    #[default]
+    All,
+    /// One thread per physical core. Memory-bound reductions often run as fast and
+    /// cooler this way, as SMT siblings share one core's caches and memory bandwidth.
+    Physical,
+    /// One thread per physical performance core, leaving the efficiency cores of hybrid
+    /// CPUs idle. The same as `Physical` where no efficiency cores are detected (they are
+    /// read from `/sys/devices/cpu_atom` on Linux).
+    Performance,
+}
+
+impl CoreSelection {
+    /// Returns the number of threads this selection allows, at least 1.
+    pub fn threads(self) -> usize {
+        match self {
+            CoreSelection::All => available_threads(),
+            CoreSelection::Physical => physical_cores(),
+            CoreSelection::Performance => performance_cores(),
+        }
+    }
+}
+
+/// Returns whether [`DETERMINISTIC_ENV`] is set.
+fn deterministic_env() -> bool {
+    static SET: OnceLock<bool> = OnceLock::new();
+    *SET.get_or_init(|| env::var_os(DETERMINISTIC_ENV).is_some_and(|v| !v.is_empty() && v != "0"))
+}
+
+/// Tuning options for parallel reductions.
+///
+/// The default configuration matches the behavior of
+/// [`parallel_binary_operation`](crate::parallel_binary_operation): one chunk per
+/// available core and no sequential fallback beyond trivial inputs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParallelConfig {
+    /// Number of worker threads to split the data across. `None` uses every available core.
+    pub threads: Option<usize>,
+    /// Fixed number of elements per chunk. `None` divides the data evenly across the threads.
+    pub chunk_size: Option<usize>,
+    /// Smallest automatically chosen chunk, so cheap operations are not split into tasks
+    /// whose scheduling costs more than the work they do.
+    pub min_len: Option<usize>,
+    /// Largest automatically chosen chunk, so expensive operations are split finely
+    /// enough for idle threads to steal work.
+    pub max_len: Option<usize>,
+    /// Inputs shorter than this are reduced sequentially, as a single chunk.
+    pub sequential_threshold: usize,
+    /// Optional limit on how much CPU the reduction may use.
+    pub throttle: Option<Throttle>,
+    /// In debug builds, check the operation for associativity and the identity for
+    /// correctness on a sample of the input before reducing, and panic on a violation.
+    /// Ignored in release builds.
+    pub check_operation: bool,
+    /// In debug builds, recompute every parallel result sequentially and panic if the two
+    /// differ by more than this relative tolerance (applied to `f32` and `f64` results;
+    /// other types must match exactly). Ignored in release builds.
+    pub verify: Option<f64>,
+    /// Split the input into the same chunks on every machine and fold and combine them on
+    /// a single worker thread, so results are reproducible bit for bit.
+    pub deterministic: bool,
+    /// Pool that output and scratch buffers are taken from and returned to. `None` uses
+    /// the global allocator.
+    pub buffer_pool: Option<Arc<BufferPool>>,
+    /// Algorithm the variance statistics use.
+    pub variance: VarianceAlgorithm,
+    /// Cores to use when `threads` is `None`.
+    pub cores: CoreSelection,
+    /// Advise large intermediate buffers to be backed by transparent huge pages. Ignored
+    /// without the `hugepages` feature or outside Linux.
+    pub huge_pages: bool,
+}
+
+impl ParallelConfig {
+    /// Creates a configuration with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the configuration the active tuning profile recommends for elements of type `T`.
+    ///
+    /// Without an active profile (see [`autotune`](crate::autotune)) this is the default
+    /// configuration.
+    pub fn tuned<T>() -> Self {
+        match active_profile() {
+            Some(profile) => profile.config_for(mem::size_of::<T>()),
+            None => Self::default(),
+        }
+    }
+
+    /// Sets the number of worker threads.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Sets a fixed chunk size.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Sets the minimum number of elements per automatically sized chunk.
+    pub fn with_min_len(mut self, min_len: usize) -> Self {
+        self.min_len = Some(min_len);
+        self
+    }
+
+    /// Sets the maximum number of elements per automatically sized chunk.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Sets the input length below which the reduction runs sequentially.
+    pub fn with_sequential_threshold(mut self, threshold: usize) -> Self {
+        self.sequential_threshold = threshold;
+        self
+    }
+
+    /// Sets a CPU throttle.
+    ///
+    /// # Panics
+    /// Panics if a [`Throttle::DutyCycle`] fraction is not greater than 0 and at most 1.
+    pub fn with_throttle(mut self, throttle: Throttle) -> Self {
+        if let Throttle::DutyCycle(fraction) = throttle {
+            assert!(
+                fraction > 0.0 && fraction <= 1.0,
+                "duty cycle must be greater than 0 and at most 1, got {}",
+                fraction
+            );
+        }
+        self.throttle = Some(throttle);
+        self
+    }
+
+    /// Enables the debug-build operation check (see [`verify_operation`](crate::verify_operation)).
+    pub fn with_operation_check(mut self) -> Self {
+        self.check_operation = true;
+        self
+    }
+
+    /// Enables debug-build shadow verification against a sequential fold, allowing float
+    /// results a relative difference of `1e-4`.
+    pub fn with_verify(self) -> Self {
+        self.with_verify_tolerance(DEFAULT_VERIFY_TOLERANCE)
+    }
+
+    /// Enables debug-build shadow verification with an explicit relative float tolerance.
+    pub fn with_verify_tolerance(mut self, tolerance: f64) -> Self {
+        self.verify = Some(tolerance);
+        self
+    }
+
+    /// Enables deterministic execution for reproducible tests.
+    ///
+    /// The input is chunked as if for the configured thread count (4 if unset, rather than
+    /// the number of cores) and runs through the usual chunk and combine steps, but on one
+    /// worker thread, so the combine order and therefore the result never vary between
+    /// runs or machines. See also [`DETERMINISTIC_ENV`].
+    pub fn with_deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
+
+    /// Takes output and scratch buffers from `pool` instead of the global allocator.
+    pub fn with_buffer_pool(mut self, pool: Arc<BufferPool>) -> Self {
+        self.buffer_pool = Some(pool);
+        self
+    }
+
+    /// Selects the algorithm the variance statistics use.
+    pub fn with_variance_algorithm(mut self, algorithm: VarianceAlgorithm) -> Self {
+        self.variance = algorithm;
+        self
+    }
+
+    /// Restricts the default thread count to a subset of the cores, e.g. physical cores
+    /// only. An explicit thread count takes precedence.
+    pub fn with_cores(mut self, cores: CoreSelection) -> Self {
+        self.cores = cores;
+        self
+    }
+
+    /// Advises buffers of several megabytes and more to be backed by transparent huge
+    /// pages, reducing TLB misses on very large inputs. Ignored without the `hugepages`
+    /// feature or outside Linux.
+    pub fn with_huge_pages(mut self) -> Self {
+        self.huge_pages = true;
+        self
+    }
+
+    /// Takes an empty buffer for `capacity` elements from the pool, or allocates one.
+    pub(crate) fn take_buffer<T: Send + 'static>(&self, capacity: usize) -> Vec<T> {
+        #[allow(unused_mut)]
+        let mut buffer = match &self.buffer_pool {
+            Some(pool) => pool.take(capacity),
+            None => Vec::with_capacity(capacity),
+        };
+        #[cfg(all(feature = "hugepages", target_os = "linux"))]
+        if self.huge_pages {
+            crate::huge_pages::advise_if_large(&mut buffer);
+        }
+        buffer
+    }
+
+    /// Returns a buffer to the pool, or frees it.
+    pub(crate) fn give_buffer<T: Send + 'static>(&self, buffer: Vec<T>) {
+        if let Some(pool) = &self.buffer_pool {
+            pool.give(buffer);
+        }
+    }
+
+    /// Returns whether this configuration, or the environment, asks for deterministic execution.
+    pub(crate) fn is_deterministic(&self) -> bool {
+        self.deterministic || deterministic_env()
+    }
+
+    /// Returns the number of threads this configuration resolves to (at least 1).
+    pub fn resolved_threads(&self) -> usize {
+        let threads = self
+            .threads
+            .unwrap_or_else(|| {
+                if self.is_deterministic() {
+                    DETERMINISTIC_THREADS
+                } else {
+                    self.cores.threads()
+                }
+            })
+            .max(1);
+        match self.throttle {
+            Some(throttle) => throttle.limit_threads(threads),
+            None => threads,
+        }
+    }
+
+    /// Clamps an automatically chosen chunk size to the configured minimum and maximum.
+    ///
+    /// The maximum wins if the two conflict. An explicit `chunk_size` is never clamped.
+    pub(crate) fn bound_chunk_size(&self, chunk_size: usize) -> usize {
+        bound_chunk_size(chunk_size, self.min_len, self.max_len)
+    }
+
+    /// Returns the size of the dedicated thread pool this configuration needs, if the
+    /// global pool cannot be used because the thread count or the cores are explicitly
+    /// limited or the execution must be deterministic.
+    pub(crate) fn pool_size(&self) -> Option<usize> {
+        if self.is_deterministic() {
+            return Some(1);
+        }
+        match (self.threads, self.throttle) {
+            (Some(_), _) | (_, Some(Throttle::MaxWorkers(_))) => Some(self.resolved_threads()),
+            _ if self.cores != CoreSelection::All => Some(self.resolved_threads()),
+            _ => None,
+        }
+    }
+
+    /// Runs `work` on a thread pool sized for this configuration.
+    ///
+    /// Uses the global rayon pool unless the thread count is explicitly limited, in which
+    /// case a dedicated pool of that size is used, shared by every configuration asking
+    /// for the same size. If that pool cannot be created the work runs on the global pool
+    /// instead.
+    pub(crate) fn install<R, F>(&self, work: F) -> R
+    where
+        R: Send,
+        F: FnOnce() -> R + Send,
+    {
+        match self.pool_size().and_then(shared_pool) {
+            Some(pool) => pool.install(work),
+            None => work(),
+        }
+    }
+}
+
+/// Returns the process-wide pool of `threads` threads, building it on first use.
+///
+/// Pools are kept for the life of the process, one per size requested, so repeated
+/// reductions do not spawn and join their threads on every call.
+fn shared_pool(threads: usize) -> Option<Arc<ThreadPool>> {
+    static POOLS: OnceLock<Mutex<HashMap<usize, Arc<ThreadPool>>>> = OnceLock::new();
+
+    let mut pools = POOLS
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(pool) = pools.get(&threads) {
+        return Some(Arc::clone(pool));
+    }
+    let pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .ok()?,
+    );
+    pools.insert(threads, Arc::clone(&pool));
+    Some(pool)
+}
+
+/// Clamps `chunk_size` to `min_len` and then `max_len`, keeping it at least 1.
+pub(crate) fn bound_chunk_size(
+    chunk_size: usize,
+    min_len: Option<usize>,
+    max_len: Option<usize>,
+) -> usize {
+    let chunk_size = min_len.map_or(chunk_size, |min_len| chunk_size.max(min_len));
+    max_len
+        .map_or(chunk_size, |max_len| chunk_size.min(max_len))
+        .max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that repeated reductions with a dedicated pool run on the same threads
+    #[test]
+    fn test_install_reuses_pool() {
+        let config = ParallelConfig::new().with_threads(1);
+        let first = config.install(std::thread::current);
+        let second = config.clone().install(std::thread::current);
+        assert_eq!(first.id(), second.id());
+        assert_ne!(first.id(), std::thread::current().id());
+        assert_eq!(config.install(rayon::current_num_threads), 1);
+    }
+
+    // Test that pools are shared per size
+    #[test]
+    fn test_shared_pool_per_size() {
+        let a = shared_pool(3).unwrap();
+        assert!(Arc::ptr_eq(&a, &shared_pool(3).unwrap()));
+        assert!(!Arc::ptr_eq(&a, &shared_pool(2).unwrap()));
+        assert_eq!(a.current_num_threads(), 3);
+    }
+
+    // Test that duty cycles in (0, 1] are accepted
+    #[test]
+    fn test_duty_cycle_accepted() {
+        for fraction in [0.25, 1.0] {
+            let config = ParallelConfig::new().with_throttle(Throttle::DutyCycle(fraction));
+            assert_eq!(config.throttle, Some(Throttle::DutyCycle(fraction)));
+        }
+    }
+
+    // Test that a duty cycle above 1 is rejected instead of running unthrottled
+    #[test]
+    #[should_panic(expected = "duty cycle must be greater than 0 and at most 1, got 50")]
+    fn test_duty_cycle_too_large() {
+        ParallelConfig::new().with_throttle(Throttle::DutyCycle(50.0));
+    }
+
+    // Test that a zero duty cycle is rejected
+    #[test]
+    #[should_panic(expected = "duty cycle must be greater than 0")]
+    fn test_duty_cycle_zero() {
+        ParallelConfig::new().with_throttle(Throttle::DutyCycle(0.0));
+    }
+
+    // Test that a NaN duty cycle is rejected
+    #[test]
+    #[should_panic(expected = "got NaN")]
+    fn test_duty_cycle_nan() {
+        ParallelConfig::new().with_throttle(Throttle::DutyCycle(f64::NAN));
+    }
+}
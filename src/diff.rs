@@ -0,0 +1,78 @@
+use rayon::prelude::*;
+use std::ops::Sub;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Computes the differences between adjacent elements in parallel.
+///
+/// Every output element reads its own pair of inputs, so chunks overlap by one element
+/// and no difference across a chunk boundary is lost.
+///
+/// # Parameters
+/// - `data`: The series to differentiate.
+///
+/// # Returns
+/// `data[i + 1] - data[i]` for every `i`, one element shorter than `data` (empty for
+/// fewer than two elements).
+pub fn parallel_diff<T>(data: &[T]) -> Vec<T>
+where
+    T: Copy + Send + Sync + Sub<Output = T>,
+{
+    if data.len() < 2 {
+        return Vec::new();
+    }
+    data.par_windows(2)
+        .with_min_len(aligned_chunk_size(data, available_threads()))
+        .map(|pair| pair[1] - pair[0])
+        .collect()
+}
+
+/// Computes the `order`-th differences of a series in parallel.
+///
+/// # Parameters
+/// - `data`: The series to differentiate.
+/// - `order`: How many times to take adjacent differences; `0` returns a copy of `data`.
+///
+/// # Returns
+/// The `order`-th differences, `order` elements shorter than `data` (empty if `data` is
+/// not longer than `order`).
+pub fn parallel_diff_n<T>(data: &[T], order: usize) -> Vec<T>
+where
+    T: Copy + Send + Sync + Sub<Output = T>,
+{
+    let mut result = data.to_vec();
+    for _ in 0..order {
+        if result.is_empty() {
+            break;
+        }
+        result = parallel_diff(&result);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that differences across chunk boundaries are included
+    #[test]
+    fn test_diff() {
+        let data: Vec<i64> = (0..100_000).map(|i| i * i).collect();
+        let diff = parallel_diff(&data);
+        assert_eq!(diff.len(), 99_999);
+        assert!(diff.iter().enumerate().all(|(i, &d)| d == 2 * i as i64 + 1));
+        assert!(parallel_diff(&[1.0f32]).is_empty());
+    }
+
+    // Test higher orders
+    #[test]
+    fn test_diff_n() {
+        let data: Vec<i64> = (0..1_000).map(|i| i * i * i).collect();
+        let third = parallel_diff_n(&data, 3);
+        assert_eq!(third.len(), 997);
+        assert!(third.iter().all(|&d| d == 6));
+        assert_eq!(parallel_diff_n(&data, 0), data);
+        assert!(parallel_diff_n(&[1, 2], 5).is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Number of hash bits that select a register; the sketch has `2^PRECISION` registers.
+///
+/// 14 bits give 16 KiB per sketch and a standard error of about 0.8%.
+const PRECISION: u32 = 14;
+
+/// Number of registers in a sketch.
+const REGISTERS: usize = 1 << PRECISION;
+
+/// A HyperLogLog sketch: per register, the longest run of leading zeros seen, plus one.
+struct Sketch {
+    registers: Vec<u8>,
+}
+
+impl Sketch {
+    fn new() -> Self {
+        Sketch {
+            registers: vec![0; REGISTERS],
+        }
+    }
+
+    /// Records one element by its hash.
+    fn insert(&mut self, hash: u64) {
+        let register = (hash >> (64 - PRECISION)) as usize;
+        // The sentinel bit caps the rank when the remaining bits are all zero
+        let rest = (hash << PRECISION) | (1 << (PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        let slot = &mut self.registers[register];
+        *slot = (*slot).max(rank);
+    }
+
+    /// Merges another sketch into this one; the result is the sketch of both inputs.
+    fn merge(mut self, other: Sketch) -> Sketch {
+        for (slot, rank) in self.registers.iter_mut().zip(other.registers) {
+            *slot = (*slot).max(rank);
+        }
+        self
+    }
+
+    /// Estimates the number of distinct elements recorded.
+    fn estimate(&self) -> f64 {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let harmonic: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 1.0 / (1u64 << rank) as f64)
+            .sum();
+        let raw = alpha * m * m / harmonic;
+
+        // Small cardinalities leave registers empty; linear counting is more accurate there
+        let zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+}
+
+/// Estimates the number of distinct elements in parallel with HyperLogLog.
+///
+/// Each chunk hashes its elements into its own 16 KiB sketch, and the sketches are merged
+/// register by register, so memory stays fixed however large the input is and the
+/// estimate does not depend on the chunking. The standard error is about 0.8%, and small
+/// counts are estimated almost exactly.
+///
+/// # Parameters
+/// - `data`: The elements to count.
+///
+/// # Returns
+/// The estimated number of distinct elements; `0` for an empty slice.
+pub fn parallel_approx_distinct<T>(data: &[T]) -> u64
+where
+    T: Hash + Sync,
+{
+    if data.is_empty() {
+        return 0;
+    }
+    let sketch = data
+        .par_chunks(aligned_chunk_size(data, available_threads()))
+        .map(|chunk| {
+            let mut sketch = Sketch::new();
+            for x in chunk {
+                let mut hasher = DefaultHasher::new();
+                x.hash(&mut hasher);
+                sketch.insert(hasher.finish());
+            }
+            sketch
+        })
+        .reduce(Sketch::new, Sketch::merge);
+    sketch.estimate().round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test the estimate on a large set of distinct values, each repeated
+    #[test]
+    fn test_approx_distinct_large() {
+        let data: Vec<u64> = (0..1_000_000).map(|i| i % 200_000).collect();
+        let estimate = parallel_approx_distinct(&data) as f64;
+        assert!(
+            (estimate - 200_000.0).abs() < 0.03 * 200_000.0,
+            "{}",
+            estimate
+        );
+    }
+
+    // Test small counts, where linear counting applies
+    #[test]
+    fn test_approx_distinct_small() {
+        assert_eq!(parallel_approx_distinct(&["a", "b", "a", "c", "b"]), 3);
+        let data: Vec<u32> = (0..100).chain(0..100).collect();
+        let estimate = parallel_approx_distinct(&data);
+        assert!((99..=101).contains(&estimate), "{}", estimate);
+        assert_eq!(parallel_approx_distinct::<u8>(&[]), 0);
+    }
+
+    // Test that merged sketches estimate the union
+    #[test]
+    fn test_sketch_merge() {
+        let (mut left, mut right) = (Sketch::new(), Sketch::new());
+        for i in 0..50_000u64 {
+            let mut hasher = DefaultHasher::new();
+            i.hash(&mut hasher);
+            match i % 2 {
+                0 => left.insert(hasher.finish()),
+                _ => right.insert(hasher.finish()),
+            }
+        }
+        let estimate = left.merge(right).estimate();
+        assert!(
+            (estimate - 50_000.0).abs() < 0.03 * 50_000.0,
+            "{}",
+            estimate
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Cluster-wide reductions built from per-machine partial results.
+//!
+//! Every worker reduces its local data into chunk partials with
+//! [`parallel_chunk_reduce`] and sends them, tagged with
+//! its rank, to a coordinator over TCP. The coordinator waits for all workers, orders
+//! the partials by rank, and combines them with the same operation.
+//!
+//! The wire format is a little-endian `u64` rank, a `u64` count, and `count` encoded
+//! values.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use crate::{parallel_chunk_reduce, Bounded};
+
+/// Values that can be sent between machines as fixed-size little-endian bytes.
+pub trait WireValue: Sized {
+    /// The encoded size in bytes.
+    const SIZE: usize;
+
+    /// Appends the encoded value to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Decodes a value from exactly [`Self::SIZE`] bytes.
+    fn decode(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_wire_value {
+    ($($t:ty),*) => {
+        $(
+            impl WireValue for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().expect("slice has the encoded size"))
+                }
+            }
+        )*
+    };
+}
+
+impl_wire_value!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+/// Sends a worker's partial results to the coordinator.
+///
+/// # Parameters
+/// - `stream`: The connection to the coordinator.
+/// - `rank`: The worker's position in the global data order.
+/// - `partials`: The worker's partial results, in local data order.
+pub fn send_partials<T, W>(stream: &mut W, rank: u64, partials: &[T]) -> io::Result<()>
+where
+    T: WireValue,
+    W: Write,
+{
+    let mut message = Vec::with_capacity(16 + partials.len() * T::SIZE);
+    message.extend_from_slice(&rank.to_le_bytes());
+    message.extend_from_slice(&(partials.len() as u64).to_le_bytes());
+    for value in partials {
+        value.encode(&mut message);
+    }
+    stream.write_all(&message)?;
+    stream.flush()
+}
+
+/// Receives one worker's partial results.
+///
+/// # Returns
+/// The worker's rank and its partial results.
+pub fn receive_partials<T, R>(stream: &mut R) -> io::Result<(u64, Vec<T>)>
+where
+    T: WireValue,
+    R: Read,
+{
+    let mut header = [0u8; 16];
+    stream.read_exact(&mut header)?;
+    let rank = u64::from_le_bytes(header[..8].try_into().unwrap());
+    let count = u64::from_le_bytes(header[8..].try_into().unwrap());
+    let len = count
+        .checked_mul(T::SIZE as u64)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "partial count too large"))?;
+
+    // The count is untrusted, so the payload grows with the bytes actually received
+    let mut payload = Vec::new();
+    stream.take(len).read_to_end(&mut payload)?;
+    if payload.len() as u64 != len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "connection closed before all partials arrived",
+        ));
+    }
+    let values = payload.chunks_exact(T::SIZE).map(T::decode).collect();
+    Ok((rank, values))
+}
+
+/// Reduces local data into chunk partials and sends them to the coordinator.
+///
+/// # Parameters
+/// - `coordinator`: The address the coordinator listens on.
+/// - `rank`: The worker's position in the global data order.
+/// - `data`: The worker's share of the data.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+pub fn run_worker<T, A>(
+    coordinator: A,
+    rank: u64,
+    data: Vec<T>,
+    operation: fn(T, T) -> T,
+) -> io::Result<()>
+where
+    T: WireValue + Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+    A: ToSocketAddrs,
+{
+    let partials = parallel_chunk_reduce(data, operation);
+    let mut stream = TcpStream::connect(coordinator)?;
+    send_partials(&mut stream, rank, &partials)
+}
+
+/// Collects partials from `workers` connections and combines them.
+///
+/// Partials are combined in rank order, so order-sensitive operations give the same
+/// result as a single-machine reduction over the concatenated data.
+///
+/// # Parameters
+/// - `listener`: The socket workers connect to.
+/// - `workers`: The number of worker connections to wait for.
+/// - `operation`: The operation the workers reduced with.
+///
+/// # Returns
+/// The combined result, or `None` if no worker sent any partials.
+pub fn coordinate<T>(
+    listener: &TcpListener,
+    workers: usize,
+    operation: fn(T, T) -> T,
+) -> io::Result<Option<T>>
+where
+    T: WireValue,
+{
+    let mut received = Vec::with_capacity(workers);
+    for _ in 0..workers {
+        let (mut stream, _) = listener.accept()?;
+        received.push(receive_partials::<T, _>(&mut stream)?);
+    }
+    received.sort_by_key(|(rank, _)| *rank);
+
+    Ok(received
+        .into_iter()
+        .flat_map(|(_, partials)| partials)
+        .reduce(operation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    // Test that partials survive the wire format
+    #[test]
+    fn test_wire_roundtrip() {
+        let mut buffer = Vec::new();
+        send_partials(&mut buffer, 7, &[1.5f64, -2.0, 3.25]).unwrap();
+        let (rank, values) = receive_partials::<f64, _>(&mut buffer.as_slice()).unwrap();
+        assert_eq!(rank, 7);
+        assert_eq!(values, vec![1.5, -2.0, 3.25]);
+    }
+
+    // Test a full reduction across several workers over TCP
+    #[test]
+    fn test_distributed_sum() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let workers: Vec<_> = (0..3u64)
+            .map(|rank| {
+                thread::spawn(move || {
+                    let start = rank as i64 * 1_000 + 1;
+                    let data: Vec<i64> = (start..start + 1_000).collect();
+                    run_worker(addr, rank, data, |a, b| a + b).unwrap();
+                })
+            })
+            .collect();
+
+        let result = coordinate::<i64>(&listener, 3, |a, b| a + b).unwrap();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(result, Some(3_000 * 3_001 / 2));
+    }
+
+    // Test that truncated input is reported
+    #[test]
+    fn test_receive_truncated() {
+        let mut buffer = Vec::new();
+        send_partials(&mut buffer, 0, &[1u32, 2, 3]).unwrap();
+        buffer.truncate(buffer.len() - 1);
+        let result = receive_partials::<u32, _>(&mut buffer.as_slice());
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+
+        // A forged count is rejected instead of allocating for it up front
+        let mut header = 0u64.to_le_bytes().to_vec();
+        header.extend_from_slice(&u64::MAX.to_le_bytes());
+        let result = receive_partials::<u32, _>(&mut header.as_slice());
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        header[8..].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        let result = receive_partials::<u32, _>(&mut header.as_slice());
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
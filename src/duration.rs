@@ -0,0 +1,137 @@
+use rayon::prelude::*;
+use std::time::Duration;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Converts a nanosecond count back into a `Duration`, or `None` if it does not fit.
+fn from_nanos(nanos: u128) -> Option<Duration> {
+    let secs = u64::try_from(nanos / NANOS_PER_SEC).ok()?;
+    Some(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+}
+
+/// Sums the durations in parallel as `u128` nanoseconds.
+fn total_nanos(data: &[Duration]) -> u128 {
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    data.par_chunks(chunk_size)
+        .map(|chunk| chunk.iter().map(Duration::as_nanos).sum::<u128>())
+        .sum()
+}
+
+/// Computes the total of a slice of durations in parallel.
+///
+/// Accumulates in `u128` nanoseconds, so intermediate sums never overflow.
+///
+/// # Returns
+/// The total duration, or `None` if it exceeds `Duration::MAX`.
+pub fn parallel_duration_total(data: &[Duration]) -> Option<Duration> {
+    from_nanos(total_nanos(data))
+}
+
+/// Computes the mean of a slice of durations in parallel.
+///
+/// # Returns
+/// The mean duration (truncated to whole nanoseconds), or `None` for an empty slice.
+pub fn parallel_duration_mean(data: &[Duration]) -> Option<Duration> {
+    if data.is_empty() {
+        return None;
+    }
+    from_nanos(total_nanos(data) / data.len() as u128)
+}
+
+/// Finds the longest duration in parallel.
+///
+/// # Returns
+/// The maximum duration, or `None` for an empty slice.
+pub fn parallel_duration_max(data: &[Duration]) -> Option<Duration> {
+    data.par_iter().copied().max()
+}
+
+/// Computes percentiles of a slice of durations using the nearest-rank method.
+///
+/// The durations are copied and sorted in parallel once, then every requested
+/// percentile is looked up.
+///
+/// # Parameters
+/// - `data`: The durations to analyze.
+/// - `percentiles`: Percentiles between 0 and 100 (e.g. `[50.0, 95.0, 99.0]`).
+///
+/// # Returns
+/// One duration per requested percentile, in the same order, or an empty vector if
+/// `data` is empty.
+pub fn parallel_duration_percentiles(data: &[Duration], percentiles: &[f64]) -> Vec<Duration> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+    let mut sorted = data.to_vec();
+    sorted.par_sort_unstable();
+
+    percentiles
+        .iter()
+        .map(|p| {
+            let rank = (p.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+            sorted[rank.saturating_sub(1).min(sorted.len() - 1)]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(range: std::ops::RangeInclusive<u64>) -> Vec<Duration> {
+        range.map(Duration::from_millis).collect()
+    }
+
+    // Test for total and mean
+    #[test]
+    fn test_duration_total_mean() {
+        let data = millis(1..=100);
+        assert_eq!(
+            parallel_duration_total(&data),
+            Some(Duration::from_millis(5050))
+        );
+        assert_eq!(
+            parallel_duration_mean(&data),
+            Some(Duration::from_micros(50_500))
+        );
+        assert_eq!(
+            parallel_duration_max(&data),
+            Some(Duration::from_millis(100))
+        );
+    }
+
+    // Test that sums beyond u64 nanoseconds do not overflow
+    #[test]
+    fn test_duration_total_large() {
+        let data = vec![Duration::from_secs(u64::MAX / 4); 3];
+        let expected = Duration::from_secs(u64::MAX / 4 * 3);
+        assert_eq!(parallel_duration_total(&data), Some(expected));
+        assert_eq!(
+            parallel_duration_total(&[Duration::MAX, Duration::MAX]),
+            None
+        );
+    }
+
+    // Test for nearest-rank percentiles
+    #[test]
+    fn test_duration_percentiles() {
+        let data = millis(1..=100);
+        let result = parallel_duration_percentiles(&data, &[50.0, 95.0, 99.0, 100.0]);
+        let expected: Vec<Duration> = [50, 95, 99, 100]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+        assert_eq!(result, expected);
+    }
+
+    // Test for empty input
+    #[test]
+    fn test_duration_empty() {
+        assert_eq!(parallel_duration_total(&[]), Some(Duration::ZERO));
+        assert_eq!(parallel_duration_mean(&[]), None);
+        assert!(parallel_duration_percentiles(&[], &[50.0]).is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+use rayon::prelude::*;
+use std::mem::MaybeUninit;
+
+use crate::platform::available_threads;
+use crate::{aligned_chunk_size, plan_for, ParallelConfig};
+
+/// Applies a function to every element in parallel.
+///
+/// The output is written straight into the vector's spare capacity, one contiguous chunk
+/// per thread, so no pass is spent zeroing it first.
+///
+/// # Parameters
+/// - `data`: The input elements.
+/// - `f`: Maps one element.
+///
+/// # Returns
+/// The mapped elements, in input order.
+pub fn parallel_map<T, U, F>(data: &[T], f: F) -> Vec<U>
+where
+    T: Copy + Sync,
+    U: Copy + Send,
+    F: Fn(T) -> U + Sync,
+{
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    build_output(data.len(), chunk_size, |start, out| {
+        for &x in &data[start..start + out.len()] {
+            out.push(f(x));
+        }
+    })
+}
+
+/// Applies a function to every element in parallel using the given configuration.
+///
+/// The output vector is taken from the configuration's buffer pool, if it has one, and
+/// can be handed back to it once the caller is done with it.
+///
+/// # Parameters
+/// - `data`: The input elements.
+/// - `f`: Maps one element.
+/// - `config`: The thread count, chunking, and buffer pool to use.
+///
+/// # Returns
+/// The mapped elements, in input order.
+pub fn parallel_map_with_config<T, U, F>(data: &[T], f: F, config: &ParallelConfig) -> Vec<U>
+where
+    T: Copy + Sync,
+    U: Copy + Send + 'static,
+    F: Fn(T) -> U + Sync,
+{
+    let chunk_size = plan_for::<T>(data.len(), config).chunk_size;
+    let output = config.take_buffer(data.len());
+    config.install(|| {
+        build_output_in(output, data.len(), chunk_size, |start, out| {
+            for &x in &data[start..start + out.len()] {
+                out.push(f(x));
+            }
+        })
+    })
+}
+
+/// Combines two slices element by element in parallel.
+///
+/// Like [`parallel_map`], the output is written without being zeroed first.
+///
+/// # Parameters
+/// - `a`: The first operands.
+/// - `b`: The second operands.
+/// - `f`: Combines one element of each.
+///
+/// # Returns
+/// `f(a[i], b[i])` for every index, in order.
+///
+/// # Panics
+/// Panics if the slices differ in length.
+pub fn parallel_zip_with<A, B, U, F>(a: &[A], b: &[B], f: F) -> Vec<U>
+where
+    A: Copy + Sync,
+    B: Copy + Sync,
+    U: Copy + Send,
+    F: Fn(A, B) -> U + Sync,
+{
+    assert_eq!(a.len(), b.len(), "slices differ in length");
+    let chunk_size = aligned_chunk_size(a, available_threads());
+    build_output(a.len(), chunk_size, |start, out| {
+        let end = start + out.len();
+        for (&x, &y) in a[start..end].iter().zip(&b[start..end]) {
+            out.push(f(x, y));
+        }
+    })
+}
+
+/// Combines two slices element by element in parallel using the given configuration.
+///
+/// Like [`parallel_map_with_config`], the output vector is taken from the configuration's
+/// buffer pool, if it has one.
+///
+/// # Parameters
+/// - `a`: The first operands.
+/// - `b`: The second operands.
+/// - `f`: Combines one element of each.
+/// - `config`: The thread count, chunking, and buffer pool to use.
+///
+/// # Returns
+/// `f(a[i], b[i])` for every index, in order.
+///
+/// # Panics
+/// Panics if the slices differ in length.
+pub fn parallel_zip_with_config<A, B, U, F>(
+    a: &[A],
+    b: &[B],
+    f: F,
+    config: &ParallelConfig,
+) -> Vec<U>
+where
+    A: Copy + Sync,
+    B: Copy + Sync,
+    U: Copy + Send + 'static,
+    F: Fn(A, B) -> U + Sync,
+{
+    assert_eq!(a.len(), b.len(), "slices differ in length");
+    let chunk_size = plan_for::<A>(a.len(), config).chunk_size;
+    let output = config.take_buffer(a.len());
+    config.install(|| {
+        build_output_in(output, a.len(), chunk_size, |start, out| {
+            let end = start + out.len();
+            for (&x, &y) in a[start..end].iter().zip(&b[start..end]) {
+                out.push(f(x, y));
+            }
+        })
+    })
+}
+
+/// One chunk of an output vector under construction, written front to back.
+pub(crate) struct OutputChunk<'a, U> {
+    slots: &'a mut [MaybeUninit<U>],
+    filled: usize,
+}
+
+impl<U> OutputChunk<'_, U> {
+    /// Writes the next element.
+    ///
+    /// # Panics
+    /// Panics if the chunk is already full.
+    pub(crate) fn push(&mut self, value: U) {
+        self.slots[self.filled].write(value);
+        self.filled += 1;
+    }
+
+    /// Returns the number of elements the chunk holds when complete.
+    pub(crate) fn len(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+/// Builds a vector of `len` elements in parallel without initializing it first.
+///
+/// The output is split into chunks of `chunk_size`, and `fill(start, chunk)` must push
+/// exactly the elements for positions `start..start + chunk.len()`.
+///
+/// # Panics
+/// Panics if `fill` leaves a chunk incomplete.
+pub(crate) fn build_output<U, F>(len: usize, chunk_size: usize, fill: F) -> Vec<U>
+where
+    U: Copy + Send,
+    F: Fn(usize, &mut OutputChunk<'_, U>) + Sync,
+{
+    build_output_in(Vec::with_capacity(len), len, chunk_size, fill)
+}
+
+/// Like [`build_output`], but builds the output in `output`, whose elements are dropped
+/// first.
+pub(crate) fn build_output_in<U, F>(
+    mut output: Vec<U>,
+    len: usize,
+    chunk_size: usize,
+    fill: F,
+) -> Vec<U>
+where
+    U: Copy + Send,
+    F: Fn(usize, &mut OutputChunk<'_, U>) + Sync,
+{
+    output.clear();
+    output.reserve(len);
+    output.spare_capacity_mut()[..len]
+        .par_chunks_mut(chunk_size.max(1))
+        .enumerate()
+        .for_each(|(index, slots)| {
+            let mut chunk = OutputChunk { slots, filled: 0 };
+            fill(index * chunk_size, &mut chunk);
+            assert_eq!(chunk.filled, chunk.len(), "output chunk left incomplete");
+        });
+    // SAFETY: every chunk pushed all of its elements, or the assertion above panicked
+    unsafe { output.set_len(len) };
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test mapping and zipping into a changed element type
+    #[test]
+    fn test_map_and_zip_with() {
+        let data: Vec<u32> = (0..100_003).collect();
+        let squares = parallel_map(&data, |x| u64::from(x) * u64::from(x));
+        assert_eq!(squares.len(), data.len());
+        assert_eq!(squares[100_002], 100_002 * 100_002);
+
+        let halves: Vec<f64> = data.iter().map(|&x| f64::from(x) / 2.0).collect();
+        let sums = parallel_zip_with(&data, &halves, |x, h| f64::from(x) + h);
+        assert!(sums.iter().enumerate().all(|(i, &s)| s == i as f64 * 1.5));
+        assert!(parallel_map(&[] as &[u8], |x| x).is_empty());
+    }
+
+    // Test that the configured variants reuse the pool's buffers
+    #[test]
+    fn test_map_and_zip_with_config() {
+        use crate::BufferPool;
+        use std::sync::Arc;
+
+        let pool = Arc::new(BufferPool::new());
+        let config = ParallelConfig::new().with_buffer_pool(Arc::clone(&pool));
+        let data: Vec<u32> = (0..10_000).collect();
+        let doubled = parallel_map_with_config(&data, |x| x * 2, &config);
+        assert_eq!(doubled[9_999], 19_998);
+        let ptr = doubled.as_ptr();
+        pool.give(doubled);
+
+        let sums = parallel_zip_with_config(&data, &data, |x, y| x + y, &config);
+        assert_eq!(sums.as_ptr(), ptr);
+        assert_eq!(sums, parallel_map(&data, |x| x * 2));
+    }
+
+    // Test that an incompletely written chunk is caught
+    #[test]
+    #[should_panic(expected = "output chunk left incomplete")]
+    fn test_build_output_incomplete() {
+        build_output(10, 4, |_, out: &mut OutputChunk<'_, u8>| out.push(1));
+    }
+}
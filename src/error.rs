@@ -0,0 +1,182 @@
+use std::any::Any;
+use std::fmt;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{NonFiniteError, UnsupportedOperation};
+
+/// Error returned by the `try_`-prefixed functions.
+///
+/// Every reduction that can fail on its input or operation has a `try_` variant, with
+/// these deliberate exceptions:
+/// - Functions that already return a `Result` keep their dedicated error type, which
+///   converts into `Error`: the `parallel_float_*` family and the `_with_policy`
+///   reductions ([`NonFiniteError`]), and [`parallel_apply`](crate::parallel_apply)
+///   ([`UnsupportedOperation`]).
+/// - Functions that are defined for every input and take no user operation, such as
+///   [`parallel_zscore_outliers`](crate::parallel_zscore_outliers) and
+///   [`parallel_normalize`](crate::parallel_normalize), have nothing to report.
+/// - Element-wise transforms (maps, scans, sorts, set operations) return their output
+///   unchanged in shape and propagate a panicking closure like rayon does.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input was empty and the operation has no identity to return instead.
+    EmptyInput,
+    /// An integer result did not fit in the element type.
+    Overflow {
+        /// The chunk whose fold overflowed, or `None` if combining the chunk results did.
+        chunk: Option<usize>,
+        /// The elements folded when the overflow happened; for a chunk, the last one is
+        /// the element whose addition overflowed.
+        elements: Range<usize>,
+    },
+    /// The reduction was cancelled before it finished.
+    Cancelled,
+    /// The reduction did not finish before its deadline.
+    TimedOut,
+    /// The input has fewer elements than the statistic needs.
+    TooFewElements {
+        /// The number of elements the statistic needs.
+        required: usize,
+        /// The number of elements in the input.
+        len: usize,
+    },
+    /// Two inputs that must be walked together have different lengths.
+    LengthMismatch {
+        /// The length of the first input.
+        left: usize,
+        /// The length of the second input.
+        right: usize,
+    },
+    /// The operation panicked; holds the panic message.
+    OperationPanicked(String),
+    /// The identity of the operation could not be inferred; pass one explicitly.
+    UnknownIdentity,
+    /// The operation is not defined for the element type.
+    UnsupportedOperation(UnsupportedOperation),
+    /// A NaN or infinity was encountered under [`FloatPolicy::Error`](crate::FloatPolicy::Error).
+    NonFinite(NonFiniteError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::EmptyInput => write!(f, "input is empty"),
+            Error::Overflow {
+                chunk: Some(chunk),
+                elements,
+            } => write!(
+                f,
+                "arithmetic overflow in chunk {} at elements {:?}",
+                chunk, elements
+            ),
+            Error::Overflow {
+                chunk: None,
+                elements,
+            } => write!(
+                f,
+                "arithmetic overflow combining the chunk results of elements {:?}",
+                elements
+            ),
+            Error::Cancelled => write!(f, "reduction was cancelled"),
+            Error::TimedOut => write!(f, "reduction timed out"),
+            Error::TooFewElements { required, len } => {
+                write!(f, "needs at least {} elements, got {}", required, len)
+            }
+            Error::LengthMismatch { left, right } => {
+                write!(f, "input lengths differ: {} and {}", left, right)
+            }
+            Error::OperationPanicked(message) => write!(f, "operation panicked: {}", message),
+            Error::UnknownIdentity => {
+                write!(f, "the identity of the operation could not be inferred")
+            }
+            Error::UnsupportedOperation(err) => err.fmt(f),
+            Error::NonFinite(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::UnsupportedOperation(err) => Some(err),
+            Error::NonFinite(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<UnsupportedOperation> for Error {
+    fn from(err: UnsupportedOperation) -> Self {
+        Error::UnsupportedOperation(err)
+    }
+}
+
+impl From<NonFiniteError> for Error {
+    fn from(err: NonFiniteError) -> Self {
+        Error::NonFinite(err)
+    }
+}
+
+/// Runs `work`, turning a panic in it (or in any worker it spawns) into an error.
+///
+/// Only used around reductions, which leave no shared state behind when they unwind.
+pub(crate) fn catch_panic<R>(work: impl FnOnce() -> R) -> Result<R, Error> {
+    panic::catch_unwind(AssertUnwindSafe(work))
+        .map_err(|payload| Error::OperationPanicked(panic_message(payload)))
+}
+
+/// Returns `Ok(())` if the two lengths are equal.
+pub(crate) fn check_lengths(left: usize, right: usize) -> Result<(), Error> {
+    if left == right {
+        Ok(())
+    } else {
+        Err(Error::LengthMismatch { left, right })
+    }
+}
+
+/// Extracts the message from a panic payload.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => String::from(*message),
+            Err(_) => String::from("unknown panic"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that panics are caught with their message
+    #[test]
+    fn test_catch_panic() {
+        assert_eq!(catch_panic(|| 7), Ok(7));
+        let err = catch_panic(|| -> i32 { panic!("bad {}", 1) }).unwrap_err();
+        assert_eq!(err, Error::OperationPanicked(String::from("bad 1")));
+        assert_eq!(err.to_string(), "operation panicked: bad 1");
+    }
+
+    // Test conversions and messages of wrapped errors
+    #[test]
+    fn test_error_conversions() {
+        let err = Error::from(NonFiniteError { index: 3 });
+        assert_eq!(err.to_string(), "non-finite value encountered at index 3");
+        assert!(std::error::Error::source(&err).is_some());
+        let overflow = Error::Overflow {
+            chunk: Some(2),
+            elements: 20..27,
+        };
+        assert_eq!(
+            overflow.to_string(),
+            "arithmetic overflow in chunk 2 at elements 20..27"
+        );
+        assert_eq!(
+            check_lengths(2, 3).unwrap_err().to_string(),
+            "input lengths differ: 2 and 3"
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Chunking and combining decoupled from any particular thread pool.
+//!
+//! Available without the `std` feature, so embedded users with their own threading can
+//! reuse the reduction algorithm by supplying an [`Executor`].
+//!
+//! Only [`parallel_reduce_with_executor`] and `Reducer::with_executor` take an executor.
+//! The other reductions in the crate schedule their work on rayon directly, and
+//! selecting a backend for them means going through one of those two.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::chunk_size_for;
+
+/// A unit of work handed to an [`Executor`].
+pub type Job<'a> = Box<dyn FnOnce() + Send + 'a>;
+
+/// Runs batches of jobs, typically on threads owned by the embedder.
+///
+/// Jobs borrow the input data, so `execute` must not return before every job has run to
+/// completion. Jobs may run concurrently and in any order.
+pub trait Executor {
+    /// Runs every job to completion before returning.
+    fn execute<'a>(&self, jobs: Vec<Job<'a>>);
+}
+
+/// Runs every job on the calling thread, one after another.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SequentialExecutor;
+
+impl Executor for SequentialExecutor {
+    fn execute<'a>(&self, jobs: Vec<Job<'a>>) {
+        for job in jobs {
+            job();
+        }
+    }
+}
+
+/// Runs every job on the global rayon pool.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RayonExecutor;
+
+#[cfg(feature = "std")]
+impl Executor for RayonExecutor {
+    fn execute<'a>(&self, jobs: Vec<Job<'a>>) {
+        rayon::scope(|scope| {
+            for job in jobs {
+                scope.spawn(move |_| job());
+            }
+        });
+    }
+}
+
+/// Performs a binary operation over a slice, delegating the chunk work to an executor.
+///
+/// The slice is split into `parts` chunks; each chunk is folded by one job, and the
+/// per-chunk results are combined in order on the calling thread.
+///
+/// # Parameters
+/// - `data`: The elements to operate on.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `parts`: The number of chunks (and therefore jobs) to create.
+/// - `executor`: Runs the per-chunk jobs.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or `identity` for an empty slice.
+pub fn parallel_reduce_with_executor<T, E>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    parts: usize,
+    executor: &E,
+) -> T
+where
+    T: Copy + Send + Sync,
+    E: Executor + ?Sized,
+{
+    if data.is_empty() {
+        return identity;
+    }
+
+    let chunk_size = chunk_size_for(data.len(), parts);
+    let mut partials: Vec<T> = alloc::vec![identity; data.len().div_ceil(chunk_size)];
+
+    let jobs: Vec<Job<'_>> = partials
+        .iter_mut()
+        .zip(data.chunks(chunk_size))
+        .map(|(slot, chunk)| -> Job<'_> {
+            Box::new(move || *slot = chunk.iter().copied().fold(identity, operation))
+        })
+        .collect();
+    executor.execute(jobs);
+
+    partials.into_iter().fold(identity, operation)
+}
+
+/// Folds each `chunk_size` chunk of `data` from its first element in one job on
+/// `executor`, and combines the per-chunk results in order on the calling thread.
+///
+/// Needs no identity, so it serves reductions like [`crate::Reducer`] that have none.
+#[cfg(feature = "std")]
+pub(crate) fn reduce_chunks_with_executor<T, E>(
+    data: &[T],
+    operation: fn(T, T) -> T,
+    chunk_size: usize,
+    executor: &E,
+) -> Option<T>
+where
+    T: Copy + Send + Sync,
+    E: Executor + ?Sized,
+{
+    let mut partials: Vec<Option<T>> = alloc::vec![None; data.len().div_ceil(chunk_size)];
+    let jobs: Vec<Job<'_>> = partials
+        .iter_mut()
+        .zip(data.chunks(chunk_size))
+        .map(|(slot, chunk)| -> Job<'_> {
+            Box::new(move || *slot = chunk.iter().copied().reduce(operation))
+        })
+        .collect();
+    executor.execute(jobs);
+
+    partials.into_iter().flatten().reduce(operation)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    /// Runs each job on its own scoped thread, like an embedder-supplied spawn function.
+    struct ScopedThreads;
+
+    impl Executor for ScopedThreads {
+        fn execute<'a>(&self, jobs: Vec<Job<'a>>) {
+            std::thread::scope(|scope| {
+                for job in jobs {
+                    scope.spawn(job);
+                }
+            });
+        }
+    }
+
+    // Test with the sequential executor
+    #[test]
+    fn test_executor_sequential() {
+        let data: Vec<u64> = (1..=1_000).collect();
+        let result = parallel_reduce_with_executor(&data, 0, |a, b| a + b, 7, &SequentialExecutor);
+        assert_eq!(result, 500_500);
+    }
+
+    // Test with a custom thread-spawning executor
+    #[test]
+    fn test_executor_custom() {
+        let data: Vec<i64> = (1..=100).collect();
+        let result = parallel_reduce_with_executor(&data, i64::MIN, i64::max, 3, &ScopedThreads);
+        assert_eq!(result, 100);
+    }
+
+    // Test with the rayon executor
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_executor_rayon() {
+        let data: Vec<u64> = (1..=20).collect();
+        let result = parallel_reduce_with_executor(&data, 1, |a, b| a * b, 8, &RayonExecutor);
+        assert_eq!(result, (1..=20).product());
+    }
+
+    // Test for empty input
+    #[test]
+    fn test_executor_empty() {
+        let result =
+            parallel_reduce_with_executor(&[], 1, |a: i32, b| a * b, 4, &SequentialExecutor);
+        assert_eq!(result, 1);
+    }
+}
@@ -0,0 +1,431 @@
+use rayon::prelude::*;
+use std::fmt;
+use std::ops::{Add, Mul};
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Floating-point element types supported by the float reductions.
+///
+/// Implemented for `f32` and `f64`. Unlike the identity probing used by
+/// [`parallel_binary_operation`](crate::parallel_binary_operation), the float reductions
+/// use these constants as explicit identities.
+pub trait Float: Copy + Send + Sync + PartialOrd + Add<Output = Self> + Mul<Output = Self> {
+    /// The additive identity.
+    const ZERO: Self;
+    /// The multiplicative identity.
+    const ONE: Self;
+    /// Positive infinity, the identity for `min`.
+    const INFINITY: Self;
+    /// Negative infinity, the identity for `max`.
+    const NEG_INFINITY: Self;
+    /// A quiet NaN.
+    const NAN: Self;
+
+    /// Returns `true` if the value is NaN.
+    fn is_nan(self) -> bool;
+
+    /// Returns `true` if the value is neither NaN nor infinite.
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {
+        $(
+            impl Float for $t {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+                const INFINITY: Self = <$t>::INFINITY;
+                const NEG_INFINITY: Self = <$t>::NEG_INFINITY;
+                const NAN: Self = <$t>::NAN;
+
+                fn is_nan(self) -> bool {
+                    <$t>::is_nan(self)
+                }
+
+                fn is_finite(self) -> bool {
+                    <$t>::is_finite(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32, f64);
+
+/// What a float reduction or statistic does with non-finite elements (NaN and infinities).
+///
+/// Infinities are treated like NaN because they turn sums into infinities and become
+/// NaN once two of opposite sign meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatPolicy {
+    /// Non-finite elements take part in the computation (IEEE semantics; any NaN makes
+    /// the result NaN, also for `min`/`max`).
+    #[default]
+    Propagate,
+    /// Non-finite elements are skipped as if they were not in the input.
+    Skip,
+    /// The computation fails with the position of the first non-finite element.
+    Error,
+}
+
+impl FloatPolicy {
+    /// The former name of [`FloatPolicy::Skip`].
+    #[deprecated(note = "use `FloatPolicy::Skip`, which also skips infinities")]
+    #[allow(non_upper_case_globals)]
+    pub const Ignore: FloatPolicy = FloatPolicy::Skip;
+}
+
+/// The former NaN-only policy of the float reductions.
+#[deprecated(note = "use `FloatPolicy`, which also covers infinities")]
+pub type NanPolicy = FloatPolicy;
+
+/// How [`parallel_float_sum_with_summation`] adds up the elements of each chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Summation {
+    /// Left-to-right accumulation. Fastest, but the rounding error can grow linearly with
+    /// the chunk length.
+    #[default]
+    Naive,
+    /// Pairwise (cascade) summation: each chunk is split in halves recursively down to
+    /// blocks of [`PAIRWISE_BLOCK`] elements, which are summed naively. The error grows
+    /// with the logarithm of the length, at nearly the cost of a naive sum.
+    Pairwise,
+}
+
+/// Block length below which [`Summation::Pairwise`] stops splitting and sums naively.
+pub const PAIRWISE_BLOCK: usize = 128;
+
+/// Error returned by float computations using [`FloatPolicy::Error`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonFiniteError {
+    /// Index of the first NaN or infinite element in the input.
+    pub index: usize,
+}
+
+impl fmt::Display for NonFiniteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "non-finite value encountered at index {}", self.index)
+    }
+}
+
+impl std::error::Error for NonFiniteError {}
+
+/// The former error of the float reductions' NaN-only policy.
+#[deprecated(note = "use `NonFiniteError`")]
+pub type NanError = NonFiniteError;
+
+/// Performs a parallel float reduction with an explicit identity and a policy for
+/// non-finite elements.
+///
+/// # Parameters
+/// - `data`: A vector of floats to operate on.
+/// - `identity`: The identity of `operation` (e.g. `0.0` for addition).
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `policy`: How NaN and infinite elements are handled.
+///
+/// # Returns
+/// The result of applying the operation to all elements, `identity` for an empty
+/// vector, or a [`NonFiniteError`] if the policy is [`FloatPolicy::Error`] and a NaN or
+/// infinity was found.
+pub fn parallel_float_reduce<T: Float>(
+    data: Vec<T>,
+    identity: T,
+    operation: fn(T, T) -> T,
+    policy: FloatPolicy,
+) -> Result<T, NonFiniteError> {
+    reduce_rejecting(&data, identity, operation, policy, Summation::Naive)
+}
+
+/// Performs a parallel float reduction with an explicit identity and a policy for
+/// non-finite elements.
+#[deprecated(note = "use `parallel_float_reduce`, which now takes a `FloatPolicy`")]
+pub fn parallel_float_reduce_with_policy<T: Float>(
+    data: Vec<T>,
+    identity: T,
+    operation: fn(T, T) -> T,
+    policy: FloatPolicy,
+) -> Result<T, NonFiniteError> {
+    parallel_float_reduce(data, identity, operation, policy)
+}
+
+/// Reduces `data`, applying `policy` to its non-finite elements.
+///
+/// # Returns
+/// The result, or the first non-finite element's index under [`FloatPolicy::Error`].
+fn reduce_rejecting<T: Float>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    policy: FloatPolicy,
+    summation: Summation,
+) -> Result<T, NonFiniteError> {
+    if data.is_empty() {
+        return Ok(identity);
+    }
+    if policy == FloatPolicy::Error {
+        if let Some(index) = data.par_iter().position_first(|&x| !x.is_finite()) {
+            return Err(NonFiniteError { index });
+        }
+    }
+
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    let skip = policy == FloatPolicy::Skip;
+
+    let keep = |x: T| !skip || x.is_finite();
+
+    Ok(data
+        .par_chunks(chunk_size)
+        .map(|chunk| match summation {
+            Summation::Naive => fold_kept(chunk, identity, operation, keep),
+            Summation::Pairwise => pairwise_fold(chunk, identity, operation, keep),
+        })
+        .reduce(|| identity, operation))
+}
+
+/// Folds the elements of `chunk` that `keep` accepts, left to right.
+fn fold_kept<T: Float>(
+    chunk: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    keep: impl Fn(T) -> bool + Copy,
+) -> T {
+    chunk
+        .iter()
+        .copied()
+        .filter(|&x| keep(x))
+        .fold(identity, operation)
+}
+
+/// Folds the elements of `chunk` that `keep` accepts by recursive halving.
+fn pairwise_fold<T: Float>(
+    chunk: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    keep: impl Fn(T) -> bool + Copy,
+) -> T {
+    if chunk.len() <= PAIRWISE_BLOCK {
+        return fold_kept(chunk, identity, operation, keep);
+    }
+    let (left, right) = chunk.split_at(chunk.len() / 2);
+    operation(
+        pairwise_fold(left, identity, operation, keep),
+        pairwise_fold(right, identity, operation, keep),
+    )
+}
+
+/// Computes the sum of a float vector in parallel.
+///
+/// # Returns
+/// The sum, `0.0` for an empty vector, or a [`NonFiniteError`] under
+/// [`FloatPolicy::Error`].
+pub fn parallel_float_sum<T: Float>(
+    data: Vec<T>,
+    policy: FloatPolicy,
+) -> Result<T, NonFiniteError> {
+    parallel_float_reduce(data, T::ZERO, |a, b| a + b, policy)
+}
+
+/// Computes the sum of a float vector in parallel with the given summation strategy.
+///
+/// [`Summation::Pairwise`] is a cheaper middle ground between a naive and a compensated
+/// sum: chunks are summed by recursive halving and the chunk sums are combined as a tree,
+/// so the rounding error grows as O(log n) instead of O(n).
+///
+/// # Parameters
+/// - `data`: A vector of floats to sum.
+/// - `policy`: How NaN and infinite elements are handled.
+/// - `summation`: How each chunk is summed.
+///
+/// # Returns
+/// The sum, `0.0` for an empty vector, or a [`NonFiniteError`] under
+/// [`FloatPolicy::Error`].
+pub fn parallel_float_sum_with_summation<T: Float>(
+    data: Vec<T>,
+    policy: FloatPolicy,
+    summation: Summation,
+) -> Result<T, NonFiniteError> {
+    reduce_rejecting(&data, T::ZERO, |a, b| a + b, policy, summation)
+}
+
+/// Computes the product of a float vector in parallel.
+///
+/// # Returns
+/// The product, `1.0` for an empty vector, or a [`NonFiniteError`] under
+/// [`FloatPolicy::Error`].
+pub fn parallel_float_product<T: Float>(
+    data: Vec<T>,
+    policy: FloatPolicy,
+) -> Result<T, NonFiniteError> {
+    parallel_float_reduce(data, T::ONE, |a, b| a * b, policy)
+}
+
+/// Computes the minimum of a float vector in parallel.
+///
+/// # Returns
+/// The minimum, positive infinity for an empty vector, or a [`NonFiniteError`] under
+/// [`FloatPolicy::Error`].
+pub fn parallel_float_min<T: Float>(
+    data: Vec<T>,
+    policy: FloatPolicy,
+) -> Result<T, NonFiniteError> {
+    parallel_float_reduce(data, T::INFINITY, nan_min, policy)
+}
+
+/// Computes the maximum of a float vector in parallel.
+///
+/// # Returns
+/// The maximum, negative infinity for an empty vector, or a [`NonFiniteError`] under
+/// [`FloatPolicy::Error`].
+pub fn parallel_float_max<T: Float>(
+    data: Vec<T>,
+    policy: FloatPolicy,
+) -> Result<T, NonFiniteError> {
+    parallel_float_reduce(data, T::NEG_INFINITY, nan_max, policy)
+}
+
+/// Minimum that returns NaN if either operand is NaN.
+fn nan_min<T: Float>(a: T, b: T) -> T {
+    if a.is_nan() || b.is_nan() {
+        T::NAN
+    } else if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+/// Maximum that returns NaN if either operand is NaN.
+fn nan_max<T: Float>(a: T, b: T) -> T {
+    if a.is_nan() || b.is_nan() {
+        T::NAN
+    } else if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test for float sum
+    #[test]
+    fn test_float_sum() {
+        let data: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(parallel_float_sum(data, FloatPolicy::Propagate), Ok(5050.0));
+    }
+
+    // Test for float min and max
+    #[test]
+    fn test_float_min_max() {
+        let data = vec![3.0f32, -1.5, 7.25, 0.0];
+        assert_eq!(
+            parallel_float_min(data.clone(), FloatPolicy::Propagate),
+            Ok(-1.5)
+        );
+        assert_eq!(parallel_float_max(data, FloatPolicy::Propagate), Ok(7.25));
+    }
+
+    // Test that NaN propagates, including through min
+    #[test]
+    fn test_nan_propagate() {
+        let data = vec![1.0, f64::NAN, 3.0];
+        assert!(parallel_float_sum(data.clone(), FloatPolicy::Propagate)
+            .unwrap()
+            .is_nan());
+        assert!(parallel_float_min(data, FloatPolicy::Propagate)
+            .unwrap()
+            .is_nan());
+    }
+
+    // Test that NaN can be skipped
+    #[test]
+    fn test_nan_ignore() {
+        let data = vec![1.0, f64::NAN, 3.0];
+        assert_eq!(parallel_float_sum(data.clone(), FloatPolicy::Skip), Ok(4.0));
+        assert_eq!(parallel_float_max(data, FloatPolicy::Skip), Ok(3.0));
+    }
+
+    // Test that NaN can be reported as an error
+    #[test]
+    fn test_nan_error() {
+        let data = vec![1.0, 2.0, f64::NAN, f64::NAN];
+        assert_eq!(
+            parallel_float_product(data, FloatPolicy::Error),
+            Err(NonFiniteError { index: 2 })
+        );
+    }
+
+    // Test that infinities are handled by the float policy
+    #[test]
+    fn test_float_policy() {
+        let data = vec![1.0, f64::INFINITY, 2.0, f64::NAN, f64::NEG_INFINITY];
+        let sum = |a, b| a + b;
+        assert!(
+            parallel_float_reduce(data.clone(), 0.0, sum, FloatPolicy::Propagate)
+                .unwrap()
+                .is_nan()
+        );
+        assert_eq!(
+            parallel_float_reduce(data.clone(), 0.0, sum, FloatPolicy::Skip),
+            Ok(3.0)
+        );
+        assert_eq!(
+            parallel_float_reduce(data, 0.0, sum, FloatPolicy::Error),
+            Err(NonFiniteError { index: 1 })
+        );
+    }
+
+    // Test that the named reductions skip and report infinities like NaN
+    #[test]
+    fn test_named_reductions_skip_infinities() {
+        let data = vec![2.0, f64::NEG_INFINITY, 5.0, f64::INFINITY];
+        assert_eq!(parallel_float_sum(data.clone(), FloatPolicy::Skip), Ok(7.0));
+        assert_eq!(parallel_float_min(data.clone(), FloatPolicy::Skip), Ok(2.0));
+        assert_eq!(parallel_float_max(data.clone(), FloatPolicy::Skip), Ok(5.0));
+        assert_eq!(
+            parallel_float_min(data, FloatPolicy::Error),
+            Err(NonFiniteError { index: 1 })
+        );
+    }
+
+    // Test that the deprecated names still resolve to the float policy
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_nan_policy() {
+        let policy: NanPolicy = NanPolicy::Ignore;
+        assert_eq!(policy, FloatPolicy::Skip);
+        let result: Result<f64, NanError> = parallel_float_sum(vec![1.0, f64::NAN], policy);
+        assert_eq!(result, Ok(1.0));
+    }
+
+    // Test that pairwise summation keeps the error small where a naive sum drifts
+    #[test]
+    fn test_pairwise_summation() {
+        let data = vec![0.1f32; 1_000_000];
+        let exact = 1_000_000.0 * f64::from(0.1f32);
+        let pairwise = parallel_float_sum_with_summation(
+            data.clone(),
+            FloatPolicy::Propagate,
+            Summation::Pairwise,
+        )
+        .unwrap();
+        assert!((f64::from(pairwise) - exact).abs() < 0.1, "{}", pairwise);
+        let naive = data.iter().fold(0.0f32, |a, &b| a + b);
+        assert!((f64::from(naive) - exact).abs() > 100.0);
+    }
+
+    // Test that pairwise summation honors the NaN policy
+    #[test]
+    fn test_pairwise_nan_policy() {
+        let mut data: Vec<f64> = (1..=1_000).map(f64::from).collect();
+        data[700] = f64::NAN;
+        let sum =
+            |policy| parallel_float_sum_with_summation(data.clone(), policy, Summation::Pairwise);
+        assert_eq!(sum(FloatPolicy::Skip), Ok(500_500.0 - 701.0));
+        assert_eq!(sum(FloatPolicy::Error), Err(NonFiniteError { index: 700 }));
+        assert!(sum(FloatPolicy::Propagate).unwrap().is_nan());
+    }
+}
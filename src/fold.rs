@@ -0,0 +1,235 @@
+use rayon::prelude::*;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Folds the data into an accumulator of a different type in parallel.
+///
+/// Each chunk is folded into its own copy of `identity` with `fold_op`, and the chunk
+/// accumulators are merged with `combine_op`. Keeping the two separate makes
+/// aggregations such as counting or averaging expressible, where adding an element to
+/// an accumulator is not the same as merging two accumulators.
+///
+/// # Parameters
+/// - `data`: The elements to fold.
+/// - `identity`: The empty accumulator, which must be neutral for `combine_op`.
+/// - `fold_op`: Adds one element to an accumulator.
+/// - `combine_op`: Merges two accumulators, the earlier chunk's first.
+///
+/// # Returns
+/// The combined accumulator, or `identity` for an empty slice.
+pub fn parallel_fold_combine<T, A>(
+    data: &[T],
+    identity: A,
+    fold_op: fn(A, T) -> A,
+    combine_op: fn(A, A) -> A,
+) -> A
+where
+    T: Copy + Sync,
+    A: Clone + Send + Sync,
+{
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    data.par_chunks(chunk_size)
+        .map(|chunk| chunk.iter().copied().fold(identity.clone(), fold_op))
+        .reduce(|| identity.clone(), combine_op)
+}
+
+/// Folds the data into an accumulator in parallel, passing each element's index.
+///
+/// Like [`parallel_fold_combine`], but `fold_op` also receives the element's position
+/// in `data`, so position-weighted sums, alternating series, and polynomial evaluation
+/// need no index-zipped copy of the input.
+///
+/// # Parameters
+/// - `data`: The elements to fold.
+/// - `identity`: The empty accumulator, which must be neutral for `combine_op`.
+/// - `fold_op`: Adds the element at an index to an accumulator.
+/// - `combine_op`: Merges two accumulators, the earlier chunk's first.
+///
+/// # Returns
+/// The combined accumulator, or `identity` for an empty slice.
+pub fn parallel_fold_indexed<T, A>(
+    data: &[T],
+    identity: A,
+    fold_op: fn(usize, T, A) -> A,
+    combine_op: fn(A, A) -> A,
+) -> A
+where
+    T: Copy + Sync,
+    A: Clone + Send + Sync,
+{
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    data.par_chunks(chunk_size)
+        .enumerate()
+        .map(|(chunk_index, chunk)| {
+            let start = chunk_index * chunk_size;
+            chunk
+                .iter()
+                .enumerate()
+                .fold(identity.clone(), |acc, (offset, &x)| {
+                    fold_op(start + offset, x, acc)
+                })
+        })
+        .reduce(|| identity.clone(), combine_op)
+}
+
+/// How many chunks [`parallel_fold_combine_weighted`] makes per thread, so that threads
+/// finishing early can steal the remaining ones when cost hints are inexact.
+const CHUNKS_PER_THREAD: usize = 4;
+
+/// Folds the data into an accumulator in parallel, balancing chunks by estimated cost.
+///
+/// Like [`parallel_fold_combine`], but for elements whose processing cost varies widely,
+/// such as strings or nested collections of different sizes. Instead of equal-length
+/// chunks, the slice is split into ranges of roughly equal total `cost`, several per
+/// thread, and rayon's work stealing evens out what the estimates miss. An element
+/// costlier than a whole range gets a range of its own.
+///
+/// # Parameters
+/// - `data`: The elements to fold.
+/// - `identity`: The empty accumulator, which must be neutral for `combine_op`.
+/// - `fold_op`: Adds one element to an accumulator.
+/// - `combine_op`: Merges two accumulators, the earlier range's first.
+/// - `cost`: Estimates the relative cost of folding an element, e.g. its length.
+///
+/// # Returns
+/// The combined accumulator, or `identity` for an empty slice.
+pub fn parallel_fold_combine_weighted<T, A, C>(
+    data: &[T],
+    identity: A,
+    fold_op: fn(A, &T) -> A,
+    combine_op: fn(A, A) -> A,
+    cost: C,
+) -> A
+where
+    T: Sync,
+    A: Clone + Send + Sync,
+    C: Fn(&T) -> usize + Sync,
+{
+    let costs: Vec<usize> = data.par_iter().map(|x| cost(x).max(1)).collect();
+    let total: usize = costs.iter().sum();
+    let target = total
+        .div_ceil(available_threads() * CHUNKS_PER_THREAD)
+        .max(1);
+
+    let mut ranges = Vec::new();
+    let (mut start, mut weight) = (0, 0);
+    for (index, &c) in costs.iter().enumerate() {
+        weight += c;
+        if weight >= target {
+            ranges.push(start..index + 1);
+            start = index + 1;
+            weight = 0;
+        }
+    }
+    if start < data.len() {
+        ranges.push(start..data.len());
+    }
+
+    ranges
+        .into_par_iter()
+        .map(|range| data[range].iter().fold(identity.clone(), fold_op))
+        .reduce(|| identity.clone(), combine_op)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test an average accumulated as (sum, count)
+    #[test]
+    fn test_fold_combine_mean() {
+        let data: Vec<u32> = (1..=1_000).collect();
+        let (sum, count) = parallel_fold_combine(
+            &data,
+            (0u64, 0usize),
+            |(sum, count), x| (sum + x as u64, count + 1),
+            |(s1, c1), (s2, c2)| (s1 + s2, c1 + c2),
+        );
+        assert_eq!((sum, count), (500_500, 1_000));
+    }
+
+    // Test counting matches, where folding and combining differ
+    #[test]
+    fn test_fold_combine_count() {
+        let data: Vec<i32> = (0..10_000).collect();
+        let evens = parallel_fold_combine(
+            &data,
+            0usize,
+            |count, x| count + usize::from(x % 2 == 0),
+            |a, b| a + b,
+        );
+        assert_eq!(evens, 5_000);
+        assert_eq!(
+            parallel_fold_combine(&[] as &[i32], 0usize, |c, _| c + 1, |a, b| a + b),
+            0
+        );
+    }
+
+    // Test a position-weighted sum and an alternating series
+    #[test]
+    fn test_fold_indexed() {
+        let data: Vec<u64> = vec![1; 10_000];
+        let weighted =
+            parallel_fold_indexed(&data, 0u64, |i, x, acc| acc + i as u64 * x, |a, b| a + b);
+        assert_eq!(weighted, 9_999 * 10_000 / 2);
+
+        let ones: Vec<f64> = vec![1.0; 100_000];
+        let ln2 = parallel_fold_indexed(
+            &ones,
+            0.0,
+            |i, x, acc| {
+                let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+                acc + sign * x / (i + 1) as f64
+            },
+            |a, b| a + b,
+        );
+        assert!((ln2 - std::f64::consts::LN_2).abs() < 1e-5);
+        assert_eq!(
+            parallel_fold_indexed(&[] as &[u8], 7u8, |_, x, acc| acc + x, |a, b| a + b),
+            7
+        );
+    }
+
+    // Test folding strings of wildly different lengths, weighted by length
+    #[test]
+    fn test_fold_combine_weighted_strings() {
+        let words: Vec<String> = (0..2_000)
+            .map(|i| "x".repeat(if i % 100 == 0 { 10_000 } else { i % 7 }))
+            .collect();
+        let expected: usize = words.iter().map(String::len).sum();
+        let (chars, count) = parallel_fold_combine_weighted(
+            &words,
+            (0usize, 0usize),
+            |(chars, count), w| (chars + w.chars().count(), count + 1),
+            |(c1, n1), (c2, n2)| (c1 + c2, n1 + n2),
+            String::len,
+        );
+        assert_eq!((chars, count), (expected, 2_000));
+    }
+
+    // Test that range order is kept and empty input yields the identity
+    #[test]
+    fn test_fold_combine_weighted_order() {
+        let data: Vec<u32> = (0..500).collect();
+        let concatenated = parallel_fold_combine_weighted(
+            &data,
+            Vec::new(),
+            |mut acc, &x| {
+                acc.push(x);
+                acc
+            },
+            |mut a, b| {
+                a.extend(b);
+                a
+            },
+            |&x| if x % 50 == 0 { 1_000 } else { 0 },
+        );
+        assert_eq!(concatenated, data);
+        let empty: &[u32] = &[];
+        assert_eq!(
+            parallel_fold_combine_weighted(empty, 0u32, |a, &b| a + b, |a, b| a + b, |_| 1),
+            0
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Add;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Per-key aggregates computed by [`parallel_group_stats`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GroupStats<T> {
+    /// The number of values with this key.
+    pub count: usize,
+    /// The sum of the values.
+    pub sum: T,
+    /// The smallest value.
+    pub min: T,
+    /// The largest value.
+    pub max: T,
+}
+
+impl<T> GroupStats<T>
+where
+    T: Copy + PartialOrd + Add<Output = T>,
+{
+    /// Starts the aggregates of a group from its first value.
+    fn new(x: T) -> Self {
+        GroupStats {
+            count: 1,
+            sum: x,
+            min: x,
+            max: x,
+        }
+    }
+
+    /// Adds one value to the group.
+    fn push(&mut self, x: T) {
+        self.count += 1;
+        self.sum = self.sum + x;
+        if x < self.min {
+            self.min = x;
+        }
+        if x > self.max {
+            self.max = x;
+        }
+    }
+
+    /// Merges the aggregates of the same group from another chunk.
+    fn merge(&mut self, other: GroupStats<T>) {
+        self.count += other.count;
+        self.sum = self.sum + other.sum;
+        if other.min < self.min {
+            self.min = other.min;
+        }
+        if other.max > self.max {
+            self.max = other.max;
+        }
+    }
+
+    /// Returns the arithmetic mean of the group's values.
+    pub fn mean(&self) -> f64
+    where
+        T: Into<f64>,
+    {
+        self.sum.into() / self.count as f64
+    }
+}
+
+/// Computes count, sum, min, max, and mean per key in a single parallel pass.
+///
+/// Each chunk of `pairs` is aggregated into its own local map, and the local maps are
+/// merged pairwise at the end, always folding the smaller map into the larger one. No
+/// lock is shared between threads, which makes this a small group-by engine for
+/// key/value records such as parsed log lines. Min and max ignore values that do not
+/// compare (such as NaN) unless they come first in a chunk.
+///
+/// # Parameters
+/// - `pairs`: The `(key, value)` records to group.
+///
+/// # Returns
+/// The aggregates of every key that occurs in `pairs`.
+pub fn parallel_group_stats<K, T>(pairs: &[(K, T)]) -> HashMap<K, GroupStats<T>>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    T: Copy + Send + Sync + PartialOrd + Add<Output = T>,
+{
+    if pairs.is_empty() {
+        return HashMap::new();
+    }
+    pairs
+        .par_chunks(aligned_chunk_size(pairs, available_threads()))
+        .map(|chunk| {
+            let mut groups: HashMap<K, GroupStats<T>> = HashMap::new();
+            for (key, x) in chunk {
+                match groups.get_mut(key) {
+                    Some(stats) => stats.push(*x),
+                    None => {
+                        groups.insert(key.clone(), GroupStats::new(*x));
+                    }
+                }
+            }
+            groups
+        })
+        .reduce_with(|a, b| {
+            let (mut into, from) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+            for (key, stats) in from {
+                match into.get_mut(&key) {
+                    Some(existing) => existing.merge(stats),
+                    None => {
+                        into.insert(key, stats);
+                    }
+                }
+            }
+            into
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test per-key aggregates against a sequential computation
+    #[test]
+    fn test_group_stats() {
+        let pairs: Vec<(u32, u64)> = (0..100_000u64).map(|i| ((i % 7) as u32, i)).collect();
+        let groups = parallel_group_stats(&pairs);
+        assert_eq!(groups.len(), 7);
+        for (&key, stats) in &groups {
+            let values: Vec<u64> = pairs
+                .iter()
+                .filter(|(k, _)| *k == key)
+                .map(|&(_, v)| v)
+                .collect();
+            assert_eq!(stats.count, values.len());
+            assert_eq!(stats.sum, values.iter().sum::<u64>());
+            assert_eq!(stats.min, *values.iter().min().unwrap());
+            assert_eq!(stats.max, *values.iter().max().unwrap());
+        }
+    }
+
+    // Test string keys and the mean, as for log analytics
+    #[test]
+    fn test_group_stats_mean() {
+        let pairs = vec![
+            ("GET".to_string(), 12.0f64),
+            ("POST".to_string(), 30.0),
+            ("GET".to_string(), 18.0),
+        ];
+        let groups = parallel_group_stats(&pairs);
+        assert_eq!(groups["GET"].count, 2);
+        assert_eq!(groups["GET"].mean(), 15.0);
+        assert_eq!(groups["POST"].min, 30.0);
+    }
+
+    // Test for no records
+    #[test]
+    fn test_group_stats_empty() {
+        assert!(parallel_group_stats::<u8, i32>(&[]).is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+use rayon::prelude::*;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// An IEEE 754 binary16 half-precision float: 1 sign, 5 exponent and 10 mantissa bits.
+///
+/// A storage type only; reductions convert each element to `f32` and accumulate there.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[repr(transparent)]
+#[allow(non_camel_case_types)]
+pub struct f16(u16);
+
+/// A bfloat16 float: the upper 16 bits of an `f32` (1 sign, 8 exponent and 7 mantissa bits).
+///
+/// A storage type only; reductions convert each element to `f32` and accumulate there.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[repr(transparent)]
+#[allow(non_camel_case_types)]
+pub struct bf16(u16);
+
+/// Half-precision element types accepted by the half reductions.
+///
+/// Implemented for [`f16`](crate::f16) and [`bf16`].
+pub trait HalfFloat: Copy + Send + Sync {
+    /// Converts the value to `f32`, exactly.
+    fn to_f32(self) -> f32;
+}
+
+impl f16 {
+    /// Creates a value from its raw bits.
+    pub const fn from_bits(bits: u16) -> Self {
+        f16(bits)
+    }
+
+    /// Returns the raw bits.
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Converts an `f32`, rounding to nearest with ties to even.
+    ///
+    /// Values too large for `f16` become infinite and values too small become zero.
+    pub fn from_f32(value: f32) -> Self {
+        let bits = value.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exponent = ((bits >> 23) & 0xff) as i32;
+        let mantissa = bits & 0x7f_ffff;
+
+        if exponent == 0xff {
+            // Infinity, or NaN kept quiet with the high mantissa bits it carried
+            let nan = if mantissa != 0 {
+                0x200 | (mantissa >> 13) as u16
+            } else {
+                0
+            };
+            return f16(sign | 0x7c00 | nan);
+        }
+        let exponent = exponent - 127 + 15;
+        if exponent >= 0x1f {
+            return f16(sign | 0x7c00);
+        }
+        if exponent <= 0 {
+            if exponent < -10 {
+                return f16(sign);
+            }
+            // Subnormal: shift the mantissa, implicit bit included, into place
+            let mantissa = mantissa | 0x80_0000;
+            let shift = (14 - exponent) as u32;
+            return f16(sign | round_shifted(mantissa, shift) as u16);
+        }
+        // A rounding carry out of the mantissa correctly bumps the exponent
+        let bits = ((exponent as u32) << 10) | (mantissa >> 13);
+        f16(sign | round_tail(bits, mantissa & 0x1fff, 0x1000) as u16)
+    }
+}
+
+impl HalfFloat for f16 {
+    fn to_f32(self) -> f32 {
+        let sign = u32::from(self.0 & 0x8000) << 16;
+        let exponent = u32::from((self.0 >> 10) & 0x1f);
+        let mantissa = u32::from(self.0 & 0x3ff);
+        let bits = match exponent {
+            0 => {
+                // Zero or subnormal: mantissa * 2^-24 is exact in f32
+                let magnitude = mantissa as f32 / 16_777_216.0;
+                return f32::from_bits(sign | magnitude.to_bits());
+            }
+            0x1f => sign | 0x7f80_0000 | (mantissa << 13),
+            _ => sign | ((exponent + 112) << 23) | (mantissa << 13),
+        };
+        f32::from_bits(bits)
+    }
+}
+
+impl bf16 {
+    /// Creates a value from its raw bits.
+    pub const fn from_bits(bits: u16) -> Self {
+        bf16(bits)
+    }
+
+    /// Returns the raw bits.
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Converts an `f32`, rounding to nearest with ties to even.
+    pub fn from_f32(value: f32) -> Self {
+        let bits = value.to_bits();
+        if value.is_nan() {
+            // Truncating could clear every mantissa bit left; force a quiet NaN
+            return bf16(((bits >> 16) as u16) | 0x40);
+        }
+        bf16((round_tail(bits >> 16, bits & 0xffff, 0x8000)) as u16)
+    }
+}
+
+impl HalfFloat for bf16 {
+    fn to_f32(self) -> f32 {
+        f32::from_bits(u32::from(self.0) << 16)
+    }
+}
+
+impl From<f16> for f32 {
+    fn from(value: f16) -> f32 {
+        value.to_f32()
+    }
+}
+
+impl From<bf16> for f32 {
+    fn from(value: bf16) -> f32 {
+        value.to_f32()
+    }
+}
+
+/// Rounds `value >> shift` to nearest, ties to even.
+fn round_shifted(value: u32, shift: u32) -> u32 {
+    round_tail(value >> shift, value & ((1 << shift) - 1), 1 << (shift - 1))
+}
+
+/// Rounds `kept` up if the dropped `tail` is above `half`, or equal to it and `kept` is odd.
+fn round_tail(kept: u32, tail: u32, half: u32) -> u32 {
+    if tail > half || (tail == half && kept & 1 == 1) {
+        kept + 1
+    } else {
+        kept
+    }
+}
+
+/// Folds each chunk of converted values with `fold`, then folds the partials the same way.
+fn reduce_f32<T: HalfFloat>(data: &[T], identity: f32, fold: fn(f32, f32) -> f32) -> f32 {
+    if data.is_empty() {
+        return identity;
+    }
+    data.par_chunks(aligned_chunk_size(data, available_threads()))
+        .map(|chunk| chunk.iter().fold(identity, |acc, &x| fold(acc, x.to_f32())))
+        .reduce(|| identity, fold)
+}
+
+/// Computes the sum of half-precision values in parallel, accumulating in `f32`.
+///
+/// # Parameters
+/// - `data`: The values.
+///
+/// # Returns
+/// The sum as an `f32`; `0.0` for an empty slice.
+pub fn parallel_half_sum<T: HalfFloat>(data: &[T]) -> f32 {
+    reduce_f32(data, 0.0, |a, b| a + b)
+}
+
+/// Computes the mean of half-precision values in parallel, accumulating in `f32`.
+///
+/// # Returns
+/// The mean as an `f32`, or `None` for an empty slice.
+pub fn parallel_half_mean<T: HalfFloat>(data: &[T]) -> Option<f32> {
+    if data.is_empty() {
+        return None;
+    }
+    Some(parallel_half_sum(data) / data.len() as f32)
+}
+
+/// Finds the minimum of half-precision values in parallel, ignoring NaN.
+///
+/// # Returns
+/// The minimum as an `f32`, or `None` if the slice is empty or all NaN.
+pub fn parallel_half_min<T: HalfFloat>(data: &[T]) -> Option<f32> {
+    Some(reduce_f32(data, f32::INFINITY, f32::min)).filter(|_| has_number(data))
+}
+
+/// Finds the maximum of half-precision values in parallel, ignoring NaN.
+///
+/// # Returns
+/// The maximum as an `f32`, or `None` if the slice is empty or all NaN.
+pub fn parallel_half_max<T: HalfFloat>(data: &[T]) -> Option<f32> {
+    Some(reduce_f32(data, f32::NEG_INFINITY, f32::max)).filter(|_| has_number(data))
+}
+
+/// Returns `true` if any value is not NaN.
+fn has_number<T: HalfFloat>(data: &[T]) -> bool {
+    data.par_iter().any(|&x| !x.to_f32().is_nan())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test conversions of normal, subnormal, overflowing, and special values
+    #[test]
+    fn test_f16_conversion() {
+        for value in [
+            0.0,
+            -0.0,
+            1.0,
+            -2.5,
+            65504.0,
+            6.103_515_6e-5,
+            5.960_464_5e-8,
+        ] {
+            assert_eq!(f16::from_f32(value).to_f32().to_bits(), value.to_bits());
+        }
+        assert_eq!(f16::from_f32(1.0).to_bits(), 0x3c00);
+        assert_eq!(f16::from_f32(1e6).to_f32(), f32::INFINITY);
+        assert_eq!(f16::from_f32(1e-9).to_f32(), 0.0);
+        assert!(f16::from_f32(f32::NAN).to_f32().is_nan());
+        // 1 + 2^-11 is halfway between 1 and the next f16 and rounds to even
+        assert_eq!(f16::from_f32(1.0 + 2f32.powi(-11)).to_bits(), 0x3c00);
+        assert_eq!(f16::from_f32(1.0 + 3.0 * 2f32.powi(-11)).to_bits(), 0x3c02);
+    }
+
+    // Test bfloat16 conversions
+    #[test]
+    fn test_bf16_conversion() {
+        for value in [0.0, 1.0, -3.0, f32::INFINITY, 2f32.powi(100)] {
+            assert_eq!(bf16::from_f32(value).to_f32(), value);
+        }
+        assert_eq!(bf16::from_f32(1.0).to_bits(), 0x3f80);
+        assert_eq!(bf16::from_f32(1.0 + 2f32.powi(-8)).to_f32(), 1.0);
+        assert!(bf16::from_f32(f32::NAN).to_f32().is_nan());
+    }
+
+    // Test the reductions on both formats
+    #[test]
+    fn test_half_reductions() {
+        let values: Vec<f16> = (0..10_000)
+            .map(|i| f16::from_f32((i % 100) as f32))
+            .collect();
+        assert_eq!(parallel_half_sum(&values), 495_000.0);
+        assert_eq!(parallel_half_mean(&values), Some(49.5));
+        assert_eq!(parallel_half_min(&values), Some(0.0));
+        assert_eq!(parallel_half_max(&values), Some(99.0));
+
+        let mut bf: Vec<bf16> = [-1.0, 2.0, 0.5].map(bf16::from_f32).to_vec();
+        bf.push(bf16::from_f32(f32::NAN));
+        assert_eq!(parallel_half_min(&bf), Some(-1.0));
+        assert_eq!(parallel_half_max(&bf), Some(2.0));
+        assert_eq!(parallel_half_mean::<bf16>(&[]), None);
+        assert_eq!(parallel_half_max(&[bf16::from_f32(f32::NAN)]), None);
+    }
+}
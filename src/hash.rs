@@ -0,0 +1,270 @@
+use rayon::prelude::*;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Checksum and hash algorithms [`parallel_hash`] supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    /// XXH64 with seed 0.
+    ///
+    /// Inputs of up to 1 MiB hash to the standard XXH64 digest. XXH64 has no way to
+    /// combine the digests of two halves, so longer inputs are hashed as a tree: every
+    /// 1 MiB block is hashed in parallel, and the little-endian block digests are hashed
+    /// again, seeded with the input length. The result is independent of the thread count
+    /// but differs from a one-shot XXH64 of the whole input.
+    XxHash64,
+    /// CRC-32 (IEEE 802.3, as used by zlib, gzip, and PNG).
+    ///
+    /// Chunk checksums are merged with zlib's `crc32_combine`, so the result always
+    /// equals the standard CRC-32 of the whole input.
+    Crc32,
+}
+
+/// Size of the blocks a long input is split into for [`HashAlgorithm::XxHash64`].
+const XXH_BLOCK: usize = 1 << 20;
+
+/// Hashes a byte buffer in parallel, for fast integrity checks.
+///
+/// # Parameters
+/// - `data`: The bytes to hash.
+/// - `algorithm`: The hash or checksum to compute.
+///
+/// # Returns
+/// The digest; a CRC-32 occupies the low 32 bits.
+pub fn parallel_hash(data: &[u8], algorithm: HashAlgorithm) -> u64 {
+    match algorithm {
+        HashAlgorithm::XxHash64 if data.len() <= XXH_BLOCK => xxh64(data, 0),
+        HashAlgorithm::XxHash64 => {
+            let digests: Vec<u8> = data
+                .par_chunks(XXH_BLOCK)
+                .flat_map_iter(|block| xxh64(block, 0).to_le_bytes())
+                .collect();
+            xxh64(&digests, data.len() as u64)
+        }
+        HashAlgorithm::Crc32 => {
+            if data.is_empty() {
+                return 0;
+            }
+            let (crc, _) = data
+                .par_chunks(aligned_chunk_size(data, available_threads()))
+                .map(|chunk| (crc32(chunk), chunk.len() as u64))
+                .reduce(
+                    || (0, 0),
+                    |(crc1, len1), (crc2, len2)| (crc32_combine(crc1, crc2, len2), len1 + len2),
+                );
+            u64::from(crc)
+        }
+    }
+}
+
+const PRIME64_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME64_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+fn xxh64_round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(PRIME64_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME64_1)
+}
+
+fn xxh64_merge_round(acc: u64, value: u64) -> u64 {
+    (acc ^ xxh64_round(0, value))
+        .wrapping_mul(PRIME64_1)
+        .wrapping_add(PRIME64_4)
+}
+
+/// Computes the XXH64 digest of `input` sequentially.
+fn xxh64(input: &[u8], seed: u64) -> u64 {
+    let mut stripes = input.chunks_exact(32);
+    let mut h = if input.len() >= 32 {
+        let mut v = [
+            seed.wrapping_add(PRIME64_1).wrapping_add(PRIME64_2),
+            seed.wrapping_add(PRIME64_2),
+            seed,
+            seed.wrapping_sub(PRIME64_1),
+        ];
+        for stripe in &mut stripes {
+            for (lane, word) in v.iter_mut().zip(stripe.chunks_exact(8)) {
+                *lane = xxh64_round(*lane, read_u64(word));
+            }
+        }
+        let h = v[0]
+            .rotate_left(1)
+            .wrapping_add(v[1].rotate_left(7))
+            .wrapping_add(v[2].rotate_left(12))
+            .wrapping_add(v[3].rotate_left(18));
+        v.iter().fold(h, |h, &lane| xxh64_merge_round(h, lane))
+    } else {
+        seed.wrapping_add(PRIME64_5)
+    };
+    h = h.wrapping_add(input.len() as u64);
+
+    let mut rest = stripes.remainder();
+    while rest.len() >= 8 {
+        h ^= xxh64_round(0, read_u64(rest));
+        h = h
+            .rotate_left(27)
+            .wrapping_mul(PRIME64_1)
+            .wrapping_add(PRIME64_4);
+        rest = &rest[8..];
+    }
+    if rest.len() >= 4 {
+        h ^= u64::from(read_u32(rest)).wrapping_mul(PRIME64_1);
+        h = h
+            .rotate_left(23)
+            .wrapping_mul(PRIME64_2)
+            .wrapping_add(PRIME64_3);
+        rest = &rest[4..];
+    }
+    for &byte in rest {
+        h ^= u64::from(byte).wrapping_mul(PRIME64_5);
+        h = h.rotate_left(11).wrapping_mul(PRIME64_1);
+    }
+
+    h ^= h >> 33;
+    h = h.wrapping_mul(PRIME64_2);
+    h ^= h >> 29;
+    h = h.wrapping_mul(PRIME64_3);
+    h ^ (h >> 32)
+}
+
+/// The reflected CRC-32 polynomial.
+const CRC32_POLY: u32 = 0xEDB8_8320;
+
+/// Lookup table for byte-at-a-time CRC-32.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ CRC32_POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Computes the CRC-32 of `bytes` sequentially.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        CRC32_TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Multiplies a 32x32 matrix over GF(2) by a vector.
+fn gf2_times(matrix: &[u32; 32], mut vector: u32) -> u32 {
+    let mut sum = 0;
+    let mut row = 0;
+    while vector != 0 {
+        if vector & 1 != 0 {
+            sum ^= matrix[row];
+        }
+        vector >>= 1;
+        row += 1;
+    }
+    sum
+}
+
+/// Squares a 32x32 matrix over GF(2).
+fn gf2_square(matrix: &[u32; 32]) -> [u32; 32] {
+    let mut square = [0; 32];
+    for (row, &value) in square.iter_mut().zip(matrix) {
+        *row = gf2_times(matrix, value);
+    }
+    square
+}
+
+/// Computes the CRC-32 of two concatenated buffers from their CRCs and the second length.
+///
+/// This is zlib's `crc32_combine`: the first CRC is advanced over `len2` zero bytes by
+/// repeatedly squaring the one-zero-bit operator, then the second CRC is xored in.
+fn crc32_combine(crc1: u32, crc2: u32, mut len2: u64) -> u32 {
+    if len2 == 0 {
+        return crc1;
+    }
+    // The operator for one zero bit
+    let mut odd = [0u32; 32];
+    odd[0] = CRC32_POLY;
+    for (n, row) in odd.iter_mut().enumerate().skip(1) {
+        *row = 1 << (n - 1);
+    }
+    // Operators for two and four zero bits; the loop starts at one zero byte
+    let even = gf2_square(&odd);
+    let mut operator = gf2_square(&even);
+
+    let mut crc = crc1;
+    loop {
+        operator = gf2_square(&operator);
+        if len2 & 1 != 0 {
+            crc = gf2_times(&operator, crc);
+        }
+        len2 >>= 1;
+        if len2 == 0 {
+            break;
+        }
+    }
+    crc ^ crc2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test against published check values
+    #[test]
+    fn test_hash_known_values() {
+        assert_eq!(
+            parallel_hash(b"123456789", HashAlgorithm::Crc32),
+            0xCBF4_3926
+        );
+        assert_eq!(
+            parallel_hash(b"", HashAlgorithm::XxHash64),
+            0xEF46_DB37_51D8_E999
+        );
+        assert_eq!(
+            parallel_hash(b"abc", HashAlgorithm::XxHash64),
+            0x44BC_2CF5_AD77_0999
+        );
+    }
+
+    // Test that combined chunk CRCs equal the CRC of the whole buffer
+    #[test]
+    fn test_crc32_combine() {
+        let data: Vec<u8> = (0..100_003u32).map(|i| (i * 31 % 251) as u8).collect();
+        let whole = crc32(&data);
+        assert_eq!(parallel_hash(&data, HashAlgorithm::Crc32), u64::from(whole));
+        for split in [0, 1, 4_096, 99_999] {
+            let (a, b) = data.split_at(split);
+            assert_eq!(crc32_combine(crc32(a), crc32(b), b.len() as u64), whole);
+        }
+    }
+
+    // Test that the XXH64 tree hash is stable and sensitive to every block
+    #[test]
+    fn test_xxh64_tree() {
+        let mut data = vec![0u8; XXH_BLOCK * 2 + 17];
+        let digest = parallel_hash(&data, HashAlgorithm::XxHash64);
+        assert_eq!(parallel_hash(&data, HashAlgorithm::XxHash64), digest);
+        data[XXH_BLOCK + 5] = 1;
+        assert_ne!(parallel_hash(&data, HashAlgorithm::XxHash64), digest);
+    }
+}
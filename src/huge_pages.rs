@@ -0,0 +1,80 @@
+//! Transparent huge pages for large buffers (Linux).
+//!
+//! A multi-gigabyte buffer mapped in 4 KiB pages needs hundreds of thousands of TLB
+//! entries, and a parallel pass over it misses the TLB constantly. Advising the kernel
+//! with `madvise(MADV_HUGEPAGE)` before the buffer is first written lets it back the
+//! buffer with 2 MiB pages instead, when transparent huge pages are set to `madvise` or
+//! `always`.
+
+use std::mem;
+
+/// Size of an x86-64 and AArch64 (4 KiB granule) transparent huge page.
+const HUGE_PAGE: usize = 2 << 20;
+
+/// Allocates an empty vector with room for `capacity` elements, advised to be backed
+/// by huge pages.
+///
+/// Only the 2 MiB-aligned part of the allocation can use huge pages, so this matters for
+/// buffers of several megabytes and more.
+pub fn huge_page_vec<T>(capacity: usize) -> Vec<T> {
+    let mut buffer = Vec::with_capacity(capacity);
+    advise_huge_pages(&mut buffer);
+    buffer
+}
+
+/// Advises the kernel to back the allocation of `buffer` with huge pages.
+///
+/// Pages already touched keep their size until the kernel's background compaction
+/// reaches them, so advise a buffer before filling it.
+///
+/// # Returns
+/// `true` if the kernel accepted the advice, `false` if the allocation spans no whole
+/// huge page or transparent huge pages are disabled.
+pub fn advise_huge_pages<T>(buffer: &mut Vec<T>) -> bool {
+    let start = buffer.as_mut_ptr() as usize;
+    let end = start + buffer.capacity() * mem::size_of::<T>();
+    let (first, last) = (
+        start.next_multiple_of(HUGE_PAGE),
+        end / HUGE_PAGE * HUGE_PAGE,
+    );
+    if last <= first {
+        return false;
+    }
+    // SAFETY: the range lies inside the vector's allocation, and MADV_HUGEPAGE only
+    // changes how it is backed, never its contents
+    unsafe {
+        libc::madvise(
+            first as *mut libc::c_void,
+            last - first,
+            libc::MADV_HUGEPAGE,
+        ) == 0
+    }
+}
+
+/// Advises `buffer` if it is large enough to hold a huge page.
+pub(crate) fn advise_if_large<T>(buffer: &mut Vec<T>) {
+    if buffer.capacity() * mem::size_of::<T>() >= 2 * HUGE_PAGE {
+        advise_huge_pages(buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that a large advised buffer is used normally
+    #[test]
+    fn test_huge_page_vec() {
+        let mut buffer: Vec<u64> = huge_page_vec(2_000_000);
+        assert!(buffer.capacity() >= 2_000_000);
+        buffer.extend(0..2_000_000);
+        assert_eq!(buffer.iter().sum::<u64>(), 1_999_999_000_000);
+    }
+
+    // Test that buffers smaller than a huge page are left alone
+    #[test]
+    fn test_advise_small() {
+        assert!(!advise_huge_pages(&mut Vec::<u8>::with_capacity(4_096)));
+        assert!(!advise_huge_pages(&mut Vec::<u64>::new()));
+    }
+}
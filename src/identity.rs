@@ -0,0 +1,226 @@
+use std::borrow::Cow;
+
+use crate::error::catch_panic;
+use crate::log;
+use crate::profile::reduce_chunks;
+use crate::{plan_for, Error, ParallelConfig};
+
+/// Types that know their own identity element.
+///
+/// Implement this for aggregation structs (counts, running sums, maxima, ...) so they can
+/// be reduced with [`parallel_reduce`] without satisfying the numeric bounds of
+/// [`parallel_binary_operation`](crate::parallel_binary_operation).
+pub trait ReduceIdentity {
+    /// Returns the value `e` with `operation(e, x) == x` for the operations used with this type.
+    fn identity() -> Self;
+}
+
+/// Performs a parallel binary operation on a type implementing [`ReduceIdentity`].
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or `T::identity()` for an
+/// empty vector.
+pub fn parallel_reduce<T>(data: Vec<T>, operation: fn(T, T) -> T) -> T
+where
+    T: ReduceIdentity + Copy + Send + Sync,
+{
+    parallel_reduce_with_identity(data, T::identity(), operation)
+}
+
+/// Performs a parallel binary operation with an explicitly supplied identity.
+///
+/// Unlike [`parallel_binary_operation`](crate::parallel_binary_operation), the identity is
+/// not probed from the operation, so element types without `From<u8>`, `PartialEq`, or
+/// `Default` (complex numbers, matrices, user structs) can be reduced.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or `identity` for an empty vector.
+pub fn parallel_reduce_with_identity<T>(data: Vec<T>, identity: T, operation: fn(T, T) -> T) -> T
+where
+    T: Copy + Send + Sync,
+{
+    reduce_slice_with_identity(&data, identity, operation)
+}
+
+/// Performs a parallel binary operation with an explicit identity on borrowed or owned data.
+///
+/// Like [`parallel_reduce_with_identity`], for callers that hold a `Cow` and would
+/// otherwise clone borrowed data into a `Vec` just to pass it in.
+///
+/// # Parameters
+/// - `data`: The elements to operate on, borrowed or owned.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or `identity` for no elements.
+pub fn parallel_reduce_with_identity_cow<T>(
+    data: Cow<'_, [T]>,
+    identity: T,
+    operation: fn(T, T) -> T,
+) -> T
+where
+    T: Copy + Send + Sync,
+{
+    reduce_slice_with_identity(&data, identity, operation)
+}
+
+/// Performs a parallel binary operation with an explicit identity, reporting a panic in
+/// the operation as an error.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, `identity` for an empty vector,
+/// or [`Error::OperationPanicked`].
+pub fn try_parallel_reduce_with_identity<T>(
+    data: Vec<T>,
+    identity: T,
+    operation: fn(T, T) -> T,
+) -> Result<T, Error>
+where
+    T: Copy + Send + Sync,
+{
+    catch_panic(|| reduce_slice_with_identity(&data, identity, operation))
+}
+
+/// Reduces a borrowed slice with an explicit identity, using the tuned configuration.
+pub(crate) fn reduce_slice_with_identity<T>(data: &[T], identity: T, operation: fn(T, T) -> T) -> T
+where
+    T: Copy + Send + Sync,
+{
+    reduce_slice_with_config(data, identity, operation, &ParallelConfig::tuned::<T>())
+}
+
+/// Reduces a borrowed slice with an explicit identity and configuration.
+pub(crate) fn reduce_slice_with_config<T>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+    config: &ParallelConfig,
+) -> T
+where
+    T: Copy + Send + Sync,
+{
+    if data.is_empty() {
+        return identity;
+    }
+
+    let plan = plan_for::<T>(data.len(), config);
+    log::log_plan(&plan);
+    let fold = |chunk: &[T]| chunk.iter().copied().fold(identity, operation);
+
+    if plan.sequential {
+        return fold(data);
+    }
+    config.install(|| reduce_chunks(data, plan.chunk_size, identity, operation, fold))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Complex {
+        re: f64,
+        im: f64,
+    }
+
+    fn complex_mul(a: Complex, b: Complex) -> Complex {
+        Complex {
+            re: a.re * b.re - a.im * b.im,
+            im: a.re * b.im + a.im * b.re,
+        }
+    }
+
+    // Test with a type that cannot be probed for an identity
+    #[test]
+    fn test_identity_complex_product() {
+        let i = Complex { re: 0.0, im: 1.0 };
+        let one = Complex { re: 1.0, im: 0.0 };
+        // i^4 == 1
+        let result = parallel_reduce_with_identity(vec![i; 4], one, complex_mul);
+        assert_eq!(result, one);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Stats {
+        count: u64,
+        sum: i64,
+        max: i64,
+    }
+
+    impl ReduceIdentity for Stats {
+        fn identity() -> Self {
+            Stats {
+                count: 0,
+                sum: 0,
+                max: i64::MIN,
+            }
+        }
+    }
+
+    fn merge(a: Stats, b: Stats) -> Stats {
+        Stats {
+            count: a.count + b.count,
+            sum: a.sum + b.sum,
+            max: a.max.max(b.max),
+        }
+    }
+
+    // Test with a user-defined aggregation struct
+    #[test]
+    fn test_reduce_identity_struct() {
+        let data: Vec<Stats> = (1..=100)
+            .map(|x| Stats {
+                count: 1,
+                sum: x,
+                max: x,
+            })
+            .collect();
+        let result = parallel_reduce(data, merge);
+        assert_eq!(
+            result,
+            Stats {
+                count: 100,
+                sum: 5050,
+                max: 100
+            }
+        );
+    }
+
+    // Test that an empty vector yields the identity
+    #[test]
+    fn test_reduce_identity_empty() {
+        assert_eq!(parallel_reduce(Vec::new(), merge), Stats::identity());
+    }
+
+    // Test that borrowed and owned data give the same result
+    #[test]
+    fn test_identity_cow() {
+        let data: Vec<u32> = (1..=1_000).collect();
+        let borrowed = parallel_reduce_with_identity_cow(Cow::Borrowed(&data[..]), 0, |a, b| a + b);
+        let owned = parallel_reduce_with_identity_cow(Cow::Owned(data), 0, |a, b| a + b);
+        assert_eq!(borrowed, 500_500);
+        assert_eq!(owned, borrowed);
+    }
+
+    // Test for empty vector
+    #[test]
+    fn test_identity_empty() {
+        let result = parallel_reduce_with_identity(Vec::<i32>::new(), i32::MAX, i32::min);
+        assert_eq!(result, i32::MAX);
+    }
+}
@@ -0,0 +1,101 @@
+use rayon::prelude::*;
+
+/// Elements per block that is reduced on one thread before the levels across blocks.
+///
+/// A power of two, so the blocks' trees are exactly the lower levels of the whole tree.
+const BLOCK: usize = 4096;
+
+/// Smallest number of pairs a task combines on the levels above the blocks.
+const MIN_PAIRS_PER_TASK: usize = 64;
+
+/// Reduces a buffer in place as a binary tree, overwriting it.
+///
+/// Level by level, element `j` is combined with element `j + stride` into slot `j`, with
+/// the stride doubling until one value is left, so no per-chunk partial results are
+/// allocated. The lower levels run block by block, each block of 4096 elements reduced
+/// on one thread while it is in cache; only the upper levels, over the blocks' results,
+/// are spread across threads pairwise. Operands are always combined left with right, so
+/// `operation` only needs to be associative.
+///
+/// # Parameters
+/// - `data`: The elements to reduce; afterwards it holds only the result.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or `None` for an empty vector.
+pub fn parallel_reduce_in_place<T>(data: &mut Vec<T>, operation: fn(T, T) -> T) -> Option<T>
+where
+    T: Copy + Send + Sync,
+{
+    if data.is_empty() {
+        return None;
+    }
+
+    data.par_chunks_mut(BLOCK)
+        .for_each(|block| reduce_levels(block, 1, operation));
+
+    let mut stride = BLOCK;
+    while stride < data.len() {
+        data.par_chunks_mut(2 * stride)
+            .with_min_len(MIN_PAIRS_PER_TASK)
+            .for_each(|pair| combine_pair(pair, stride, operation));
+        stride *= 2;
+    }
+
+    data.truncate(1);
+    Some(data[0])
+}
+
+/// Runs the tree levels from `stride` upwards on one thread, leaving the result in `data[0]`.
+fn reduce_levels<T: Copy>(data: &mut [T], mut stride: usize, operation: fn(T, T) -> T) {
+    while stride < data.len() {
+        for pair in data.chunks_mut(2 * stride) {
+            combine_pair(pair, stride, operation);
+        }
+        stride *= 2;
+    }
+}
+
+/// Combines the value at `pair[0]` with the one `stride` elements later, if there is one.
+#[inline]
+fn combine_pair<T: Copy>(pair: &mut [T], stride: usize, operation: fn(T, T) -> T) {
+    if let Some(&right) = pair.get(stride) {
+        pair[0] = operation(pair[0], right);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that the in-place sum matches a sequential sum and consumes the buffer
+    #[test]
+    fn test_reduce_in_place_sum() {
+        for len in [1, 2, 3, 4_095, 4_097, 100_003] {
+            let mut data: Vec<u64> = (0..len).collect();
+            let result = parallel_reduce_in_place(&mut data, |a, b| a + b);
+            assert_eq!(result, Some(len * (len - 1) / 2));
+            assert_eq!(data.len(), 1);
+        }
+    }
+
+    // Test that operands are combined in order
+    #[test]
+    fn test_reduce_in_place_order() {
+        const P: u64 = 1_000_000_007;
+        // Digit concatenation as (value, 10^len) pairs: associative but not commutative
+        let op = |(a, pa): (u64, u64), (b, pb): (u64, u64)| ((a * pb + b) % P, (pa * pb) % P);
+        let mut data: Vec<(u64, u64)> = (0..20_000).map(|i| (i % 10, 10)).collect();
+        let expected = data.iter().copied().reduce(op);
+        assert_eq!(parallel_reduce_in_place(&mut data, op), expected);
+    }
+
+    // Test for an empty vector
+    #[test]
+    fn test_reduce_in_place_empty() {
+        assert_eq!(
+            parallel_reduce_in_place(&mut Vec::<i32>::new(), |a, b| a + b),
+            None
+        );
+    }
+}
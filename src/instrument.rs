@@ -0,0 +1,216 @@
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::error::catch_panic;
+use crate::platform::{available_threads, parallel_worthwhile, Stopwatch};
+use crate::{aligned_chunk_size, get_initial_value, probe_identity, Bounded, Error};
+
+/// Execution statistics collected while running a parallel reduction.
+///
+/// Returned by [`parallel_reduce_instrumented`] alongside the result, so callers can see
+/// how the work was split and where the time went.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionStats {
+    /// Number of chunks the input was split into.
+    pub chunk_count: usize,
+    /// Number of elements per chunk (the last chunk may be shorter).
+    pub chunk_size: usize,
+    /// Number of distinct worker threads that processed at least one chunk.
+    pub threads_used: usize,
+    /// Wall time spent folding each chunk, in chunk order.
+    pub chunk_times: Vec<Duration>,
+    /// Wall time spent combining the per-chunk results.
+    pub combine_time: Duration,
+    /// Number of times the operation was called while folding each chunk, in chunk order.
+    pub chunk_invocations: Vec<usize>,
+    /// Total number of times the operation was called, including the combine step.
+    ///
+    /// A parallel run over `n` elements in `c` chunks makes `n + c` calls (each chunk fold
+    /// starts from the identity) and a sequential one `n`; more means work was repeated.
+    pub invocations: usize,
+    /// Whether the reduction ran sequentially because there is only one core or worker
+    /// threads could not be started.
+    pub sequential_fallback: bool,
+}
+
+/// Performs a parallel binary operation and reports how it was executed.
+///
+/// Works like [`parallel_binary_operation`](crate::parallel_binary_operation), but
+/// additionally measures each chunk and the final combine step.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// A tuple of the reduction result and the [`ExecutionStats`] for the run.
+pub fn parallel_reduce_instrumented<T>(
+    data: Vec<T>,
+    operation: fn(T, T) -> T,
+) -> (T, ExecutionStats)
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+{
+    if data.is_empty() {
+        return (T::default(), ExecutionStats::default());
+    }
+    if data.len() == 1 {
+        let stats = ExecutionStats {
+            chunk_count: 1,
+            chunk_size: 1,
+            threads_used: 1,
+            chunk_times: vec![Duration::ZERO],
+            combine_time: Duration::ZERO,
+            chunk_invocations: vec![0],
+            invocations: 0,
+            sequential_fallback: false,
+        };
+        return (data[0], stats);
+    }
+
+    let initial = get_initial_value(operation);
+
+    let threads = available_threads();
+    if !parallel_worthwhile(threads) {
+        let start = Stopwatch::start();
+        let result = data.iter().copied().fold(initial, operation);
+        let stats = ExecutionStats {
+            chunk_count: 1,
+            chunk_size: data.len(),
+            threads_used: 1,
+            chunk_times: vec![start.elapsed()],
+            combine_time: Duration::ZERO,
+            chunk_invocations: vec![data.len()],
+            invocations: data.len(),
+            sequential_fallback: true,
+        };
+        return (result, stats);
+    }
+
+    let chunk_size = aligned_chunk_size(&data, threads);
+
+    // Fold every chunk in parallel, remembering how long it took, how often the operation
+    // ran, and which thread ran it
+    let partials: Vec<(T, Duration, usize, Option<usize>)> = data
+        .par_chunks(chunk_size)
+        .map(|chunk| {
+            let start = Stopwatch::start();
+            let mut calls = 0;
+            let value = chunk.iter().copied().fold(initial, |a, b| {
+                calls += 1;
+                operation(a, b)
+            });
+            (value, start.elapsed(), calls, rayon::current_thread_index())
+        })
+        .collect();
+
+    let combine_start = Stopwatch::start();
+    let mut combine_calls = 0;
+    let result = partials.iter().fold(initial, |acc, (value, ..)| {
+        combine_calls += 1;
+        operation(acc, *value)
+    });
+    let combine_time = combine_start.elapsed();
+
+    let threads_used = partials
+        .iter()
+        .map(|(.., thread)| *thread)
+        .collect::<HashSet<_>>()
+        .len();
+
+    let chunk_invocations: Vec<usize> = partials.iter().map(|(_, _, calls, _)| *calls).collect();
+    let stats = ExecutionStats {
+        chunk_count: partials.len(),
+        chunk_size,
+        threads_used,
+        chunk_times: partials.iter().map(|(_, time, ..)| *time).collect(),
+        combine_time,
+        invocations: chunk_invocations.iter().sum::<usize>() + combine_calls,
+        chunk_invocations,
+        sequential_fallback: false,
+    };
+    (result, stats)
+}
+
+/// Performs a parallel binary operation and reports how it was executed, reporting
+/// failures as errors.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result and [`ExecutionStats`] of [`parallel_reduce_instrumented`],
+/// [`Error::EmptyInput`] for an empty vector, [`Error::OperationPanicked`], or
+/// [`Error::UnknownIdentity`].
+pub fn try_parallel_reduce_instrumented<T>(
+    data: Vec<T>,
+    operation: fn(T, T) -> T,
+) -> Result<(T, ExecutionStats), Error>
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+{
+    if probe_identity(operation).is_none() {
+        return Err(Error::UnknownIdentity);
+    }
+    if data.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    catch_panic(|| parallel_reduce_instrumented(data, operation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that the instrumented result matches the plain reduction
+    #[test]
+    fn test_instrumented_matches_result() {
+        let data: Vec<i64> = (1..=1_000).collect();
+        let (result, stats) = parallel_reduce_instrumented(data, |a, b| a + b);
+        assert_eq!(result, 500_500);
+        assert_eq!(stats.chunk_times.len(), stats.chunk_count);
+        assert!(stats.threads_used >= 1);
+        assert_eq!(
+            stats.sequential_fallback,
+            !parallel_worthwhile(available_threads())
+        );
+    }
+
+    // Test that the chunks cover every element
+    #[test]
+    fn test_instrumented_chunk_layout() {
+        let data: Vec<i32> = (1..=97).collect();
+        let (_, stats) = parallel_reduce_instrumented(data, |a, b| a + b);
+        assert!(stats.chunk_size * stats.chunk_count >= 97);
+        assert!(stats.chunk_size * (stats.chunk_count - 1) < 97);
+    }
+
+    // Test that the operation runs once per element and once per chunk
+    #[test]
+    fn test_instrumented_invocations() {
+        let data: Vec<u64> = (1..=1_000).collect();
+        let (_, stats) = parallel_reduce_instrumented(data, |a, b| a + b);
+        assert_eq!(stats.chunk_invocations.len(), stats.chunk_count);
+        assert_eq!(stats.chunk_invocations.iter().sum::<usize>(), 1_000);
+        let combines = if stats.sequential_fallback {
+            0
+        } else {
+            stats.chunk_count
+        };
+        assert_eq!(stats.invocations, 1_000 + combines);
+    }
+
+    // Test for empty vector
+    #[test]
+    fn test_instrumented_empty() {
+        let (result, stats) = parallel_reduce_instrumented(Vec::<i32>::new(), |a, b| a + b);
+        assert_eq!(result, 0);
+        assert_eq!(stats, ExecutionStats::default());
+        assert_eq!(
+            try_parallel_reduce_instrumented(Vec::<i32>::new(), |a, b| a + b),
+            Err(Error::EmptyInput)
+        );
+    }
+}
@@ -0,0 +1,437 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg};
+
+use crate::Bounded;
+
+/// A 256-bit unsigned integer, for sums and products that outgrow `u128`.
+///
+/// Implements everything [`parallel_binary_operation`](crate::parallel_binary_operation)
+/// needs, so token amounts and similar values can be reduced like any primitive:
+/// `parallel_binary_operation(amounts, |a, b| a + b)`. The `+` and `*` operators panic
+/// on overflow in every build profile, since a silently wrapped total is worse than none;
+/// the `checked_` and `wrapping_` methods behave like their primitive counterparts.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct U256 {
+    /// Little-endian base-2^64 digits.
+    limbs: [u64; 4],
+}
+
+impl U256 {
+    /// Zero.
+    pub const ZERO: U256 = U256 { limbs: [0; 4] };
+    /// One.
+    pub const ONE: U256 = U256::from_u128(1);
+    /// The largest value, 2^256 - 1.
+    pub const MAX: U256 = U256 {
+        limbs: [u64::MAX; 4],
+    };
+
+    /// Converts a `u128`.
+    pub const fn from_u128(value: u128) -> Self {
+        U256 {
+            limbs: [value as u64, (value >> 64) as u64, 0, 0],
+        }
+    }
+
+    /// Returns the value if it fits in a `u128`.
+    pub fn to_u128(self) -> Option<u128> {
+        match self.limbs {
+            [low, high, 0, 0] => Some(u128::from(low) | u128::from(high) << 64),
+            _ => None,
+        }
+    }
+
+    /// Adds, returning the low 256 bits and whether the sum overflowed.
+    pub fn overflowing_add(self, other: U256) -> (U256, bool) {
+        let mut limbs = [0; 4];
+        let mut carry = false;
+        for (limb, (&a, &b)) in limbs.iter_mut().zip(self.limbs.iter().zip(&other.limbs)) {
+            let (sum, c1) = a.overflowing_add(b);
+            let (sum, c2) = sum.overflowing_add(u64::from(carry));
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        (U256 { limbs }, carry)
+    }
+
+    /// Multiplies, returning the low 256 bits and whether the product overflowed.
+    pub fn overflowing_mul(self, other: U256) -> (U256, bool) {
+        let mut wide = [0u64; 8];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let t = u128::from(a) * u128::from(b) + u128::from(wide[i + j]) + u128::from(carry);
+                wide[i + j] = t as u64;
+                carry = (t >> 64) as u64;
+            }
+            wide[i + 4] = carry;
+        }
+        let limbs = [wide[0], wide[1], wide[2], wide[3]];
+        (U256 { limbs }, wide[4..].iter().any(|&limb| limb != 0))
+    }
+
+    /// Adds, or returns `None` on overflow.
+    pub fn checked_add(self, other: U256) -> Option<U256> {
+        match self.overflowing_add(other) {
+            (sum, false) => Some(sum),
+            (_, true) => None,
+        }
+    }
+
+    /// Multiplies, or returns `None` on overflow.
+    pub fn checked_mul(self, other: U256) -> Option<U256> {
+        match self.overflowing_mul(other) {
+            (product, false) => Some(product),
+            (_, true) => None,
+        }
+    }
+
+    /// Adds modulo 2^256.
+    pub fn wrapping_add(self, other: U256) -> U256 {
+        self.overflowing_add(other).0
+    }
+
+    /// Multiplies modulo 2^256.
+    pub fn wrapping_mul(self, other: U256) -> U256 {
+        self.overflowing_mul(other).0
+    }
+
+    /// Divides in place by a machine integer and returns the remainder.
+    fn div_rem_u64(&mut self, divisor: u64) -> u64 {
+        let mut rem = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let wide = (u128::from(rem) << 64) | u128::from(*limb);
+            *limb = (wide / u128::from(divisor)) as u64;
+            rem = (wide % u128::from(divisor)) as u64;
+        }
+        rem
+    }
+
+    /// Returns the two's complement negation modulo 2^256.
+    fn wrapping_neg(self) -> U256 {
+        U256 {
+            limbs: self.limbs.map(|limb| !limb),
+        }
+        .wrapping_add(U256::ONE)
+    }
+
+    /// Returns the decimal digits of the value.
+    fn to_decimal(self) -> String {
+        // Peel off base-10^19 digits, the largest power of ten in a u64
+        const BASE: u64 = 10_000_000_000_000_000_000;
+        let mut rest = self;
+        let mut digits = Vec::new();
+        while rest != U256::ZERO {
+            digits.push(rest.div_rem_u64(BASE));
+        }
+        let mut text = match digits.pop() {
+            Some(top) => top.to_string(),
+            None => String::from("0"),
+        };
+        for digit in digits.iter().rev() {
+            text.push_str(&format!("{:019}", digit));
+        }
+        text
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.iter().rev().cmp(other.limbs.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for U256 {
+    type Output = U256;
+
+    fn add(self, other: U256) -> U256 {
+        self.checked_add(other)
+            .expect("attempt to add with overflow")
+    }
+}
+
+impl Mul for U256 {
+    type Output = U256;
+
+    fn mul(self, other: U256) -> U256 {
+        self.checked_mul(other)
+            .expect("attempt to multiply with overflow")
+    }
+}
+
+impl Bounded for U256 {
+    const MIN_VALUE: Self = U256::ZERO;
+    const MAX_VALUE: Self = U256::MAX;
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for U256 {
+                fn from(value: $t) -> Self {
+                    U256::from_u128(u128::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128);
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_decimal())
+    }
+}
+
+impl fmt::Debug for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// A 256-bit signed integer in two's complement, for signed amounts beyond `i128`.
+///
+/// Like [`U256`], the `+` and `*` operators panic on overflow in every build profile.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct I256 {
+    bits: U256,
+}
+
+impl I256 {
+    /// Zero.
+    pub const ZERO: I256 = I256 { bits: U256::ZERO };
+    /// The smallest value, -2^255.
+    pub const MIN: I256 = I256 {
+        bits: U256 {
+            limbs: [0, 0, 0, 1 << 63],
+        },
+    };
+    /// The largest value, 2^255 - 1.
+    pub const MAX: I256 = I256 {
+        bits: U256 {
+            limbs: [u64::MAX, u64::MAX, u64::MAX, u64::MAX >> 1],
+        },
+    };
+
+    /// Converts an `i128`.
+    pub const fn from_i128(value: i128) -> Self {
+        let extension = if value < 0 { u64::MAX } else { 0 };
+        I256 {
+            bits: U256 {
+                limbs: [value as u64, (value >> 64) as u64, extension, extension],
+            },
+        }
+    }
+
+    /// Returns the value if it fits in an `i128`.
+    pub fn to_i128(self) -> Option<i128> {
+        let [low, high, upper, top] = self.bits.limbs;
+        let value = (u128::from(low) | u128::from(high) << 64) as i128;
+        let extension = if value < 0 { u64::MAX } else { 0 };
+        (upper == extension && top == extension).then_some(value)
+    }
+
+    /// Returns `true` if the value is below zero.
+    pub fn is_negative(self) -> bool {
+        self.bits.limbs[3] >> 63 == 1
+    }
+
+    /// Returns the absolute value as a `U256`, which also holds `|MIN|`.
+    pub fn unsigned_abs(self) -> U256 {
+        if self.is_negative() {
+            self.bits.wrapping_neg()
+        } else {
+            self.bits
+        }
+    }
+
+    /// Adds, or returns `None` on overflow.
+    pub fn checked_add(self, other: I256) -> Option<I256> {
+        let sum = self.wrapping_add(other);
+        // Overflow flips the sign of a sum of two operands of equal sign
+        let overflow =
+            self.is_negative() == other.is_negative() && sum.is_negative() != self.is_negative();
+        (!overflow).then_some(sum)
+    }
+
+    /// Multiplies, or returns `None` on overflow.
+    pub fn checked_mul(self, other: I256) -> Option<I256> {
+        let magnitude = self.unsigned_abs().checked_mul(other.unsigned_abs())?;
+        if self.is_negative() != other.is_negative() {
+            (magnitude <= I256::MIN.bits).then(|| I256 {
+                bits: magnitude.wrapping_neg(),
+            })
+        } else {
+            (magnitude <= I256::MAX.bits).then_some(I256 { bits: magnitude })
+        }
+    }
+
+    /// Adds modulo 2^256.
+    pub fn wrapping_add(self, other: I256) -> I256 {
+        I256 {
+            bits: self.bits.wrapping_add(other.bits),
+        }
+    }
+
+    /// Multiplies modulo 2^256.
+    pub fn wrapping_mul(self, other: I256) -> I256 {
+        I256 {
+            bits: self.bits.wrapping_mul(other.bits),
+        }
+    }
+}
+
+impl Ord for I256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Flipping the sign bit maps two's complement order onto unsigned order
+        let key = |value: &I256| {
+            let mut bits = value.bits;
+            bits.limbs[3] ^= 1 << 63;
+            bits
+        };
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for I256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for I256 {
+    type Output = I256;
+
+    fn add(self, other: I256) -> I256 {
+        self.checked_add(other)
+            .expect("attempt to add with overflow")
+    }
+}
+
+impl Mul for I256 {
+    type Output = I256;
+
+    fn mul(self, other: I256) -> I256 {
+        self.checked_mul(other)
+            .expect("attempt to multiply with overflow")
+    }
+}
+
+impl Neg for I256 {
+    type Output = I256;
+
+    fn neg(self) -> I256 {
+        assert!(self != I256::MIN, "attempt to negate with overflow");
+        I256 {
+            bits: self.bits.wrapping_neg(),
+        }
+    }
+}
+
+impl Bounded for I256 {
+    const MIN_VALUE: Self = I256::MIN;
+    const MAX_VALUE: Self = I256::MAX;
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for I256 {
+                fn from(value: $t) -> Self {
+                    I256::from_i128(i128::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_signed!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+impl From<u128> for I256 {
+    fn from(value: u128) -> Self {
+        I256 {
+            bits: U256::from(value),
+        }
+    }
+}
+
+impl fmt::Display for I256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.is_negative(), "", &self.unsigned_abs().to_decimal())
+    }
+}
+
+impl fmt::Debug for I256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parallel_binary_operation;
+
+    // Test a parallel sum that exceeds u128
+    #[test]
+    fn test_u256_parallel_sum() {
+        let amounts: Vec<U256> = (0..10_000).map(|_| U256::from(u128::MAX)).collect();
+        let total = parallel_binary_operation(amounts.clone(), |a, b| a + b);
+        assert_eq!(total, U256::from(u128::MAX) * U256::from(10_000u32));
+        assert_eq!(total.to_u128(), None);
+        assert_eq!(
+            total.to_string(),
+            "3402823669209384634633746074317682114550000"
+        );
+        assert_eq!(
+            parallel_binary_operation(amounts, |a, b| a.max(b)),
+            U256::from(u128::MAX)
+        );
+    }
+
+    // Test products and overflow detection
+    #[test]
+    fn test_u256_product_overflow() {
+        let factors: Vec<U256> = (0..4).map(|_| U256::from(u64::MAX)).collect();
+        let product = parallel_binary_operation(factors, |a, b| a * b);
+        assert_eq!(product.checked_mul(U256::from(2u8)), None);
+        assert_eq!(U256::MAX.checked_add(U256::ONE), None);
+        assert_eq!(U256::MAX.wrapping_add(U256::ONE), U256::ZERO);
+        assert_eq!(
+            U256::MAX.to_string(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+    }
+
+    // Test signed sums, ordering, and bounds
+    #[test]
+    fn test_i256_signed() {
+        let deltas: Vec<I256> = (0..1_000)
+            .map(|i| I256::from(i128::MAX) * I256::from(if i % 2 == 0 { 3 } else { -1 }))
+            .collect();
+        let net = parallel_binary_operation(deltas.clone(), |a, b| a + b);
+        assert_eq!(net, I256::from(i128::MAX) * I256::from(1_000));
+        assert_eq!(
+            parallel_binary_operation(deltas, |a, b| a.min(b)),
+            -I256::from(i128::MAX)
+        );
+
+        assert!(I256::MIN < I256::from(-1) && I256::from(-1) < I256::ZERO);
+        assert_eq!(I256::from(-42).to_string(), "-42");
+        assert_eq!(I256::from(-42).to_i128(), Some(-42));
+        assert_eq!(I256::MIN.checked_add(I256::from(-1)), None);
+        assert_eq!(I256::MIN.checked_mul(I256::from(-1)), None);
+        assert_eq!(
+            I256::MIN.unsigned_abs(),
+            I256::MAX.unsigned_abs().wrapping_add(U256::ONE)
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Parallel reductions over a single column of a CSV file.
+//!
+//! The file is split into byte ranges, one per worker. Each worker skips forward to the
+//! first line starting inside its range, parses the requested column of every line it
+//! owns, and folds the values; the per-range results are then combined in file order.
+//!
+//! Fields are split on the delimiter without quote parsing (surrounding quotes are
+//! trimmed from the selected field), so quoted fields containing delimiters or newlines
+//! are not supported.
+
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::platform::available_threads;
+
+/// Options controlling how a CSV file is parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// The field delimiter.
+    pub delimiter: u8,
+    /// Whether the first line is a header to be skipped.
+    pub has_header: bool,
+}
+
+impl Default for CsvOptions {
+    /// Comma-delimited with a header line.
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            has_header: true,
+        }
+    }
+}
+
+/// Reduces one column of a comma-separated file with a header line.
+///
+/// # Parameters
+/// - `path`: The CSV file.
+/// - `column`: The zero-based index of the column to reduce.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to every value in the column, `T::default()` if
+/// the file has no data rows, or an error if the file cannot be read or a field is
+/// missing or cannot be parsed.
+pub fn reduce_csv_column<T, P>(path: P, column: usize, operation: fn(T, T) -> T) -> io::Result<T>
+where
+    T: FromStr + Copy + Send + Sync + Default,
+    P: AsRef<Path>,
+{
+    reduce_csv_column_with(path, column, operation, &CsvOptions::default())
+}
+
+/// Reduces one column of a delimited file using the given parsing options.
+///
+/// # Parameters
+/// - `path`: The CSV file.
+/// - `column`: The zero-based index of the column to reduce.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `options`: The delimiter and header settings.
+///
+/// # Returns
+/// The result of applying the operation to every value in the column, `T::default()` if
+/// the file has no data rows, or an error if the file cannot be read or a field is
+/// missing or cannot be parsed.
+pub fn reduce_csv_column_with<T, P>(
+    path: P,
+    column: usize,
+    operation: fn(T, T) -> T,
+    options: &CsvOptions,
+) -> io::Result<T>
+where
+    T: FromStr + Copy + Send + Sync + Default,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let len = File::open(path)?.metadata()?.len();
+
+    let ranges = available_threads() as u64;
+    let range_size = len.div_ceil(ranges).max(1);
+
+    let partials = (0..ranges)
+        .into_par_iter()
+        .map(|i| (i * range_size, ((i + 1) * range_size).min(len)))
+        .filter(|(start, end)| start < end)
+        .map(|(start, end)| reduce_range(path, start, end, column, operation, options))
+        .collect::<io::Result<Vec<Option<T>>>>()?;
+
+    // Ranges without data rows (a header-only file has nothing else) contribute nothing
+    Ok(partials
+        .into_iter()
+        .flatten()
+        .reduce(operation)
+        .unwrap_or_default())
+}
+
+/// Folds the column values of every line that starts in `[start, end)`.
+///
+/// # Returns
+/// The folded values, or `None` if no data row starts in the range.
+fn reduce_range<T>(
+    path: &Path,
+    start: u64,
+    end: u64,
+    column: usize,
+    operation: fn(T, T) -> T,
+    options: &CsvOptions,
+) -> io::Result<Option<T>>
+where
+    T: FromStr + Copy,
+{
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut line = Vec::new();
+
+    // Position at the first line starting at or after `start`
+    let mut pos = if start == 0 {
+        0
+    } else {
+        reader.seek(SeekFrom::Start(start - 1))?;
+        reader.read_until(b'\n', &mut line)? as u64 + start - 1
+    };
+    if pos == 0 && options.has_header {
+        line.clear();
+        pos += reader.read_until(b'\n', &mut line)? as u64;
+    }
+
+    let mut acc = None;
+    while pos < end {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 {
+            break;
+        }
+        let line_start = pos;
+        pos += read as u64;
+
+        let text = trim_line_end(&line);
+        if text.is_empty() {
+            continue;
+        }
+        let field = text
+            .split(|&b| b == options.delimiter)
+            .nth(column)
+            .ok_or_else(|| invalid(line_start, "missing column"))?;
+        let value = std::str::from_utf8(trim_quotes(field))
+            .ok()
+            .and_then(|s| s.trim().parse::<T>().ok())
+            .ok_or_else(|| invalid(line_start, "unparsable field"))?;
+        acc = Some(match acc {
+            Some(acc) => operation(acc, value),
+            None => value,
+        });
+    }
+    Ok(acc)
+}
+
+fn trim_line_end(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+fn trim_quotes(field: &[u8]) -> &[u8] {
+    field
+        .strip_prefix(b"\"")
+        .and_then(|f| f.strip_suffix(b"\""))
+        .unwrap_or(field)
+}
+
+fn invalid(offset: u64, reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} in line at byte offset {}", reason, offset),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_temp(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.csv", name, std::process::id()));
+        File::create(&path)
+            .unwrap()
+            .write_all(contents.as_bytes())
+            .unwrap();
+        path
+    }
+
+    // Test summing a column across many byte ranges
+    #[test]
+    fn test_csv_column_sum() {
+        let mut contents = String::from("id,name,amount\n");
+        for i in 1..=1_000 {
+            contents.push_str(&format!("{},\"item {}\",{}\r\n", i, i, i * 2));
+        }
+        let path = write_temp("csv-sum", &contents);
+        let ids = reduce_csv_column::<u64, _>(&path, 0, |a, b| a + b);
+        let amounts = reduce_csv_column::<u64, _>(&path, 2, |a, b| a + b);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(ids.unwrap(), 500_500);
+        assert_eq!(amounts.unwrap(), 1_001_000);
+    }
+
+    // Test a custom delimiter without a header
+    #[test]
+    fn test_csv_column_options() {
+        let path = write_temp("csv-opts", "1;2\n3;4\n5;6\n");
+        let options = CsvOptions {
+            delimiter: b';',
+            has_header: false,
+        };
+        let result = reduce_csv_column_with::<i32, _>(&path, 1, |a, b| a * b, &options);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap(), 48);
+    }
+
+    // Test that an unparsable field is reported
+    #[test]
+    fn test_csv_column_invalid() {
+        let path = write_temp("csv-bad", "a,b\n1,2\n3,x\n");
+        let result = reduce_csv_column::<i32, _>(&path, 1, |a, b| a + b);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    // Test that empty and header-only files give the default instead of a probed identity
+    #[test]
+    fn test_csv_column_empty() {
+        let empty = write_temp("csv-empty", "");
+        let header = write_temp("csv-header", "a,b\n\n");
+        let min = |path| reduce_csv_column::<i32, _>(path, 1, i32::min).unwrap();
+        let results = (min(&empty), min(&header));
+        std::fs::remove_file(&empty).unwrap();
+        std::fs::remove_file(&header).unwrap();
+        assert_eq!(results, (0, 0));
+    }
+}
@@ -0,0 +1,4 @@
+//! File-based input sources for parallel reductions.
+
+#[cfg(feature = "csv")]
+pub mod csv;
@@ -1,46 +1,676 @@
-use rayon::prelude::*;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod adapter;
+#[cfg(feature = "std")]
+mod array;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "bigint")]
+mod bigint;
+#[cfg(feature = "std")]
+mod bloom;
+#[cfg(any(feature = "std", feature = "backend-std"))]
+mod bounded;
+#[cfg(feature = "std")]
+mod buffer;
+#[cfg(feature = "std")]
+mod cached;
+#[cfg(feature = "std")]
+mod cancel;
+#[cfg(feature = "std")]
+mod chunk;
+#[cfg(feature = "std")]
+mod concurrent;
+#[cfg(feature = "std")]
+mod config;
+#[cfg(feature = "std")]
+mod diff;
+#[cfg(feature = "std")]
+mod distinct;
+#[cfg(feature = "std")]
+pub mod distributed;
+#[cfg(feature = "std")]
+mod duration;
+#[cfg(feature = "std")]
+mod elementwise;
+#[cfg(feature = "std")]
+mod error;
+mod executor;
+#[cfg(feature = "std")]
+mod float;
+#[cfg(feature = "std")]
+mod fold;
+#[cfg(feature = "std")]
+mod gather;
+#[cfg(feature = "std")]
+mod group;
+#[cfg(feature = "half")]
+mod half;
+#[cfg(feature = "std")]
+mod hash;
+#[cfg(all(feature = "hugepages", target_os = "linux"))]
+mod huge_pages;
+#[cfg(feature = "std")]
+mod identity;
+#[cfg(feature = "std")]
+mod in_place;
+#[cfg(feature = "std")]
+mod instrument;
+#[cfg(feature = "int256")]
+mod int256;
+#[cfg(all(feature = "std", feature = "csv"))]
+pub mod io;
+#[cfg(feature = "std")]
+mod linalg;
+#[cfg(feature = "std")]
+mod log;
+#[cfg(feature = "std")]
+mod map;
+#[cfg(feature = "std")]
+mod memo;
+#[cfg(all(feature = "std", feature = "mmap", unix))]
+mod mmap;
+#[cfg(feature = "std")]
+mod monte_carlo;
+#[cfg(feature = "std")]
+mod multi;
+#[cfg(feature = "std")]
+mod online;
+#[cfg(feature = "std")]
+mod operation;
+#[cfg(feature = "std")]
+mod option;
+#[cfg(feature = "std")]
+pub mod out_of_core;
+#[cfg(feature = "std")]
+mod owned;
+#[cfg(feature = "std")]
+mod pipeline;
+#[cfg(feature = "std")]
+mod plan;
+#[cfg(feature = "std")]
+mod platform;
+#[cfg(feature = "plots")]
+pub mod plot;
+#[cfg(feature = "std")]
+mod pod;
+#[cfg(feature = "std")]
+mod profile;
+#[cfg(feature = "std")]
+mod reducer;
+#[cfg(feature = "std")]
+mod resample;
+#[cfg(feature = "std")]
+mod rle;
+#[cfg(feature = "std")]
+mod rng;
+#[cfg(feature = "std")]
+mod rolling;
+#[cfg(feature = "std")]
+mod sample;
+#[cfg(feature = "std")]
+mod scan;
+#[cfg(feature = "backend-std")]
+mod scoped;
+#[cfg(feature = "std")]
+mod scratch;
+#[cfg(feature = "std")]
+mod selftest;
+#[cfg(feature = "std")]
+mod set;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+mod shuffle;
+#[cfg(feature = "std")]
+mod simd;
+#[cfg(feature = "std")]
+mod softmax;
+#[cfg(feature = "std")]
+mod sort;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "std")]
+mod tdigest;
+#[cfg(feature = "bench")]
+pub mod testing;
+#[cfg(feature = "std")]
+mod throttle;
+#[cfg(feature = "std")]
+mod time;
+#[cfg(feature = "std")]
+mod try_reduce;
+#[cfg(feature = "std")]
+mod tuning;
+#[cfg(feature = "std")]
+mod tuple;
+
+#[cfg(feature = "std")]
+pub use adapter::ParallelOps;
+#[cfg(feature = "std")]
+pub use array::{parallel_reduce_array, ARRAY_PARALLEL_LEN};
+#[cfg(feature = "std")]
+pub use batch::{parallel_reduce_batch, parallel_reduce_nested};
+#[cfg(feature = "bigint")]
+pub use bigint::{parallel_product_exact, BigUint};
+#[cfg(feature = "std")]
+pub use bloom::{parallel_bloom_build, parallel_bloom_query, BloomFilter};
+#[cfg(any(feature = "std", feature = "backend-std"))]
+pub use bounded::Bounded;
+#[cfg(feature = "std")]
+pub use buffer::BufferPool;
+#[cfg(feature = "std")]
+pub use cached::CachedReducer;
+#[cfg(feature = "std")]
+pub use cancel::{
+    parallel_reduce_with_deadline, try_parallel_reduce_cancellable,
+    try_parallel_reduce_with_timeout, PartialResult,
+};
+#[cfg(feature = "std")]
+pub use chunk::{parallel_chunk_reduce, try_parallel_chunk_reduce};
+#[cfg(feature = "std")]
+pub use concurrent::{parallel_scope, Reduction, ReductionScope};
+#[cfg(feature = "std")]
+pub use config::{CoreSelection, ParallelConfig, VarianceAlgorithm, DETERMINISTIC_ENV};
+#[cfg(feature = "std")]
+pub use diff::{parallel_diff, parallel_diff_n};
+#[cfg(feature = "std")]
+pub use distinct::parallel_approx_distinct;
+#[cfg(feature = "std")]
+pub use duration::{
+    parallel_duration_max, parallel_duration_mean, parallel_duration_percentiles,
+    parallel_duration_total,
+};
+#[cfg(feature = "std")]
+pub use elementwise::{
+    parallel_map, parallel_map_with_config, parallel_zip_with, parallel_zip_with_config,
+};
+#[cfg(feature = "std")]
+pub use error::Error;
+#[cfg(feature = "std")]
+pub use executor::RayonExecutor;
+pub use executor::{parallel_reduce_with_executor, Executor, Job, SequentialExecutor};
+#[cfg(feature = "std")]
+pub use float::{
+    parallel_float_max, parallel_float_min, parallel_float_product, parallel_float_reduce,
+    parallel_float_sum, parallel_float_sum_with_summation, Float, FloatPolicy, NonFiniteError,
+    Summation, PAIRWISE_BLOCK,
+};
+#[cfg(feature = "std")]
+#[allow(deprecated)]
+pub use float::{parallel_float_reduce_with_policy, NanError, NanPolicy};
+#[cfg(feature = "std")]
+pub use fold::{parallel_fold_combine, parallel_fold_combine_weighted, parallel_fold_indexed};
+#[cfg(feature = "std")]
+pub use gather::parallel_gather_reduce;
+#[cfg(feature = "std")]
+pub use group::{parallel_group_stats, GroupStats};
+#[cfg(feature = "half")]
+pub use half::{
+    bf16, f16, parallel_half_max, parallel_half_mean, parallel_half_min, parallel_half_sum,
+    HalfFloat,
+};
+#[cfg(feature = "std")]
+pub use hash::{parallel_hash, HashAlgorithm};
+#[cfg(all(feature = "hugepages", target_os = "linux"))]
+pub use huge_pages::{advise_huge_pages, huge_page_vec};
+#[cfg(feature = "std")]
+pub use identity::{
+    parallel_reduce, parallel_reduce_with_identity, parallel_reduce_with_identity_cow,
+    try_parallel_reduce_with_identity, ReduceIdentity,
+};
+#[cfg(feature = "std")]
+pub use in_place::parallel_reduce_in_place;
+#[cfg(feature = "std")]
+pub use instrument::{
+    parallel_reduce_instrumented, try_parallel_reduce_instrumented, ExecutionStats,
+};
+#[cfg(feature = "int256")]
+pub use int256::{I256, U256};
+#[cfg(feature = "std")]
+pub use linalg::{
+    parallel_cosine_similarity, parallel_dot_product, parallel_pairwise_distances,
+    parallel_polyeval, parallel_reduce_cols, parallel_reduce_rows, parallel_sparse_dot,
+    parallel_transpose, parallel_weighted_mean, parallel_weighted_mean_with_policy,
+    parallel_weighted_sum, try_parallel_dot_product, try_parallel_weighted_mean,
+    try_parallel_weighted_sum, Metric,
+};
+#[cfg(feature = "log")]
+pub use log::{clear_debug_logger, set_debug_logger};
+#[cfg(feature = "std")]
+pub use map::{parallel_map_values, parallel_reduce_values};
+#[cfg(feature = "std")]
+pub use memo::MemoizedReducer;
+#[cfg(all(feature = "std", feature = "mmap", unix))]
+pub use mmap::parallel_reduce_file;
+#[cfg(feature = "std")]
+pub use monte_carlo::parallel_monte_carlo;
+#[cfg(feature = "std")]
+pub use multi::{parallel_multi_reduce, Aggregate, Aggregates};
+#[cfg(feature = "std")]
+pub use online::OnlineReducer;
+#[cfg(feature = "std")]
+pub use operation::{parallel_apply, try_parallel_apply, Operand, Operation, UnsupportedOperation};
+#[cfg(feature = "std")]
+pub use option::{parallel_reduce_some, try_parallel_reduce_some};
+#[cfg(feature = "std")]
+pub use owned::{parallel_reduce_owned, try_parallel_reduce_owned};
+#[cfg(feature = "std")]
+pub use pipeline::{parallel_inspect, parallel_inspect_every, Pipeline};
+#[cfg(feature = "std")]
+pub use plan::{plan, plan_for, ExecutionPlan};
+#[cfg(feature = "std")]
+pub use pod::Pod;
+#[cfg(feature = "flamegraph")]
+pub use profile::FoldedStacks;
+#[cfg(feature = "std")]
+pub use profile::{clear_profiler, set_profiler, Profiler};
+#[cfg(feature = "std")]
+pub use reducer::Reducer;
+#[cfg(feature = "std")]
+pub use resample::{parallel_resample, Interpolation};
+#[cfg(feature = "std")]
+pub use rle::parallel_rle;
+#[cfg(feature = "std")]
+pub use rng::Rng;
+#[cfg(feature = "std")]
+pub use rolling::{parallel_rolling, Statistic};
+#[cfg(feature = "std")]
+pub use sample::parallel_sample;
+#[cfg(feature = "std")]
+pub use scan::{parallel_cummax, parallel_cummin, parallel_scan};
+#[cfg(all(feature = "backend-std", not(feature = "std")))]
+pub use scoped::{parallel_binary_operation, parallel_reduce_with_identity};
+#[cfg(feature = "backend-std")]
+pub use scoped::{parallel_reduce_scoped, ScopedThreadExecutor};
+#[cfg(feature = "std")]
+pub use scratch::parallel_map_with;
+#[cfg(feature = "std")]
+pub use selftest::{
+    selftest, verify_operation, verify_operation_with_identity, verify_reduction, Mismatch,
+    SelfTestReport, Violation,
+};
+#[cfg(feature = "std")]
+pub use set::{parallel_difference, parallel_intersection, parallel_union};
+#[cfg(feature = "std")]
+pub use shared::{parallel_reduce_shared, parallel_reduce_shared_with_identity};
+#[cfg(feature = "std")]
+pub use shuffle::{parallel_shuffle, parallel_shuffle_with_config};
+#[cfg(feature = "std")]
+pub use simd::{
+    parallel_simd_dot, parallel_simd_max, parallel_simd_min, parallel_simd_product,
+    parallel_simd_sum, try_parallel_simd_dot, SimdElement,
+};
+#[cfg(feature = "std")]
+pub use softmax::{parallel_logsumexp, parallel_softmax};
+#[cfg(feature = "std")]
+pub use sort::{
+    parallel_sort_by_key, parallel_sort_by_key_with_config, parallel_sort_by_keys,
+    parallel_sort_by_keys_with_config, SortKey,
+};
+#[cfg(feature = "std")]
+pub use stats::{
+    parallel_normalize, parallel_normalize_with_policy, parallel_std_dev,
+    parallel_std_dev_with_policy, parallel_variance, parallel_variance_with_config,
+    parallel_variance_with_policy, parallel_zscore_outliers, parallel_zscore_outliers_with_policy,
+    try_parallel_std_dev, try_parallel_variance, Normalization,
+};
+#[cfg(feature = "std")]
+pub use stream::parallel_reduce_lines;
+#[cfg(feature = "std")]
+pub use tdigest::{parallel_quantile_sketch, TDigest, DEFAULT_COMPRESSION};
+#[cfg(feature = "std")]
+pub use throttle::Throttle;
+#[cfg(feature = "std")]
+pub use time::{parallel_earliest, parallel_latest, parallel_time_bounds, parallel_time_span};
+#[cfg(feature = "std")]
+pub use try_reduce::{
+    parallel_try_reduce, parallel_try_reduce_results, parallel_try_reduce_with_retry, RetryPolicy,
+};
+#[cfg(feature = "std")]
+pub use tuning::{
+    active_profile, default_profile_path, set_active_profile, TuningEntry, TuningProfile,
+    PROFILE_ENV,
+};
+#[cfg(feature = "bench")]
+pub use tuning::{autotune, autotune_to};
+#[cfg(feature = "std")]
+pub use tuple::{
+    parallel_reduce_pairs, parallel_reduce_triples, try_parallel_reduce_pairs,
+    try_parallel_reduce_triples,
+};
 
 /// Gets the initial value for a binary operation.
 ///
-/// This function determines the initial value based on the result of the operation
-/// when applied to two sample values. It is used to determine the initial value
-/// for parallel binary operations.
+/// # Parameters
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The identity inferred by [`probe_identity`].
+///
+/// # Panics
+/// Panics if the identity cannot be inferred, rather than reducing from a wrong one.
+#[cfg(any(feature = "std", feature = "backend-std"))]
+pub(crate) fn get_initial_value<T>(operation: fn(T, T) -> T) -> T
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+{
+    probe_identity(operation)
+        .expect("cannot infer the identity of the operation; use parallel_reduce_with_identity")
+}
+
+/// Infers the identity of a binary operation from its results on sample values.
+///
+/// Addition, multiplication, subtraction, and division are told apart by applying the
+/// operation to `8` and `8`. An operation that returns `8` there is probed again with `3`
+/// and `8` in both orders: `min` gets [`Bounded::MAX_VALUE`] and `max` gets
+/// [`Bounded::MIN_VALUE`].
 ///
 /// # Parameters
 /// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
 ///
 /// # Returns
-/// The initial value for the binary operation based on the sample result.
-/// For now either 0 or 1.
-fn get_initial_value<T>(operation: fn(T, T) -> T) -> T
+/// The identity, or `None` if the operation is not recognized.
+#[cfg(any(feature = "std", feature = "backend-std"))]
+pub(crate) fn probe_identity<T>(operation: fn(T, T) -> T) -> Option<T>
 where
-    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8>,
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
 {
-    let test_result = operation(T::from(8), T::from(8));
+    let (three, eight) = (T::from(3), T::from(8));
+    let test_result = operation(eight, eight);
     match test_result {
-        _ if test_result == T::from(16) => T::from(0), // For addition, use 0 as initial value
-        _ if test_result == T::from(64) => T::from(1), // For multiplication, use 1 as initial value
-        _ if test_result == T::from(0) => T::from(0),  // For subtraction, use 0 as initial value
-        _ if test_result == T::from(1) => T::from(1),  // For division, use 1 as initial value
-        _ => T::default(),                             // Default case
+        _ if test_result == T::from(16) => Some(T::from(0)), // For addition, use 0 as initial value
+        _ if test_result == T::from(64) => Some(T::from(1)), // For multiplication, use 1 as initial value
+        _ if test_result == T::from(0) => Some(T::from(0)), // For subtraction, use 0 as initial value
+        _ if test_result == T::from(1) => Some(T::from(1)), // For division, use 1 as initial value
+        _ if test_result == eight => match (operation(three, eight), operation(eight, three)) {
+            (low, high) if low == three && high == three => Some(T::MAX_VALUE), // For min
+            (low, high) if low == eight && high == eight => Some(T::MIN_VALUE), // For max
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Computes the chunk size used to split `len` elements across `threads` workers.
+///
+/// Always returns at least 1 so that `par_chunks` never receives a zero size.
+pub(crate) fn chunk_size_for(len: usize, threads: usize) -> usize {
+    len.div_ceil(threads.max(1)).max(1)
+}
+
+/// Size in bytes of a cache line on the targets the crate is tuned for.
+#[cfg(feature = "std")]
+const CACHE_LINE: usize = 64;
+
+/// Computes a chunk size for elements of `element_size` bytes whose chunk boundaries
+/// fall on cache-line multiples.
+///
+/// Adjacent workers then never share a cache line, and because the even split is only
+/// ever rounded up there are at most `threads` chunks, so no short trailing chunk is left
+/// to run on its own after the others finish. Elements larger than a cache line (or
+/// zero-sized ones) are not aligned.
+#[cfg(feature = "std")]
+pub(crate) fn aligned_chunk_size_for(len: usize, threads: usize, element_size: usize) -> usize {
+    let chunk_size = chunk_size_for(len, threads);
+    match element_size {
+        0 => chunk_size,
+        size if size > CACHE_LINE => chunk_size,
+        size => chunk_size.next_multiple_of(CACHE_LINE / size),
+    }
+}
+
+/// Computes the cache-line-aligned chunk size used to split `data` across `threads` workers.
+#[cfg(feature = "std")]
+pub(crate) fn aligned_chunk_size<T>(data: &[T], threads: usize) -> usize {
+    aligned_chunk_size_for(data.len(), threads, core::mem::size_of::<T>())
+}
+
+/// Panics if `operation` or its probed `initial` value fails a property check on a
+/// sample of `data`.
+#[cfg(feature = "std")]
+fn check_operation<T: Copy + PartialEq>(operation: fn(T, T) -> T, initial: T, data: &[T]) {
+    let samples = selftest::sample_evenly(data);
+    match selftest::find_violation(operation, Some(initial), &samples) {
+        Some(selftest::Found::NotAssociative(..)) => panic!(
+            "operation is not associative on sampled input; parallel results would depend on chunking"
+        ),
+        Some(selftest::Found::NotIdentity(..)) => panic!(
+            "the initial value probed for the operation is not its identity; use parallel_reduce_with_identity"
+        ),
+        None => {}
+    }
+}
+
+/// Panics if `result` differs from a sequential fold of `data`.
+///
+/// The sequential fold starts from the first element rather than the probed identity, so
+/// both a non-associative operation and a wrong identity show up as a difference.
+#[cfg(feature = "std")]
+fn shadow_verify<T>(result: T, data: &[T], operation: fn(T, T) -> T, tolerance: f64)
+where
+    T: Copy + PartialEq + 'static,
+{
+    let expected = match data.iter().copied().reduce(operation) {
+        Some(expected) => expected,
+        None => return,
+    };
+    if !approx_eq(result, expected, tolerance) {
+        panic!(
+            "parallel result differs from the sequential result; the operation is not associative or the probed initial value is not its identity"
+        );
+    }
+}
+
+/// Compares two results, within a relative `tolerance` if they are `f32` or `f64`.
+///
+/// Two NaN floats compare equal.
+#[cfg(feature = "std")]
+fn approx_eq<T: PartialEq + 'static>(a: T, b: T, tolerance: f64) -> bool {
+    use std::any::Any;
+
+    if a == b {
+        return true;
     }
+    let (a, b) = (&a as &dyn Any, &b as &dyn Any);
+    let floats = match (a.downcast_ref::<f64>(), b.downcast_ref::<f64>()) {
+        (Some(&a), Some(&b)) => Some((a, b)),
+        _ => match (a.downcast_ref::<f32>(), b.downcast_ref::<f32>()) {
+            (Some(&a), Some(&b)) => Some((f64::from(a), f64::from(b))),
+            _ => None,
+        },
+    };
+    floats.is_some_and(|(a, b)| {
+        (a.is_nan() && b.is_nan()) || (a - b).abs() <= tolerance * a.abs().max(b.abs())
+    })
 }
 
-/// Performs a parallel binary operation on a vector of data.
+/// Performs a parallel binary operation on a vector, range, or other parallel iterator.
 ///
 /// This function divides the data into chunks, processes each chunk in parallel using
 /// multiple threads, and combines the results using the provided binary operation.
+/// Anything that converts into a rayon parallel iterator of `T` is accepted, so
+/// `parallel_binary_operation(1..=10_000_000u64, |a, b| a + b)` reduces the range
+/// without materializing it.
+///
+/// The operation's identity is inferred by probing it: sums, products, differences,
+/// quotients, `min`, and `max` are recognized. Other operations need an explicit
+/// identity through [`parallel_reduce_with_identity`].
+///
+/// [`ReduceIdentity`] is not consulted. Probing needs the numeric bounds below, which the
+/// aggregation structs the trait is meant for cannot meet, and stable Rust cannot pick a
+/// different path for types that happen to implement it. Reduce those with
+/// [`parallel_reduce`] instead.
 ///
 /// # Parameters
-/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `data`: A vector, range, or parallel iterator of type `T` to operate on.
 /// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
 ///
 /// # Returns
 /// The result of applying the binary operation to all elements of the vector.
 ///
-pub fn parallel_binary_operation<T>(data: Vec<T>, operation: fn(T, T) -> T) -> T
+/// # Panics
+/// Panics if the operation's identity cannot be inferred.
+#[cfg(feature = "std")]
+pub fn parallel_binary_operation<T, I>(data: I, operation: fn(T, T) -> T) -> T
 where
-    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8>,
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+    I: rayon::iter::IntoParallelIterator<Item = T>,
+{
+    parallel_binary_operation_with_config(data, operation, &ParallelConfig::tuned::<T>())
+}
+
+/// Performs a parallel binary operation using the given configuration.
+///
+/// A `Vec<T>` is reduced in place, split into the chunks reported by [`plan_for`], so a
+/// dry run shows exactly what is executed. Other iterators are folded straight from the
+/// iterator on the configured number of threads, with rayon choosing the split points,
+/// unless they need to be seen as a slice: then they are collected into a pooled buffer
+/// and split like a `Vec`. That is the case for inputs the plan reduces sequentially
+/// (short ones, or any on a single core), a fixed chunk size, the operation check, shadow
+/// verification, a duty-cycle throttle, and an installed profiler or debug logger.
+///
+/// # Parameters
+/// - `data`: A vector, range, or parallel iterator of type `T` to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `config`: The thread count, chunk size, and sequential threshold to use.
+///
+/// # Returns
+/// The result of applying the binary operation to all elements of the vector.
+#[cfg(feature = "std")]
+pub fn parallel_binary_operation_with_config<T, I>(
+    data: I,
+    operation: fn(T, T) -> T,
+    config: &ParallelConfig,
+) -> T
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+    I: rayon::iter::IntoParallelIterator<Item = T>,
+{
+    use rayon::iter::{ParallelExtend, ParallelIterator};
+
+    let data = match into_vec(data) {
+        Ok(vec) => return reduce_collected(&vec, operation, config),
+        Err(data) => data,
+    };
+    let iter = data.into_par_iter();
+    let len = iter.opt_len();
+    let plan = len.map(|len| plan_for::<T>(len, config));
+    if plan.as_ref().is_some_and(|plan| plan.sequential) || needs_slice(config) {
+        let mut buffer = config.take_buffer(len.unwrap_or(0));
+        buffer.par_extend(iter);
+        let result = reduce_collected(&buffer, operation, config);
+        config.give_buffer(buffer);
+        return result;
+    }
+
+    match plan {
+        Some(_) => {
+            let initial = get_initial_value(operation);
+            config.install(|| {
+                iter.fold(|| initial, operation)
+                    .reduce(|| initial, operation)
+            })
+        }
+        // The length is unknown, so an empty input is only detected at the end
+        None => config
+            .install(|| {
+                iter.fold(
+                    || None,
+                    |acc, x| Some(acc.map_or(x, |acc| operation(acc, x))),
+                )
+                .reduce_with(|a, b| match (a, b) {
+                    (Some(a), Some(b)) => Some(operation(a, b)),
+                    (a, b) => a.or(b),
+                })
+            })
+            .flatten()
+            .unwrap_or_default(),
+    }
+}
+
+/// Performs a parallel binary operation on borrowed or owned data.
+///
+/// Like [`parallel_binary_operation`], for callers that hold a `Cow` and would otherwise
+/// clone borrowed data into a `Vec` just to pass it in. The elements are reduced in
+/// place either way.
+///
+/// # Parameters
+/// - `data`: The elements to operate on, borrowed or owned.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the binary operation to all elements, or `T::default()` for
+/// no elements.
+#[cfg(feature = "std")]
+pub fn parallel_binary_operation_cow<T>(
+    data: std::borrow::Cow<'_, [T]>,
+    operation: fn(T, T) -> T,
+) -> T
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+{
+    reduce_collected(&data, operation, &ParallelConfig::tuned::<T>())
+}
+
+/// Takes `data` as a `Vec<T>`, if that is its type.
+///
+/// Without specialization a generic input cannot be matched on its type, so the type ids
+/// are compared instead. `TypeId::of` needs `'static`, which `data` may not be; the id is
+/// taken with lifetimes erased, which is exact here since `Vec<T>` has none.
+#[cfg(feature = "std")]
+fn into_vec<I, T: 'static>(data: I) -> Result<Vec<T>, I> {
+    use std::any::TypeId;
+    use std::marker::PhantomData;
+
+    trait NonStaticAny {
+        fn type_id(&self) -> TypeId
+        where
+            Self: 'static;
+    }
+    impl<X> NonStaticAny for PhantomData<X> {
+        fn type_id(&self) -> TypeId
+        where
+            Self: 'static,
+        {
+            TypeId::of::<X>()
+        }
+    }
+
+    let marker = PhantomData::<I>;
+    // SAFETY: lifetimes do not exist at runtime, so extending the trait object's is sound
+    let marker: &(dyn NonStaticAny + 'static) =
+        unsafe { std::mem::transmute::<&dyn NonStaticAny, _>(&marker) };
+    if marker.type_id() != TypeId::of::<Vec<T>>() {
+        return Err(data);
+    }
+    let data = std::mem::ManuallyDrop::new(data);
+    // SAFETY: `I` is `Vec<T>`, and `data` is not dropped after being moved out
+    Ok(unsafe { std::ptr::read(std::ptr::from_ref(&*data).cast::<Vec<T>>()) })
+}
+
+/// Returns `true` if a reduction under `config` has to see its input as a slice.
+#[cfg(feature = "std")]
+fn needs_slice(config: &ParallelConfig) -> bool {
+    config.chunk_size.is_some()
+        || (cfg!(debug_assertions) && (config.check_operation || config.verify.is_some()))
+        || matches!(config.throttle, Some(Throttle::DutyCycle(_)))
+        || profile::is_installed()
+        || log::logger().is_some()
+}
+
+/// Reduces collected elements along the execution plan.
+#[cfg(feature = "std")]
+fn reduce_collected<T>(data: &[T], operation: fn(T, T) -> T, config: &ParallelConfig) -> T
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
 {
     if data.is_empty() {
         return T::default();
@@ -50,19 +680,92 @@ where
     }
 
     let initial = get_initial_value(operation);
+    if cfg!(debug_assertions) && config.check_operation {
+        check_operation(operation, initial, data);
+    }
+    let plan = plan_for::<T>(data.len(), config);
+    log::log_plan(&plan);
 
-    let threads = num_cpus::get(); // Automatically use the number of available cores
-    let chunk_size = (data.len() + threads - 1) / threads;
+    if plan.sequential {
+        return data.iter().copied().fold(initial, operation);
+    }
 
     // Perform the operation in parallel across chunks of data
-    data.par_chunks(chunk_size)
-        .map(|chunk| chunk.iter().copied().fold(initial, |a, b| operation(a, b)))
-        .reduce(|| initial, |a, b| operation(a, b)) // Reduce results using operation
+    let run = || {
+        profile::reduce_chunks(data, plan.chunk_size, initial, operation, |chunk| {
+            throttle::fold_chunk(chunk, initial, operation, config.throttle)
+        })
+    };
+    let result = config.install(run);
+    if let Some(tolerance) = config.verify.filter(|_| cfg!(debug_assertions)) {
+        shadow_verify(result, data, operation, tolerance);
+    }
+    result
+}
+
+/// Performs a parallel binary operation, reporting failures as errors.
+///
+/// # Parameters
+/// - `data`: A vector, range, or parallel iterator of type `T` to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the binary operation to all elements of the vector,
+/// [`Error::EmptyInput`] instead of `T::default()` for an empty vector, or
+/// [`Error::OperationPanicked`] if the operation panicked, or [`Error::UnknownIdentity`]
+/// if the operation's identity cannot be inferred.
+#[cfg(feature = "std")]
+pub fn try_parallel_binary_operation<T, I>(data: I, operation: fn(T, T) -> T) -> Result<T, Error>
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+    I: rayon::iter::IntoParallelIterator<Item = T>,
+{
+    try_parallel_binary_operation_with_config(data, operation, &ParallelConfig::tuned::<T>())
 }
 
-#[cfg(test)]
+/// Performs a parallel binary operation using the given configuration, reporting failures
+/// as errors.
+///
+/// # Parameters
+/// - `data`: A vector, range, or parallel iterator of type `T` to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `config`: The thread count, chunk size, and sequential threshold to use.
+///
+/// # Returns
+/// The result of applying the binary operation to all elements of the vector,
+/// [`Error::EmptyInput`] for an empty vector, [`Error::OperationPanicked`], or
+/// [`Error::UnknownIdentity`].
+#[cfg(feature = "std")]
+pub fn try_parallel_binary_operation_with_config<T, I>(
+    data: I,
+    operation: fn(T, T) -> T,
+    config: &ParallelConfig,
+) -> Result<T, Error>
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+    I: rayon::iter::IntoParallelIterator<Item = T>,
+{
+    use rayon::iter::ParallelIterator;
+
+    if probe_identity(operation).is_none() {
+        return Err(Error::UnknownIdentity);
+    }
+    let data = match into_vec(data) {
+        Ok(vec) if vec.is_empty() => return Err(Error::EmptyInput),
+        Ok(vec) => return error::catch_panic(|| reduce_collected(&vec, operation, config)),
+        Err(data) => data,
+    };
+    let iter = data.into_par_iter();
+    if iter.opt_len() == Some(0) {
+        return Err(Error::EmptyInput);
+    }
+    error::catch_panic(|| parallel_binary_operation_with_config(iter, operation, config))
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*; // Import the public functions for testing
+    use rayon::prelude::*;
 
     // Test for addition operation
     #[test]
@@ -103,4 +806,235 @@ mod tests {
         let result = parallel_binary_operation(data, |a, b| a + b);
         assert_eq!(result, 15); // 1 + 2 + 3 + 4 + 5 = 15
     }
+
+    // Test that trivial inputs need no identity, even for an unknown operation
+    #[test]
+    fn test_unknown_operation_trivial_input() {
+        assert_eq!(parallel_binary_operation(vec![5i32], |a, b| a | b), 5);
+        assert_eq!(
+            parallel_binary_operation(Vec::<i32>::new(), |a, b| a | b),
+            0
+        );
+        assert_eq!(parallel_binary_operation(5..6i32, |a, b| a | b), 5);
+    }
+
+    // Test reducing ranges and iterators without collecting them
+    #[test]
+    fn test_iterator_input() {
+        let sum = parallel_binary_operation(1..=10_000_000u64, |a, b| a + b);
+        assert_eq!(sum, 50_000_005_000_000);
+
+        let data: Vec<u32> = (1..=1_000).collect();
+        let squares =
+            parallel_binary_operation(data.par_iter().map(|&x| u64::from(x * x)), |a, b| a + b);
+        assert_eq!(squares, 333_833_500);
+        let evens =
+            parallel_binary_operation(data.par_iter().copied().filter(|x| x % 2 == 0), u32::max);
+        assert_eq!(evens, 1_000);
+
+        let config = ParallelConfig::new().with_threads(3).with_chunk_size(7);
+        let result = parallel_binary_operation_with_config(1..=20i64, |a, b| a * b, &config);
+        assert_eq!(result, 2_432_902_008_176_640_000);
+        assert_eq!(
+            try_parallel_binary_operation(0..0u32, |a, b| a + b),
+            Err(Error::EmptyInput)
+        );
+        assert_eq!(
+            parallel_binary_operation((0..10u32).into_par_iter().filter(|_| false), |a, b| a * b),
+            0
+        );
+    }
+
+    // Test that vectors are recognized among other inputs and reduced in place
+    #[test]
+    fn test_vec_input_detection() {
+        let data: Vec<u32> = (1..=100).collect();
+        let ptr = data.as_ptr();
+        assert_eq!(into_vec::<_, u32>(data).map(|vec| vec.as_ptr()), Ok(ptr));
+        assert!(into_vec::<_, u32>(1..=100u32).is_err());
+        assert!(into_vec::<_, u64>(vec![1u32]).is_err());
+        let data = vec![1u32, 2, 3];
+        assert!(into_vec::<_, u32>(data.par_iter().copied()).is_err());
+
+        let config = ParallelConfig::new().with_threads(2).with_chunk_size(16);
+        let data: Vec<u64> = (1..=1_000).collect();
+        assert_eq!(
+            parallel_binary_operation_with_config(data, |a, b| a + b, &config),
+            500_500
+        );
+        assert_eq!(
+            try_parallel_binary_operation(Vec::<u32>::new(), |a, b| a + b),
+            Err(Error::EmptyInput)
+        );
+    }
+
+    // Test that borrowed data is reduced without cloning it into a Vec
+    #[test]
+    fn test_cow_input() {
+        use std::borrow::Cow;
+
+        let data: Vec<i64> = (1..=1_000).collect();
+        assert_eq!(
+            parallel_binary_operation_cow(Cow::Borrowed(&data[..]), |a, b| a + b),
+            500_500
+        );
+        assert_eq!(
+            parallel_binary_operation_cow(Cow::Owned(data), |a, b| a + b),
+            500_500
+        );
+        assert_eq!(
+            parallel_binary_operation_cow::<i64>(Cow::Borrowed(&[]), |a, b| a * b),
+            0
+        );
+    }
+
+    // Test that an explicit configuration gives the same result
+    #[test]
+    fn test_with_config() {
+        let data: Vec<i64> = (1..=1_000).collect();
+        let config = ParallelConfig::new().with_threads(3).with_chunk_size(7);
+        let result = parallel_binary_operation_with_config(data, |a, b| a + b, &config);
+        assert_eq!(result, 500_500);
+    }
+
+    // Test that the sequential fallback gives the same result
+    #[test]
+    fn test_with_config_sequential() {
+        let data: Vec<i64> = (1..=10).collect();
+        let config = ParallelConfig::new().with_sequential_threshold(1_000);
+        let result = parallel_binary_operation_with_config(data, |a, b| a * b, &config);
+        assert_eq!(result, 3_628_800);
+    }
+
+    // Test that deterministic float sums are identical across runs
+    #[test]
+    fn test_with_config_deterministic() {
+        let data: Vec<f64> = (1..=100_000).map(|i| 1.0 / f64::from(i)).collect();
+        let config = ParallelConfig::new().with_deterministic();
+        let first = parallel_binary_operation_with_config(data.clone(), |a, b| a + b, &config);
+        for _ in 0..5 {
+            let again = parallel_binary_operation_with_config(data.clone(), |a, b| a + b, &config);
+            assert_eq!(again.to_bits(), first.to_bits());
+        }
+        assert!((first - 12.090_146).abs() < 1e-6);
+    }
+
+    // Test that shadow verification accepts float rounding differences
+    #[test]
+    fn test_with_config_verify_floats() {
+        let data: Vec<f32> = (1..=100_000).map(|i| 1.0 / i as f32).collect();
+        let config = ParallelConfig::new().with_threads(4).with_verify();
+        let result = parallel_binary_operation_with_config(data, |a, b| a + b, &config);
+        assert!((result - 12.09).abs() < 0.01);
+        assert!(approx_eq(f64::NAN, f64::NAN, 0.0));
+        assert!(!approx_eq(1.0f32, 1.1, 1e-6));
+        assert!(!approx_eq(1u8, 2, 1.0));
+    }
+
+    // Test that shadow verification catches a non-associative operation
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "differs from the sequential result")]
+    fn test_with_config_verify_mismatch() {
+        let data: Vec<i64> = (1..=1_000).collect();
+        let config = ParallelConfig::new().with_threads(4).with_verify();
+        parallel_binary_operation_with_config(data, |a, b| a - b, &config);
+    }
+
+    // Test that a throttled reduction gives the same result
+    #[test]
+    fn test_with_config_throttled() {
+        let data: Vec<i64> = (1..=10_000).collect();
+        let config = ParallelConfig::new().with_throttle(Throttle::MaxWorkers(2));
+        let result = parallel_binary_operation_with_config(data, |a, b| a + b, &config);
+        assert_eq!(result, 50_005_000);
+    }
+
+    // Test that advising the scratch buffer for huge pages gives the same result
+    #[test]
+    fn test_with_config_huge_pages() {
+        let config = ParallelConfig::new().with_huge_pages().with_verify();
+        let result = parallel_binary_operation_with_config(1..=1_000_000u64, |a, b| a + b, &config);
+        assert_eq!(result, 500_000_500_000);
+    }
+
+    // Test reducing on physical and performance cores only
+    #[test]
+    fn test_with_config_cores() {
+        for cores in [CoreSelection::Physical, CoreSelection::Performance] {
+            let config = ParallelConfig::new().with_cores(cores);
+            assert_eq!(config.resolved_threads(), cores.threads());
+            let result =
+                parallel_binary_operation_with_config(1..=10_000i64, |a, b| a + b, &config);
+            assert_eq!(result, 50_005_000);
+        }
+        let explicit = ParallelConfig::new()
+            .with_threads(3)
+            .with_cores(CoreSelection::Physical);
+        assert_eq!(explicit.resolved_threads(), 3);
+    }
+
+    // Test that the scratch copy of an iterator input is recycled through the pool
+    #[test]
+    fn test_with_config_buffer_pool() {
+        let pool = std::sync::Arc::new(BufferPool::new());
+        let config = ParallelConfig::new()
+            .with_chunk_size(100)
+            .with_buffer_pool(pool.clone());
+        for _ in 0..3 {
+            let result = parallel_binary_operation_with_config(1..=1_000u64, |a, b| a + b, &config);
+            assert_eq!(result, 500_500);
+            assert_eq!(pool.idle(), 1);
+        }
+    }
+
+    // Test that the try variant reports empty input and panics
+    #[test]
+    fn test_try_binary_operation() {
+        assert_eq!(
+            try_parallel_binary_operation(vec![1, 2, 3], |a, b| a + b),
+            Ok(6)
+        );
+        assert_eq!(
+            try_parallel_binary_operation(Vec::<i32>::new(), |a, b| a + b),
+            Err(Error::EmptyInput)
+        );
+        let result = try_parallel_binary_operation(vec![1, 2, 3], |a, b| match b {
+            3 => panic!("three"),
+            _ => a + b,
+        });
+        assert_eq!(result, Err(Error::OperationPanicked(String::from("three"))));
+    }
+
+    // Test that min and max start from the type's bounds rather than zero
+    #[test]
+    fn test_min_max_identity() {
+        let data: Vec<u32> = (10..10_000).collect();
+        assert_eq!(parallel_binary_operation(data.clone(), u32::min), 10);
+        assert_eq!(parallel_binary_operation(data, u32::max), 9_999);
+        let negative = vec![-3.5, -1.25, -8.0];
+        assert_eq!(parallel_binary_operation(negative, f64::max), -1.25);
+    }
+
+    // Test that operations with no recognizable identity are rejected
+    #[test]
+    fn test_unknown_identity() {
+        assert_eq!(
+            try_parallel_binary_operation(vec![1u8, 2, 4], |a, b| a | b),
+            Err(Error::UnknownIdentity)
+        );
+        let result = std::panic::catch_unwind(|| {
+            parallel_binary_operation(vec![12u64, 18], |a, b| a.min(b) ^ 1)
+        });
+        assert!(result.is_err());
+    }
+
+    // Test that the debug operation check rejects subtraction
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not its identity")]
+    fn test_operation_check() {
+        let config = ParallelConfig::new().with_operation_check();
+        parallel_binary_operation_with_config(vec![5, 3, 1, 8], |a, b| a - b, &config);
+    }
 }
@@ -0,0 +1,745 @@
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::ops::{Add, Div, Mul};
+
+use crate::aligned_chunk_size;
+use crate::error::check_lengths;
+use crate::platform::available_threads;
+use crate::simd::lane_dot;
+use crate::{Error, Float, FloatPolicy, NonFiniteError, SimdElement};
+
+/// Computes the dot product of two slices in parallel.
+///
+/// Only `Add` and `Mul` are required of the element type, so complex numbers and other
+/// non-probeable types work as long as their zero is supplied.
+///
+/// # Parameters
+/// - `a`: The first operand.
+/// - `b`: The second operand, of the same length as `a`.
+/// - `zero`: The additive identity of `T`.
+///
+/// # Returns
+/// The sum of `a[i] * b[i]` over all indices, or `zero` for empty slices.
+///
+/// # Panics
+/// Panics if `a` and `b` have different lengths.
+pub fn parallel_dot_product<T>(a: &[T], b: &[T], zero: T) -> T
+where
+    T: Copy + Send + Sync + Add<Output = T> + Mul<Output = T>,
+{
+    assert_eq!(a.len(), b.len(), "dot product operands differ in length");
+    if a.is_empty() {
+        return zero;
+    }
+
+    let chunk_size = aligned_chunk_size(a, available_threads());
+    a.par_chunks(chunk_size)
+        .zip(b.par_chunks(chunk_size))
+        .map(|(xs, ys)| xs.iter().zip(ys).fold(zero, |acc, (&x, &y)| acc + x * y))
+        .reduce(|| zero, |x, y| x + y)
+}
+
+/// Computes the weighted sum of a slice in parallel.
+///
+/// Values and weights are walked together in one pass, without materializing the
+/// products.
+///
+/// # Parameters
+/// - `values`: The values to sum.
+/// - `weights`: The weight of each value, of the same length as `values`.
+///
+/// # Returns
+/// The sum of `values[i] * weights[i]` over all indices, or zero for empty slices.
+///
+/// # Panics
+/// Panics if `values` and `weights` have different lengths.
+pub fn parallel_weighted_sum<T: Float>(values: &[T], weights: &[T]) -> T {
+    parallel_dot_product(values, weights, T::ZERO)
+}
+
+/// Computes the weighted mean of a slice in parallel.
+///
+/// The weighted sum and the total weight are accumulated together in one pass.
+///
+/// # Parameters
+/// - `values`: The values to average.
+/// - `weights`: The weight of each value, of the same length as `values`.
+///
+/// # Returns
+/// The weighted sum divided by the total weight, or `None` for empty slices or a total
+/// weight of zero.
+///
+/// # Panics
+/// Panics if `values` and `weights` have different lengths.
+pub fn parallel_weighted_mean<T>(values: &[T], weights: &[T]) -> Option<T>
+where
+    T: Float + Div<Output = T>,
+{
+    assert_eq!(
+        values.len(),
+        weights.len(),
+        "values and weights differ in length"
+    );
+    weighted_mean(values, weights, false)
+}
+
+/// Computes the weighted mean of a slice in parallel with a policy for non-finite values
+/// and weights.
+///
+/// # Parameters
+/// - `values`: The values to average.
+/// - `weights`: The weight of each value, of the same length as `values`.
+/// - `policy`: How pairs with a NaN or infinite value or weight are handled;
+///   [`FloatPolicy::Skip`] leaves out the whole pair.
+///
+/// # Returns
+/// The weighted mean, `None` for empty slices or a total weight of zero, or a
+/// [`NonFiniteError`] with the first offending index under [`FloatPolicy::Error`].
+///
+/// # Panics
+/// Panics if `values` and `weights` have different lengths.
+pub fn parallel_weighted_mean_with_policy<T>(
+    values: &[T],
+    weights: &[T],
+    policy: FloatPolicy,
+) -> Result<Option<T>, NonFiniteError>
+where
+    T: Float + Div<Output = T>,
+{
+    assert_eq!(
+        values.len(),
+        weights.len(),
+        "values and weights differ in length"
+    );
+    if policy == FloatPolicy::Error {
+        let first = values
+            .par_iter()
+            .zip(weights)
+            .position_first(|(x, w)| !x.is_finite() || !w.is_finite());
+        if let Some(index) = first {
+            return Err(NonFiniteError { index });
+        }
+    }
+    Ok(weighted_mean(values, weights, policy == FloatPolicy::Skip))
+}
+
+/// Accumulates the weighted sum and total weight in one pass, optionally skipping pairs
+/// with a non-finite value or weight.
+fn weighted_mean<T>(values: &[T], weights: &[T], skip_non_finite: bool) -> Option<T>
+where
+    T: Float + Div<Output = T>,
+{
+    let chunk_size = aligned_chunk_size(values, available_threads());
+    let zero = (T::ZERO, T::ZERO);
+    let (sum, total_weight) = values
+        .par_chunks(chunk_size)
+        .zip(weights.par_chunks(chunk_size))
+        .map(|(xs, ws)| {
+            xs.iter()
+                .zip(ws)
+                .filter(|(x, w)| !(skip_non_finite && (!x.is_finite() || !w.is_finite())))
+                .fold(zero, |(sum, total), (&x, &w)| (sum + x * w, total + w))
+        })
+        .reduce(|| zero, |(s1, w1), (s2, w2)| (s1 + s2, w1 + w2));
+
+    if total_weight == T::ZERO {
+        None
+    } else {
+        Some(sum / total_weight)
+    }
+}
+
+/// Evaluates a polynomial at many points in parallel.
+///
+/// Each point is evaluated with Horner's scheme, one multiply and one add per
+/// coefficient, and the points are split across the available cores.
+///
+/// # Parameters
+/// - `coeffs`: The coefficients in ascending order of degree: `coeffs[i]` multiplies `x^i`.
+/// - `xs`: The points to evaluate at.
+///
+/// # Returns
+/// The value of the polynomial at each point, in the order of `xs`. A polynomial without
+/// coefficients is zero everywhere.
+pub fn parallel_polyeval<T: SimdElement>(coeffs: &[T], xs: &[T]) -> Vec<T> {
+    let horner = |x: T| coeffs.iter().rev().fold(T::ZERO, |acc, &c| acc * x + c);
+    if xs.is_empty() {
+        return Vec::new();
+    }
+    xs.par_iter()
+        .with_min_len(aligned_chunk_size(xs, available_threads()))
+        .map(|&x| horner(x))
+        .collect()
+}
+
+/// Side length of the square tiles [`parallel_transpose`] copies at a time.
+///
+/// A 32x32 tile of 8-byte elements reads 32 rows and writes 32 rows of 256 bytes each,
+/// which stays within the L1 cache of every common target.
+const TRANSPOSE_TILE: usize = 32;
+
+/// Transposes a row-major matrix in parallel.
+///
+/// The output is split into bands of output rows that are filled in parallel, each one
+/// tile by tile, so reads and writes both stay within a few cache lines at a time.
+///
+/// # Parameters
+/// - `data`: The matrix in row-major order.
+/// - `rows`: The number of rows of `data`.
+/// - `cols`: The number of columns of `data`.
+///
+/// # Returns
+/// The transposed `cols` x `rows` matrix in row-major order.
+///
+/// # Panics
+/// Panics if `data.len()` is not `rows * cols`.
+pub fn parallel_transpose<T>(data: &[T], rows: usize, cols: usize) -> Vec<T>
+where
+    T: Copy + Send + Sync,
+{
+    check_dimensions(data, rows, cols);
+    let mut out = data.to_vec();
+    if data.is_empty() {
+        return out;
+    }
+
+    // Each band is TRANSPOSE_TILE output rows, i.e. TRANSPOSE_TILE input columns
+    out.par_chunks_mut(TRANSPOSE_TILE * rows)
+        .enumerate()
+        .for_each(|(band, block)| {
+            let col_start = band * TRANSPOSE_TILE;
+            let band_cols = block.len() / rows;
+            for row_start in (0..rows).step_by(TRANSPOSE_TILE) {
+                let row_end = (row_start + TRANSPOSE_TILE).min(rows);
+                for c in 0..band_cols {
+                    for r in row_start..row_end {
+                        block[c * rows + r] = data[r * cols + col_start + c];
+                    }
+                }
+            }
+        });
+    out
+}
+
+/// Number of adjacent columns [`parallel_reduce_cols`] accumulates per task.
+const COLUMN_BLOCK: usize = 64;
+
+/// Reduces every row of a row-major matrix in parallel.
+///
+/// # Parameters
+/// - `data`: The matrix in row-major order.
+/// - `rows`: The number of rows of `data`.
+/// - `cols`: The number of columns of `data`.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// One result per row, in row order; `identity` for every row if there are no columns.
+///
+/// # Panics
+/// Panics if `data.len()` is not `rows * cols`.
+pub fn parallel_reduce_rows<T>(
+    data: &[T],
+    rows: usize,
+    cols: usize,
+    identity: T,
+    operation: fn(T, T) -> T,
+) -> Vec<T>
+where
+    T: Copy + Send + Sync,
+{
+    check_dimensions(data, rows, cols);
+    if cols == 0 {
+        return vec![identity; rows];
+    }
+    data.par_chunks(cols)
+        .map(|row| row.iter().copied().fold(identity, operation))
+        .collect()
+}
+
+/// Reduces every column of a row-major matrix in parallel.
+///
+/// Blocks of adjacent columns are reduced in parallel, each walking down the rows with
+/// one accumulator per column, so the matrix is still read row by row.
+///
+/// # Parameters
+/// - `data`: The matrix in row-major order.
+/// - `rows`: The number of rows of `data`.
+/// - `cols`: The number of columns of `data`.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// One result per column, in column order; `identity` for every column if there are no rows.
+///
+/// # Panics
+/// Panics if `data.len()` is not `rows * cols`.
+pub fn parallel_reduce_cols<T>(
+    data: &[T],
+    rows: usize,
+    cols: usize,
+    identity: T,
+    operation: fn(T, T) -> T,
+) -> Vec<T>
+where
+    T: Copy + Send + Sync,
+{
+    check_dimensions(data, rows, cols);
+    let mut out = vec![identity; cols];
+    out.par_chunks_mut(COLUMN_BLOCK)
+        .enumerate()
+        .for_each(|(block, acc)| {
+            let start = block * COLUMN_BLOCK;
+            for row in data.chunks_exact(cols) {
+                for (a, &x) in acc.iter_mut().zip(&row[start..]) {
+                    *a = operation(*a, x);
+                }
+            }
+        });
+    out
+}
+
+/// Computes the cosine similarity of a query vector with every row of a corpus in parallel.
+///
+/// The corpus is a row-major matrix of `dim`-dimensional vectors, e.g. embeddings. Rows
+/// are scored independently with vectorized dot products, and the query's norm is
+/// computed once.
+///
+/// # Parameters
+/// - `query`: The query vector, of length `dim`.
+/// - `corpus_rows`: The corpus vectors, concatenated.
+/// - `dim`: The number of dimensions of each vector.
+///
+/// # Returns
+/// One similarity in `[-1, 1]` per corpus row, in row order; `0.0` for a row or query
+/// with zero norm.
+///
+/// # Panics
+/// Panics if `dim` is zero, `query` does not have `dim` elements, or the corpus length is
+/// not a multiple of `dim`.
+pub fn parallel_cosine_similarity(query: &[f32], corpus_rows: &[f32], dim: usize) -> Vec<f32> {
+    assert!(dim > 0, "dimension must be at least 1");
+    assert_eq!(
+        query.len(),
+        dim,
+        "query length does not match the dimension"
+    );
+    assert_eq!(
+        corpus_rows.len() % dim,
+        0,
+        "corpus length is not a multiple of the dimension"
+    );
+    let query_norm = lane_dot(query, query).sqrt();
+    corpus_rows
+        .par_chunks_exact(dim)
+        .map(|row| {
+            let norms = query_norm * lane_dot(row, row).sqrt();
+            if norms == 0.0 {
+                0.0
+            } else {
+                lane_dot(query, row) / norms
+            }
+        })
+        .collect()
+}
+
+/// A distance between two vectors, for [`parallel_pairwise_distances`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// The straight-line distance, `sqrt(sum((a - b)^2))`.
+    Euclidean,
+    /// One minus the cosine similarity, from 0 for parallel vectors to 2 for opposite ones;
+    /// 1 if either vector has zero norm.
+    Cosine,
+}
+
+/// Number of points per side of a tile of the distance matrix.
+const DISTANCE_TILE: usize = 64;
+
+/// Computes the distances between all pairs of points in parallel.
+///
+/// The result is the upper triangle of the distance matrix, row by row, without the
+/// diagonal: the distance between points `i < j` is at index
+/// `i * n - i * (i + 1) / 2 + (j - i - 1)` for `n` points, the condensed layout SciPy's
+/// `pdist` uses. Bands of 64 rows are filled in parallel, each walking the columns in
+/// tiles of 64 points so a tile's points stay in cache for the whole band.
+///
+/// # Parameters
+/// - `points`: The points, each `dim` values, concatenated.
+/// - `dim`: The number of dimensions of each point.
+/// - `metric`: The distance to compute.
+///
+/// # Returns
+/// `n * (n - 1) / 2` distances for `n` points.
+///
+/// # Panics
+/// Panics if `dim` is zero or the length of `points` is not a multiple of `dim`.
+pub fn parallel_pairwise_distances(points: &[f32], dim: usize, metric: Metric) -> Vec<f32> {
+    assert!(dim > 0, "dimension must be at least 1");
+    assert_eq!(
+        points.len() % dim,
+        0,
+        "points length is not a multiple of the dimension"
+    );
+    let n = points.len() / dim;
+    let point = |i: usize| &points[i * dim..(i + 1) * dim];
+    let norms: Vec<f32> = match metric {
+        Metric::Euclidean => Vec::new(),
+        Metric::Cosine => points
+            .par_chunks_exact(dim)
+            .map(|p| lane_dot(p, p).sqrt())
+            .collect(),
+    };
+    let distance = |i: usize, j: usize| match metric {
+        Metric::Euclidean => point(i)
+            .iter()
+            .zip(point(j))
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>()
+            .sqrt(),
+        Metric::Cosine => {
+            let norm = norms[i] * norms[j];
+            if norm == 0.0 {
+                1.0
+            } else {
+                1.0 - lane_dot(point(i), point(j)) / norm
+            }
+        }
+    };
+
+    // Split the condensed output into one slice per row
+    let mut out = vec![0.0; n * n.saturating_sub(1) / 2];
+    let mut rows: Vec<&mut [f32]> = Vec::with_capacity(n);
+    let mut rest = out.as_mut_slice();
+    for i in 0..n {
+        let (row, tail) = rest.split_at_mut(n - 1 - i);
+        rows.push(row);
+        rest = tail;
+    }
+
+    rows.par_chunks_mut(DISTANCE_TILE)
+        .enumerate()
+        .for_each(|(band, band_rows)| {
+            let first = band * DISTANCE_TILE;
+            for tile in (first + 1..n).step_by(DISTANCE_TILE) {
+                let tile_end = (tile + DISTANCE_TILE).min(n);
+                for (offset, row) in band_rows.iter_mut().enumerate() {
+                    let i = first + offset;
+                    for j in tile.max(i + 1)..tile_end {
+                        row[j - i - 1] = distance(i, j);
+                    }
+                }
+            }
+        });
+    out
+}
+
+/// Panics if `data` does not hold a `rows` x `cols` matrix.
+fn check_dimensions<T>(data: &[T], rows: usize, cols: usize) {
+    assert_eq!(
+        Some(data.len()),
+        rows.checked_mul(cols),
+        "matrix data does not match its dimensions"
+    );
+}
+
+/// Computes the dot product of two sparse vectors in parallel.
+///
+/// Each vector is given as its nonzero entries: strictly increasing indices and the
+/// values at them. The entries of `a` are split across the available cores; each part
+/// binary-searches the matching range of `b` and intersects the two sorted runs with a
+/// linear merge.
+///
+/// # Parameters
+/// - `indices_a`: The indices of the nonzero entries of `a`, strictly increasing.
+/// - `values_a`: The values of `a` at `indices_a`.
+/// - `indices_b`: The indices of the nonzero entries of `b`, strictly increasing.
+/// - `values_b`: The values of `b` at `indices_b`.
+///
+/// # Returns
+/// The sum of `a[i] * b[i]` over the indices present in both vectors, or zero if there
+/// are none.
+///
+/// # Panics
+/// Panics if a vector has a different number of indices and values.
+pub fn parallel_sparse_dot<I, T>(
+    indices_a: &[I],
+    values_a: &[T],
+    indices_b: &[I],
+    values_b: &[T],
+) -> T
+where
+    I: Ord + Copy + Send + Sync,
+    T: SimdElement,
+{
+    assert_eq!(
+        indices_a.len(),
+        values_a.len(),
+        "sparse vector a has mismatched indices and values"
+    );
+    assert_eq!(
+        indices_b.len(),
+        values_b.len(),
+        "sparse vector b has mismatched indices and values"
+    );
+    if indices_a.is_empty() || indices_b.is_empty() {
+        return T::ZERO;
+    }
+
+    let chunk_size = aligned_chunk_size(indices_a, available_threads());
+    indices_a
+        .par_chunks(chunk_size)
+        .zip(values_a.par_chunks(chunk_size))
+        .map(|(ia, va)| {
+            // The entries of b that can match this part of a
+            let start = indices_b.partition_point(|&i| i < ia[0]);
+            let end = start + indices_b[start..].partition_point(|&i| i <= ia[ia.len() - 1]);
+            let (ib, vb) = (&indices_b[start..end], &values_b[start..end]);
+
+            let (mut x, mut y, mut sum) = (0, 0, T::ZERO);
+            while x < ia.len() && y < ib.len() {
+                match ia[x].cmp(&ib[y]) {
+                    Ordering::Less => x += 1,
+                    Ordering::Greater => y += 1,
+                    Ordering::Equal => {
+                        sum = sum + va[x] * vb[y];
+                        x += 1;
+                        y += 1;
+                    }
+                }
+            }
+            sum
+        })
+        .reduce(|| T::ZERO, |a, b| a + b)
+}
+
+/// Computes the dot product of two slices in parallel, reporting a length mismatch as an
+/// error.
+///
+/// # Returns
+/// The sum of `a[i] * b[i]` over all indices, `zero` for empty slices, or
+/// [`Error::LengthMismatch`].
+pub fn try_parallel_dot_product<T>(a: &[T], b: &[T], zero: T) -> Result<T, Error>
+where
+    T: Copy + Send + Sync + Add<Output = T> + Mul<Output = T>,
+{
+    check_lengths(a.len(), b.len())?;
+    Ok(parallel_dot_product(a, b, zero))
+}
+
+/// Computes the weighted sum of a slice in parallel, reporting a length mismatch as an
+/// error.
+///
+/// # Returns
+/// The sum of `values[i] * weights[i]` over all indices, zero for empty slices, or
+/// [`Error::LengthMismatch`].
+pub fn try_parallel_weighted_sum<T: Float>(values: &[T], weights: &[T]) -> Result<T, Error> {
+    check_lengths(values.len(), weights.len())?;
+    Ok(parallel_weighted_sum(values, weights))
+}
+
+/// Computes the weighted mean of a slice in parallel, reporting failures as errors.
+///
+/// # Returns
+/// The weighted mean, [`Error::LengthMismatch`], or [`Error::EmptyInput`] for empty
+/// slices and a total weight of zero.
+pub fn try_parallel_weighted_mean<T>(values: &[T], weights: &[T]) -> Result<T, Error>
+where
+    T: Float + Div<Output = T>,
+{
+    check_lengths(values.len(), weights.len())?;
+    parallel_weighted_mean(values, weights).ok_or(Error::EmptyInput)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test for integer dot product
+    #[test]
+    fn test_dot_product() {
+        let a: Vec<i64> = (1..=100).collect();
+        let b = vec![2; 100];
+        assert_eq!(parallel_dot_product(&a, &b, 0), 10_100);
+    }
+
+    // Test polynomial evaluation against the expanded form
+    #[test]
+    fn test_polyeval() {
+        // 3 - 2x + x^3
+        let coeffs = [3.0, -2.0, 0.0, 1.0];
+        let xs: Vec<f64> = (0..10_000).map(|i| f64::from(i) / 100.0 - 50.0).collect();
+        let ys = parallel_polyeval(&coeffs, &xs);
+        for (x, y) in xs.iter().zip(&ys) {
+            assert!((y - (3.0 - 2.0 * x + x * x * x)).abs() < 1e-9);
+        }
+        assert_eq!(parallel_polyeval::<i64>(&[], &[1, 2]), vec![0, 0]);
+        assert!(parallel_polyeval(&[1u32], &[]).is_empty());
+    }
+
+    // Test a non-square transpose across several tiles
+    #[test]
+    fn test_transpose() {
+        let (rows, cols) = (70, 45);
+        let data: Vec<usize> = (0..rows * cols).collect();
+        let t = parallel_transpose(&data, rows, cols);
+        for r in 0..rows {
+            for c in 0..cols {
+                assert_eq!(t[c * rows + r], data[r * cols + c]);
+            }
+        }
+        assert_eq!(parallel_transpose(&t, cols, rows), data);
+        assert!(parallel_transpose::<u8>(&[], 0, 5).is_empty());
+    }
+
+    // Test row and column sums and maxima
+    #[test]
+    fn test_reduce_rows_and_cols() {
+        let (rows, cols) = (3, 100);
+        let data: Vec<i64> = (0..rows * cols).map(|i| i as i64).collect();
+        let row_sums = parallel_reduce_rows(&data, rows, cols, 0, |a, b| a + b);
+        assert_eq!(row_sums, vec![4_950, 14_950, 24_950]);
+        let col_max = parallel_reduce_cols(&data, rows, cols, i64::MIN, i64::max);
+        assert_eq!(col_max, (200..300).collect::<Vec<i64>>());
+        let col_sums = parallel_reduce_cols(&data, rows, cols, 0, |a, b| a + b);
+        assert_eq!(col_sums[99], 99 + 199 + 299);
+    }
+
+    // Test degenerate dimensions
+    #[test]
+    fn test_reduce_rows_and_cols_empty() {
+        assert_eq!(
+            parallel_reduce_rows::<u8>(&[], 2, 0, 0, u8::max),
+            vec![0, 0]
+        );
+        assert_eq!(
+            parallel_reduce_cols::<u8>(&[], 0, 2, 1, u8::min),
+            vec![1, 1]
+        );
+    }
+
+    // Test cosine similarity against known angles, including a zero row
+    #[test]
+    fn test_cosine_similarity() {
+        let query = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+        let mut corpus = Vec::new();
+        corpus.extend_from_slice(&query.map(|x| x * 3.0));
+        corpus.extend_from_slice(&query.map(|x| -x));
+        corpus.extend_from_slice(&[1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        corpus.extend_from_slice(&[0.0; 10]);
+        let similarities = parallel_cosine_similarity(&query, &corpus, 10);
+        let expected = [1.0, -1.0, std::f32::consts::FRAC_1_SQRT_2, 0.0];
+        for (s, e) in similarities.iter().zip(expected) {
+            assert!((s - e).abs() < 1e-6, "{} {}", s, e);
+        }
+    }
+
+    // Test pairwise distances against direct computation in the condensed layout
+    #[test]
+    fn test_pairwise_distances() {
+        let n = 150;
+        let points: Vec<f32> = (0..n * 3).map(|i| ((i * 37) % 11) as f32 - 5.0).collect();
+        let euclidean = parallel_pairwise_distances(&points, 3, Metric::Euclidean);
+        let cosine = parallel_pairwise_distances(&points, 3, Metric::Cosine);
+        assert_eq!(euclidean.len(), n * (n - 1) / 2);
+        for i in 0..n {
+            for j in i + 1..n {
+                let (a, b) = (&points[i * 3..i * 3 + 3], &points[j * 3..j * 3 + 3]);
+                let index = i * n - i * (i + 1) / 2 + (j - i - 1);
+                let squared: f32 = a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum();
+                assert!((euclidean[index] - squared.sqrt()).abs() < 1e-5);
+                let similarity = parallel_cosine_similarity(a, b, 3)[0];
+                assert!((cosine[index] - (1.0 - similarity)).abs() < 1e-5);
+            }
+        }
+    }
+
+    // Test fewer than two points
+    #[test]
+    fn test_pairwise_distances_degenerate() {
+        assert!(parallel_pairwise_distances(&[], 2, Metric::Euclidean).is_empty());
+        assert!(parallel_pairwise_distances(&[1.0, 2.0], 2, Metric::Cosine).is_empty());
+        assert_eq!(
+            parallel_pairwise_distances(&[0.0, 0.0, 3.0, 4.0], 2, Metric::Cosine),
+            [1.0]
+        );
+    }
+
+    // Test a sparse dot product against the dense one
+    #[test]
+    fn test_sparse_dot() {
+        let ia: Vec<u32> = (0..50_000).map(|i| i * 2).collect();
+        let va: Vec<f64> = ia.iter().map(|&i| f64::from(i % 7)).collect();
+        let ib: Vec<u32> = (0..40_000).map(|i| i * 3).collect();
+        let vb: Vec<f64> = ib.iter().map(|&i| f64::from(i % 5)).collect();
+
+        let expected: f64 = ia
+            .iter()
+            .zip(&va)
+            .filter(|(i, _)| *i % 3 == 0 && **i < 120_000)
+            .map(|(&i, &v)| v * f64::from(i % 5))
+            .sum();
+        assert_eq!(parallel_sparse_dot(&ia, &va, &ib, &vb), expected);
+        assert_eq!(parallel_sparse_dot::<u32, i32>(&[], &[], &[1], &[2]), 0);
+    }
+
+    // Test for empty slices
+    #[test]
+    fn test_dot_product_empty() {
+        assert_eq!(parallel_dot_product::<f64>(&[], &[], 0.0), 0.0);
+    }
+
+    // Test that mismatched lengths are rejected
+    #[test]
+    #[should_panic]
+    fn test_dot_product_length_mismatch() {
+        parallel_dot_product(&[1, 2, 3], &[1, 2], 0);
+    }
+
+    // Test for weighted sum and mean
+    #[test]
+    fn test_weighted_sum_and_mean() {
+        let values = vec![10.0, 20.0, 30.0, 40.0];
+        let weights = vec![1.0, 1.0, 2.0, 4.0];
+        assert_eq!(parallel_weighted_sum(&values, &weights), 250.0);
+        assert_eq!(parallel_weighted_mean(&values, &weights), Some(31.25));
+    }
+
+    // Test that a zero total weight has no mean
+    #[test]
+    fn test_weighted_mean_zero_weight() {
+        assert_eq!(parallel_weighted_mean::<f32>(&[], &[]), None);
+        assert_eq!(parallel_weighted_mean(&[1.0f64, 2.0], &[0.0, 0.0]), None);
+    }
+
+    // Test the try variants
+    #[test]
+    fn test_try_variants() {
+        assert_eq!(
+            try_parallel_dot_product(&[1, 2, 3], &[1, 2], 0),
+            Err(Error::LengthMismatch { left: 3, right: 2 })
+        );
+        assert_eq!(try_parallel_weighted_sum(&[2.0, 3.0], &[0.5, 2.0]), Ok(7.0));
+        assert_eq!(
+            try_parallel_weighted_mean(&[1.0, 2.0], &[0.0, 0.0]),
+            Err(Error::EmptyInput)
+        );
+    }
+
+    // Test the weighted mean policies for non-finite values
+    #[test]
+    fn test_weighted_mean_policy() {
+        let values = [1.0, f64::NAN, 3.0, 5.0];
+        let weights = [1.0, 1.0, f64::INFINITY, 1.0];
+        let mean = parallel_weighted_mean_with_policy(&values, &weights, FloatPolicy::Propagate);
+        assert!(mean.unwrap().unwrap().is_nan());
+        assert_eq!(
+            parallel_weighted_mean_with_policy(&values, &weights, FloatPolicy::Skip),
+            Ok(Some(3.0))
+        );
+        assert_eq!(
+            parallel_weighted_mean_with_policy(&values, &weights, FloatPolicy::Error),
+            Err(NonFiniteError { index: 1 })
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Debug records of how reductions are scheduled.
+//!
+//! With the `log` feature, [`set_debug_logger`] installs a sink that receives one line per
+//! scheduling decision: the plan (thread count and chunk size), which worker folded which
+//! chunk, and the order in which partial results were combined. All records are
+//! debug-level; forward them to `log::debug!` or `tracing::debug!` to merge them with an
+//! application's own logging. Without the feature, nothing is recorded and the checks
+//! compile to nothing.
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use crate::ExecutionPlan;
+
+/// A sink for debug records.
+pub(crate) type Logger = Arc<dyn Fn(&str) + Send + Sync>;
+
+static LOGGER: RwLock<Option<Logger>> = RwLock::new(None);
+
+/// Installs a sink for debug records of subsequent reductions, replacing any previous one.
+///
+/// Records are emitted by [`parallel_binary_operation`](crate::parallel_binary_operation),
+/// [`parallel_binary_operation_with_config`](crate::parallel_binary_operation_with_config),
+/// and [`parallel_reduce_with_identity`](crate::parallel_reduce_with_identity). The sink
+/// is called from worker threads.
+///
+/// # Parameters
+/// - `logger`: Called with every record, without a trailing newline.
+#[cfg(feature = "log")]
+pub fn set_debug_logger(logger: impl Fn(&str) + Send + Sync + 'static) {
+    *LOGGER.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(logger));
+}
+
+/// Removes the installed debug sink, if any.
+#[cfg(feature = "log")]
+pub fn clear_debug_logger() {
+    *LOGGER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Returns the installed sink, or `None` if there is none or the `log` feature is off.
+pub(crate) fn logger() -> Option<Logger> {
+    if cfg!(feature = "log") {
+        LOGGER.read().unwrap_or_else(|e| e.into_inner()).clone()
+    } else {
+        None
+    }
+}
+
+/// Sends one record to the installed sink, if any.
+pub(crate) fn debug(args: fmt::Arguments<'_>) {
+    if let Some(logger) = logger() {
+        logger(&args.to_string());
+    }
+}
+
+/// Records the plan a reduction is about to run with.
+pub(crate) fn log_plan(plan: &ExecutionPlan) {
+    if plan.sequential {
+        debug(format_args!(
+            "reducing {} elements sequentially on the calling thread",
+            plan.data_len
+        ));
+    } else {
+        debug(format_args!(
+            "reducing {} elements on {} threads in {} chunks of {}",
+            plan.data_len,
+            plan.threads,
+            plan.chunks.len(),
+            plan.chunk_size
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "log"))]
+mod tests {
+    use super::*;
+    use crate::profile::reduce_chunks;
+    use crate::{plan, ParallelConfig};
+    use std::sync::Mutex;
+
+    // Debug sinks are global, so tests that install one must not overlap
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    // Installs a sink that keeps only records from threads named with `prefix`
+    fn capture(prefix: &'static str) -> Arc<Mutex<Vec<String>>> {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&records);
+        set_debug_logger(move |record| {
+            if std::thread::current()
+                .name()
+                .is_some_and(|name| name.starts_with(prefix))
+            {
+                sink.lock().unwrap().push(record.to_string());
+            }
+        });
+        records
+    }
+
+    // Test the records for parallel and sequential plans
+    #[test]
+    fn test_log_plan() {
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let records = capture("log-test-plan");
+        let config = ParallelConfig::new().with_threads(4).with_chunk_size(25);
+        std::thread::Builder::new()
+            .name(String::from("log-test-plan"))
+            .spawn(move || {
+                log_plan(&plan(100, &config));
+                log_plan(&plan(10, &config.with_sequential_threshold(50)));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        clear_debug_logger();
+
+        assert_eq!(
+            *records.lock().unwrap(),
+            [
+                "reducing 100 elements on 4 threads in 4 chunks of 25",
+                "reducing 10 elements sequentially on the calling thread",
+            ]
+        );
+    }
+
+    // Test that every chunk fold and combine step is recorded
+    #[test]
+    fn test_log_chunks_and_combines() {
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let records = capture("log-test-pool");
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .thread_name(|i| format!("log-test-pool-{}", i))
+            .build()
+            .unwrap();
+        let data: Vec<i64> = (1..=1_000).collect();
+        let result =
+            pool.install(|| reduce_chunks(&data, 100, 0, |a, b| a + b, |c| c.iter().sum()));
+        clear_debug_logger();
+
+        assert_eq!(result, 500_500);
+        let records = records.lock().unwrap();
+        let folds = records.iter().filter(|r| r.starts_with("folded chunk"));
+        assert_eq!(folds.count(), 10);
+        assert!(
+            records.contains(&String::from("folded chunk 9 (900..1000) on worker 0"))
+                || records.contains(&String::from("folded chunk 9 (900..1000) on worker 1"))
+        );
+        let combines = records.iter().filter(|r| r.starts_with("combined chunks"));
+        assert_eq!(combines.count(), 9);
+    }
+}
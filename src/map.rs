@@ -0,0 +1,118 @@
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::ops::Range;
+
+use crate::platform::available_threads;
+
+/// Splits `len` entries into one contiguous range per worker.
+///
+/// Each worker walks the map's own iterator to the start of its range, which only scans
+/// the table's control bytes, so no entries are copied into an intermediate `Vec`.
+fn entry_ranges(len: usize) -> impl IndexedParallelIterator<Item = Range<usize>> {
+    let workers = available_threads().clamp(1, len.max(1));
+    let per_worker = len.div_ceil(workers);
+    (0..workers)
+        .into_par_iter()
+        .map(move |w| (w * per_worker).min(len)..((w + 1) * per_worker).min(len))
+}
+
+/// Reduces the values of a `HashMap` in parallel, without collecting them first.
+///
+/// The map's entries are partitioned into one contiguous range per thread, each thread
+/// folds its range straight out of the table, and the partial results are combined. The
+/// iteration order of a `HashMap` is unspecified, so `operation` should be commutative as
+/// well as associative.
+///
+/// # Parameters
+/// - `map`: The map whose values to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `V` and returns a result of type `V`.
+///
+/// # Returns
+/// The reduction of all values, or `identity` if the map is empty.
+pub fn parallel_reduce_values<K, V, S>(
+    map: &HashMap<K, V, S>,
+    identity: V,
+    operation: fn(V, V) -> V,
+) -> V
+where
+    K: Sync,
+    V: Copy + Send + Sync,
+    S: Sync,
+{
+    entry_ranges(map.len())
+        .map(|range| {
+            map.values()
+                .skip(range.start)
+                .take(range.len())
+                .fold(identity, |acc, &v| operation(acc, v))
+        })
+        .reduce(|| identity, operation)
+}
+
+/// Applies a function to every value of a `HashMap` in parallel.
+///
+/// Values are read straight out of the table, partitioned like [`parallel_reduce_values`];
+/// only the keys are cloned into the new map, which keeps the original's hasher.
+///
+/// # Parameters
+/// - `map`: The map whose values to transform.
+/// - `f`: The function applied to each value.
+///
+/// # Returns
+/// A map with the same keys, each bound to `f` of its original value.
+pub fn parallel_map_values<K, V, U, S, F>(map: &HashMap<K, V, S>, f: F) -> HashMap<K, U, S>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Sync,
+    U: Send,
+    S: BuildHasher + Clone + Sync,
+    F: Fn(&V) -> U + Sync,
+{
+    let parts: Vec<Vec<(K, U)>> = entry_ranges(map.len())
+        .map(|range| {
+            map.iter()
+                .skip(range.start)
+                .take(range.len())
+                .map(|(k, v)| (k.clone(), f(v)))
+                .collect()
+        })
+        .collect();
+    let mut mapped = HashMap::with_capacity_and_hasher(map.len(), map.hasher().clone());
+    mapped.extend(parts.into_iter().flatten());
+    mapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that reducing the values matches a sequential sum
+    #[test]
+    fn test_reduce_values() {
+        let map: HashMap<u32, u64> = (0..100_000).map(|i| (i, u64::from(i) * 3)).collect();
+        let expected: u64 = map.values().sum();
+        assert_eq!(parallel_reduce_values(&map, 0, |a, b| a + b), expected);
+        assert_eq!(parallel_reduce_values(&map, 0, u64::max), 299_997);
+    }
+
+    // Test that mapping the values keeps every key
+    #[test]
+    fn test_map_values() {
+        let map: HashMap<String, u32> = (0..10_000).map(|i| (i.to_string(), i)).collect();
+        let mapped = parallel_map_values(&map, |&v| f64::from(v) / 2.0);
+        assert_eq!(mapped.len(), map.len());
+        for (k, v) in &map {
+            assert_eq!(mapped[k], f64::from(*v) / 2.0);
+        }
+    }
+
+    // Test for an empty map
+    #[test]
+    fn test_empty_map() {
+        let map: HashMap<u8, i32> = HashMap::new();
+        assert_eq!(parallel_reduce_values(&map, 1, |a, b| a * b), 1);
+        assert!(parallel_map_values(&map, |v| v + 1).is_empty());
+    }
+}
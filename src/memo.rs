@@ -0,0 +1,156 @@
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+use std::slice;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::{parallel_hash, HashAlgorithm, Pod, Reducer};
+
+/// Identifies a query: the input length, its XXH64 fingerprint, and the operation's
+/// address. The same closure written out twice may get two addresses, which only costs
+/// a cache miss.
+type Key = (usize, u64, usize);
+
+/// A reduction cache that answers repeated identical queries without recomputing them.
+///
+/// Each query is keyed by the input's length, a parallel XXH64 hash of its bytes, and
+/// the operation, so re-requesting the same aggregation over unchanged data costs one
+/// hashing pass instead of a reduction. Inputs that differ but collide on the 64-bit
+/// hash would share a result; for anything but dashboards and similar repeated reads,
+/// reduce directly. The oldest entry is evicted once `capacity` results are cached.
+pub struct MemoizedReducer<T> {
+    capacity: usize,
+    state: Mutex<Cache<T>>,
+}
+
+struct Cache<T> {
+    results: HashMap<Key, Option<T>>,
+    order: VecDeque<Key>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<T: Pod> MemoizedReducer<T> {
+    /// Creates an empty cache holding up to `capacity` results (at least one).
+    pub fn new(capacity: usize) -> Self {
+        MemoizedReducer {
+            capacity: capacity.max(1),
+            state: Mutex::new(Cache {
+                results: HashMap::new(),
+                order: VecDeque::new(),
+                hits: 0,
+                misses: 0,
+            }),
+        }
+    }
+
+    /// Reduces `data`, or returns the cached result of an identical earlier query.
+    ///
+    /// # Parameters
+    /// - `data`: The elements to reduce.
+    /// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+    ///
+    /// # Returns
+    /// The result of applying the operation to all elements, or `None` for an empty slice.
+    pub fn reduce(&self, data: &[T], operation: fn(T, T) -> T) -> Option<T> {
+        // SAFETY: `T` is plain old data, so its memory may be viewed as bytes
+        let bytes =
+            unsafe { slice::from_raw_parts(data.as_ptr() as *const u8, mem::size_of_val(data)) };
+        let key = (
+            data.len(),
+            parallel_hash(bytes, HashAlgorithm::XxHash64),
+            operation as usize,
+        );
+        {
+            let mut cache = self.lock();
+            if let Some(&result) = cache.results.get(&key) {
+                cache.hits += 1;
+                return result;
+            }
+            cache.misses += 1;
+        }
+
+        // Reduce without holding the lock, so other queries are not blocked
+        let result = Reducer::new(operation).run(data);
+        let mut cache = self.lock();
+        if cache.results.insert(key, result).is_none() {
+            cache.order.push_back(key);
+            if cache.order.len() > self.capacity {
+                let oldest = cache.order.pop_front().expect("order is not empty");
+                cache.results.remove(&oldest);
+            }
+        }
+        result
+    }
+
+    /// Returns the number of queries answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.lock().hits
+    }
+
+    /// Returns the number of queries that had to be reduced.
+    pub fn misses(&self) -> u64 {
+        self.lock().misses
+    }
+
+    /// Returns the number of cached results.
+    pub fn len(&self) -> usize {
+        self.lock().results.len()
+    }
+
+    /// Returns `true` if no results are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Discards every cached result, keeping the hit and miss counts.
+    pub fn clear(&self) {
+        let mut cache = self.lock();
+        cache.results.clear();
+        cache.order.clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Cache<T>> {
+        // The cache is consistent after every statement, so a poisoned lock is still usable
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that repeated queries hit the cache and changed data or operations miss it
+    #[test]
+    fn test_memoized_hits() {
+        let memo = MemoizedReducer::new(8);
+        let sum: fn(u64, u64) -> u64 = |a, b| a + b;
+        let mut data: Vec<u64> = (1..=100_000).collect();
+        assert_eq!(memo.reduce(&data, sum), Some(5_000_050_000));
+        assert_eq!(memo.reduce(&data, sum), Some(5_000_050_000));
+        assert_eq!((memo.hits(), memo.misses()), (1, 1));
+
+        assert_eq!(memo.reduce(&data, u64::max), Some(100_000));
+        data[0] = 0;
+        assert_eq!(memo.reduce(&data, u64::max), Some(100_000));
+        assert_eq!(memo.reduce(&data[..0], u64::max), None);
+        assert_eq!((memo.hits(), memo.misses(), memo.len()), (1, 4, 4));
+    }
+
+    // Test that the oldest result is evicted at capacity
+    #[test]
+    fn test_memoized_eviction() {
+        let memo = MemoizedReducer::new(2);
+        let sum: fn(i32, i32) -> i32 = |a, b| a + b;
+        for data in [[1, 2], [3, 4], [5, 6]] {
+            memo.reduce(&data, sum);
+        }
+        assert_eq!(memo.len(), 2);
+        assert_eq!(memo.reduce(&[1, 2], sum), Some(3));
+        assert_eq!(memo.reduce(&[5, 6], sum), Some(11));
+        assert_eq!((memo.hits(), memo.misses()), (1, 4));
+        memo.clear();
+        assert!(memo.is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use rayon::prelude::*;
+use std::fs::File;
+use std::io;
+use std::mem;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::ptr;
+use std::slice;
+
+use crate::platform::available_threads;
+use crate::{aligned_chunk_size, get_initial_value, Bounded, Pod};
+
+/// A read-only memory mapping of a whole file, unmapped on drop.
+struct Mapping {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+impl Mapping {
+    fn open(file: &File, len: usize) -> io::Result<Mapping> {
+        // SAFETY: mapping a file descriptor we own, read-only and private
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mapping { ptr, len })
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` and `len` come from a successful mmap call
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+/// Performs a parallel binary operation over a binary file of fixed-width numbers.
+///
+/// The file is memory-mapped rather than read into the heap, so datasets larger than a
+/// comfortable allocation can be reduced directly from disk. Elements are read in native
+/// byte order.
+///
+/// # Parameters
+/// - `path`: The file to reduce.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements in the file, or an I/O error if
+/// the file cannot be mapped, `T` is zero-sized, or the file's length is not a multiple
+/// of `size_of::<T>()`.
+///
+/// # Safety
+/// The file must not be modified or truncated, by this or any other process, until the
+/// function returns. Writes would change the elements while they are being read, and
+/// truncation makes reads past the new end fault with `SIGBUS`.
+pub unsafe fn parallel_reduce_file<T, P>(path: P, operation: fn(T, T) -> T) -> io::Result<T>
+where
+    T: Pod + Default + PartialEq + From<u8> + Bounded,
+    P: AsRef<Path>,
+{
+    if mem::size_of::<T>() == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "zero-sized elements cannot be read from a file",
+        ));
+    }
+    let file = File::open(path)?;
+    let len = usize::try_from(file.metadata()?.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "file too large to map"))?;
+    if len % mem::size_of::<T>() != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "file length is not a multiple of the element size",
+        ));
+    }
+    if len == 0 {
+        return Ok(T::default());
+    }
+
+    let mapping = Mapping::open(&file, len)?;
+    // SAFETY: the mapping is page-aligned, `len` bytes long, and `T` is valid for any bits;
+    // the caller guarantees the file is not changed while it is mapped
+    let data: &[T] =
+        unsafe { slice::from_raw_parts(mapping.ptr as *const T, len / mem::size_of::<T>()) };
+
+    if data.len() == 1 {
+        return Ok(data[0]);
+    }
+
+    let initial = get_initial_value(operation);
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    Ok(data
+        .par_chunks(chunk_size)
+        .map(|chunk| chunk.iter().copied().fold(initial, operation))
+        .reduce(|| initial, operation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_temp(name: &str, bytes: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        File::create(&path).unwrap().write_all(bytes).unwrap();
+        path
+    }
+
+    // Test summing a file of u64 values
+    #[test]
+    fn test_reduce_file_sum() {
+        let bytes: Vec<u8> = (1..=10_000u64).flat_map(|x| x.to_ne_bytes()).collect();
+        let path = write_temp("mmap-sum", &bytes);
+        // SAFETY: the temporary file is only touched by this test
+        let result = unsafe { parallel_reduce_file::<u64, _>(&path, |a, b| a + b) };
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap(), 50_005_000);
+    }
+
+    // Test that a truncated element is rejected
+    #[test]
+    fn test_reduce_file_bad_length() {
+        let path = write_temp("mmap-bad", &[0u8; 7]);
+        // SAFETY: the temporary file is only touched by this test
+        let result = unsafe { parallel_reduce_file::<u32, _>(&path, |a, b| a + b) };
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    // Test for an empty file
+    #[test]
+    fn test_reduce_file_empty() {
+        let path = write_temp("mmap-empty", &[]);
+        // SAFETY: the temporary file is only touched by this test
+        let result = unsafe { parallel_reduce_file::<i32, _>(&path, |a, b| a + b) };
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap(), 0);
+    }
+}
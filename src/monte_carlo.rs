@@ -0,0 +1,82 @@
+use rayon::prelude::*;
+
+use crate::rng::Rng;
+
+/// Number of samples drawn from each random stream.
+///
+/// Fixed rather than derived from the thread count, so the result only depends on the
+/// seed and the number of samples.
+const SAMPLES_PER_STREAM: usize = 4_096;
+
+/// Runs independent random trials in parallel and reduces their results.
+///
+/// The trials are split into fixed-size batches, each drawing from its own random stream
+/// derived from `seed`, and each batch is folded with `reduce_op` on whichever thread
+/// runs it. The batch results are then combined in batch order, so the same seed and
+/// sample count give the same result on any number of threads, even for floats.
+///
+/// # Parameters
+/// - `n_samples`: The number of trials to run.
+/// - `seed`: Seed for the random streams.
+/// - `sample_fn`: Runs one trial, drawing randomness from the given generator.
+/// - `reduce_op`: Combines two trial results, e.g. summing hits or accumulators.
+///
+/// # Returns
+/// The reduced result of all trials, or `None` if `n_samples` is zero.
+pub fn parallel_monte_carlo<T, S>(
+    n_samples: usize,
+    seed: u64,
+    sample_fn: S,
+    reduce_op: fn(T, T) -> T,
+) -> Option<T>
+where
+    T: Send,
+    S: Fn(&mut Rng) -> T + Sync,
+{
+    let batches: Vec<T> = (0..n_samples.div_ceil(SAMPLES_PER_STREAM))
+        .into_par_iter()
+        .map(|batch| {
+            let mut rng = Rng::stream(seed, batch as u64);
+            let start = batch * SAMPLES_PER_STREAM;
+            let len = SAMPLES_PER_STREAM.min(n_samples - start);
+            let first = sample_fn(&mut rng);
+            (1..len).fold(first, |acc, _| reduce_op(acc, sample_fn(&mut rng)))
+        })
+        .collect();
+    batches.into_iter().reduce(reduce_op)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test estimating pi from points in the unit square
+    #[test]
+    fn test_monte_carlo_pi() {
+        let n = 1_000_000;
+        let hits = parallel_monte_carlo(
+            n,
+            42,
+            |rng| {
+                let (x, y) = (rng.next_f64(), rng.next_f64());
+                u64::from(x * x + y * y < 1.0)
+            },
+            |a, b| a + b,
+        )
+        .unwrap();
+        let pi = 4.0 * hits as f64 / n as f64;
+        assert!((pi - std::f64::consts::PI).abs() < 0.01, "{}", pi);
+    }
+
+    // Test that float results are reproducible from the seed
+    #[test]
+    fn test_monte_carlo_reproducible() {
+        let run = |seed| parallel_monte_carlo(10_001, seed, |rng| rng.next_f64(), |a, b| a + b);
+        assert_eq!(run(3).map(f64::to_bits), run(3).map(f64::to_bits));
+        assert_ne!(run(3), run(4));
+        assert_eq!(
+            parallel_monte_carlo(0, 3, |rng| rng.next_u64(), u64::max),
+            None
+        );
+    }
+}
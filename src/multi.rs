@@ -0,0 +1,192 @@
+use rayon::prelude::*;
+use std::ops::Add;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// An aggregate that [`parallel_multi_reduce`] can compute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Aggregate {
+    /// The sum of all elements.
+    Sum,
+    /// The smallest element.
+    Min,
+    /// The largest element.
+    Max,
+    /// The number of elements.
+    Count,
+}
+
+/// The results of [`parallel_multi_reduce`].
+///
+/// Aggregates that were not requested are `None`; so are `sum`, `min`, and `max` for an
+/// empty input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aggregates<T> {
+    /// The sum of all elements.
+    pub sum: Option<T>,
+    /// The smallest element.
+    pub min: Option<T>,
+    /// The largest element.
+    pub max: Option<T>,
+    /// The number of elements.
+    pub count: Option<usize>,
+}
+
+/// Which aggregates to compute.
+#[derive(Clone, Copy)]
+struct Requested {
+    sum: bool,
+    min: bool,
+    max: bool,
+}
+
+/// Combines two optional partial results, keeping whichever exists if only one does.
+fn merge<T>(a: Option<T>, b: Option<T>, operation: impl Fn(T, T) -> T) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(operation(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn min_of<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max_of<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+fn fold_chunk<T>(chunk: &[T], requested: Requested) -> Aggregates<T>
+where
+    T: Copy + PartialOrd + Add<Output = T>,
+{
+    let mut acc = Aggregates {
+        sum: None,
+        min: None,
+        max: None,
+        count: Some(chunk.len()),
+    };
+    for &x in chunk {
+        if requested.sum {
+            acc.sum = Some(acc.sum.map_or(x, |s| s + x));
+        }
+        if requested.min {
+            acc.min = Some(acc.min.map_or(x, |m| min_of(m, x)));
+        }
+        if requested.max {
+            acc.max = Some(acc.max.map_or(x, |m| max_of(m, x)));
+        }
+    }
+    acc
+}
+
+fn combine<T>(a: Aggregates<T>, b: Aggregates<T>) -> Aggregates<T>
+where
+    T: Copy + PartialOrd + Add<Output = T>,
+{
+    Aggregates {
+        sum: merge(a.sum, b.sum, |x, y| x + y),
+        min: merge(a.min, b.min, min_of),
+        max: merge(a.max, b.max, max_of),
+        count: merge(a.count, b.count, |x, y| x + y),
+    }
+}
+
+/// Computes several aggregates of a slice in a single parallel pass.
+///
+/// Running separate reductions reads the data once per aggregate; this reads it once in
+/// total, which matters for vectors larger than the caches. Min and max ignore elements
+/// that do not compare (such as NaN) unless they come first in a chunk.
+///
+/// # Parameters
+/// - `data`: The elements to aggregate.
+/// - `aggregates`: The aggregates to compute.
+///
+/// # Returns
+/// The requested aggregates; all others are `None`.
+pub fn parallel_multi_reduce<T>(data: &[T], aggregates: &[Aggregate]) -> Aggregates<T>
+where
+    T: Copy + Send + Sync + PartialOrd + Add<Output = T>,
+{
+    let requested = Requested {
+        sum: aggregates.contains(&Aggregate::Sum),
+        min: aggregates.contains(&Aggregate::Min),
+        max: aggregates.contains(&Aggregate::Max),
+    };
+
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    let mut result = data
+        .par_chunks(chunk_size)
+        .map(|chunk| fold_chunk(chunk, requested))
+        .reduce_with(combine)
+        .unwrap_or(Aggregates {
+            sum: None,
+            min: None,
+            max: None,
+            count: None,
+        });
+
+    result.count = if aggregates.contains(&Aggregate::Count) {
+        Some(data.len())
+    } else {
+        None
+    };
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test computing every aggregate at once
+    #[test]
+    fn test_multi_reduce_all() {
+        let data: Vec<i64> = (-500..=1_000).collect();
+        let result = parallel_multi_reduce(
+            &data,
+            &[
+                Aggregate::Sum,
+                Aggregate::Min,
+                Aggregate::Max,
+                Aggregate::Count,
+            ],
+        );
+        assert_eq!(result.sum, Some(data.iter().sum()));
+        assert_eq!(result.min, Some(-500));
+        assert_eq!(result.max, Some(1_000));
+        assert_eq!(result.count, Some(1_501));
+    }
+
+    // Test that only the requested aggregates are returned
+    #[test]
+    fn test_multi_reduce_subset() {
+        let data = vec![2.5f64, -1.0, 7.0];
+        let result = parallel_multi_reduce(&data, &[Aggregate::Max, Aggregate::Count]);
+        assert_eq!(
+            result,
+            Aggregates {
+                sum: None,
+                min: None,
+                max: Some(7.0),
+                count: Some(3),
+            }
+        );
+    }
+
+    // Test for an empty slice
+    #[test]
+    fn test_multi_reduce_empty() {
+        let result = parallel_multi_reduce::<u32>(&[], &[Aggregate::Sum, Aggregate::Count]);
+        assert_eq!(result.sum, None);
+        assert_eq!(result.count, Some(0));
+    }
+}
@@ -0,0 +1,87 @@
+use crate::identity::reduce_slice_with_identity;
+
+/// A reduction fed in batches, for streaming ingestion.
+///
+/// Each [`push`](OnlineReducer::push) reduces its batch in parallel and folds the result
+/// into a running value, so only one partial result is kept however much data arrives.
+/// Batches are combined in the order they are pushed.
+#[derive(Debug, Clone)]
+pub struct OnlineReducer<T> {
+    identity: T,
+    operation: fn(T, T) -> T,
+    value: T,
+    count: usize,
+}
+
+impl<T> OnlineReducer<T>
+where
+    T: Copy + Send + Sync,
+{
+    /// Creates a reducer that has seen no elements yet.
+    ///
+    /// # Parameters
+    /// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+    /// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+    pub fn new(identity: T, operation: fn(T, T) -> T) -> Self {
+        OnlineReducer {
+            identity,
+            operation,
+            value: identity,
+            count: 0,
+        }
+    }
+
+    /// Reduces a batch in parallel and folds it into the running value.
+    pub fn push(&mut self, batch: &[T]) {
+        if batch.is_empty() {
+            return;
+        }
+        let partial = reduce_slice_with_identity(batch, self.identity, self.operation);
+        self.value = (self.operation)(self.value, partial);
+        self.count += batch.len();
+    }
+
+    /// Returns the reduction of everything pushed so far (the identity if nothing was).
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Returns the number of elements pushed so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Consumes the reducer and returns the reduction of everything pushed.
+    pub fn finish(self) -> T {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that pushing batches equals reducing all the data at once
+    #[test]
+    fn test_online_batches() {
+        let mut reducer = OnlineReducer::new(0u64, |a, b| a + b);
+        for start in (0..100_000u64).step_by(7_919) {
+            let batch: Vec<u64> = (start..(start + 7_919).min(100_000)).collect();
+            reducer.push(&batch);
+        }
+        assert_eq!(reducer.count(), 100_000);
+        assert_eq!(reducer.finish(), 99_999 * 100_000 / 2);
+    }
+
+    // Test that batch order is preserved for an order-sensitive operation
+    #[test]
+    fn test_online_order() {
+        // Keeps the first non-zero value seen
+        let mut reducer = OnlineReducer::new(0i32, |a, b| if a != 0 { a } else { b });
+        reducer.push(&[]);
+        assert_eq!(reducer.value(), 0);
+        reducer.push(&[0, 5, 6]);
+        reducer.push(&[7]);
+        assert_eq!(reducer.finish(), 5);
+    }
+}
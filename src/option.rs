@@ -0,0 +1,79 @@
+use rayon::prelude::*;
+
+use crate::error::catch_panic;
+use crate::platform::available_threads;
+use crate::{aligned_chunk_size, Error};
+
+/// Performs a parallel binary operation over the present values of a vector of options.
+///
+/// `None` entries are treated as missing data and skipped, which is the usual way to
+/// aggregate sparse measurements.
+///
+/// # Parameters
+/// - `data`: A vector of optional values.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to every `Some` value, or `None` if there are none.
+pub fn parallel_reduce_some<T>(data: Vec<Option<T>>, operation: fn(T, T) -> T) -> Option<T>
+where
+    T: Send,
+{
+    let chunk_size = aligned_chunk_size(&data, available_threads());
+    data.into_par_iter()
+        .with_min_len(chunk_size)
+        .flatten()
+        .reduce_with(operation)
+}
+
+/// Performs a parallel binary operation over the present values of a vector of options,
+/// reporting failures as errors.
+///
+/// # Parameters
+/// - `data`: A vector of optional values.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to every `Some` value, [`Error::EmptyInput`] if
+/// there are none, or [`Error::OperationPanicked`].
+pub fn try_parallel_reduce_some<T>(
+    data: Vec<Option<T>>,
+    operation: fn(T, T) -> T,
+) -> Result<T, Error>
+where
+    T: Send,
+{
+    catch_panic(|| parallel_reduce_some(data, operation))?.ok_or(Error::EmptyInput)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that None entries are skipped
+    #[test]
+    fn test_reduce_some_skips_none() {
+        let data: Vec<Option<i32>> = (1..=100)
+            .map(|x| if x % 2 == 0 { Some(x) } else { None })
+            .collect();
+        assert_eq!(parallel_reduce_some(data, |a, b| a + b), Some(2550));
+    }
+
+    // Test with an operation that has no numeric identity
+    #[test]
+    fn test_reduce_some_min() {
+        let data = vec![None, Some(7), Some(-3), None, Some(12)];
+        assert_eq!(parallel_reduce_some(data, i32::min), Some(-3));
+    }
+
+    // Test that all-None input yields None
+    #[test]
+    fn test_reduce_some_all_none() {
+        let data: Vec<Option<u8>> = vec![None; 10];
+        assert_eq!(parallel_reduce_some(data.clone(), |a, b| a + b), None);
+        assert_eq!(
+            try_parallel_reduce_some(data, |a, b| a + b),
+            Err(Error::EmptyInput)
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Reductions over binary data too large to hold in memory.
+//!
+//! The input is streamed in fixed-size blocks of native-endian numbers. While one block
+//! is reduced in parallel on the thread pool, the next is read into a second buffer on
+//! the calling thread, so at most two blocks are ever resident and disk and CPU work
+//! overlap. The per-block results are combined in input order.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::mem;
+use std::path::Path;
+use std::slice;
+
+use crate::identity::reduce_slice_with_identity;
+use crate::Pod;
+
+/// Size in bytes of the blocks [`reduce_file`] reads at a time.
+pub const DEFAULT_BLOCK_BYTES: usize = 64 * 1024 * 1024;
+
+/// Reduces a stream of fixed-width binary numbers block by block.
+///
+/// # Parameters
+/// - `reader`: The source of native-endian values.
+/// - `block_len`: The number of elements per block.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to every value, or an error if reading fails or
+/// the stream ends partway through an element.
+pub fn reduce_reader<R, T>(
+    mut reader: R,
+    block_len: usize,
+    identity: T,
+    operation: fn(T, T) -> T,
+) -> io::Result<T>
+where
+    R: Read + Send,
+    T: Pod,
+{
+    let block_len = block_len.max(1);
+    let mut current = vec![identity; block_len];
+    let mut next = vec![identity; block_len];
+
+    let mut result = identity;
+    let mut filled = read_block(&mut reader, &mut current)?;
+    while filled > 0 {
+        let (partial, next_filled) = rayon::join(
+            || reduce_slice_with_identity(&current[..filled], identity, operation),
+            || read_block(&mut reader, &mut next),
+        );
+        result = operation(result, partial);
+        filled = next_filled?;
+        mem::swap(&mut current, &mut next);
+    }
+    Ok(result)
+}
+
+/// Reduces a binary file of fixed-width numbers in blocks of [`DEFAULT_BLOCK_BYTES`].
+///
+/// # Parameters
+/// - `path`: The file to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to every value in the file, or an error if the
+/// file cannot be read or its length is not a multiple of `size_of::<T>()`.
+pub fn reduce_file<T, P>(path: P, identity: T, operation: fn(T, T) -> T) -> io::Result<T>
+where
+    T: Pod,
+    P: AsRef<Path>,
+{
+    let block_len = DEFAULT_BLOCK_BYTES / mem::size_of::<T>().max(1);
+    reduce_reader(File::open(path)?, block_len, identity, operation)
+}
+
+/// Fills `block` from `reader`, returning how many whole elements were read.
+///
+/// Fewer than `block.len()` elements are only returned at the end of the stream.
+fn read_block<R: Read, T: Pod>(reader: &mut R, block: &mut [T]) -> io::Result<usize> {
+    let size = mem::size_of::<T>();
+    // SAFETY: `T` is plain old data, so its memory may be viewed and overwritten as bytes
+    let bytes = unsafe {
+        slice::from_raw_parts_mut(block.as_mut_ptr() as *mut u8, mem::size_of_val(block))
+    };
+
+    let mut filled = 0;
+    while filled < bytes.len() {
+        match reader.read(&mut bytes[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    if filled % size != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream length is not a multiple of the element size",
+        ));
+    }
+    Ok(filled / size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_bytes(values: &[u64]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+    }
+
+    // Test a stream spanning many blocks, including a partial last block
+    #[test]
+    fn test_reduce_reader_blocks() {
+        let values: Vec<u64> = (1..=10_001).collect();
+        let bytes = to_bytes(&values);
+        let result = reduce_reader(bytes.as_slice(), 1_000, 0u64, |a, b| a + b);
+        assert_eq!(result.unwrap(), 50_015_001);
+        let result = reduce_reader(&[][..], 16, 0u64, |a, b| a + b);
+        assert_eq!(result.unwrap(), 0);
+    }
+
+    // Test that a truncated element is reported
+    #[test]
+    fn test_reduce_reader_truncated() {
+        let mut bytes = to_bytes(&[1, 2, 3]);
+        bytes.pop();
+        let result = reduce_reader(bytes.as_slice(), 2, 0u64, |a, b| a + b);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    // Test reducing a file
+    #[test]
+    fn test_reduce_file() {
+        let path = std::env::temp_dir().join(format!("out_of_core_{}.bin", std::process::id()));
+        std::fs::write(&path, to_bytes(&[7, 3, 9, 4])).unwrap();
+        let result = reduce_file(&path, 0u64, u64::max);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap(), 9);
+    }
+}
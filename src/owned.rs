@@ -0,0 +1,80 @@
+use rayon::prelude::*;
+
+use crate::error::catch_panic;
+use crate::platform::available_threads;
+use crate::{aligned_chunk_size, Error};
+
+/// Performs a parallel binary operation on elements that are not `Copy`.
+///
+/// Elements are moved into the operation instead of copied, so heap-backed types such as
+/// arbitrary-precision integers (e.g. `num_bigint::BigUint`) can be reduced in parallel.
+/// No identity value is needed: every chunk is folded starting from its first element,
+/// and the operation is always applied in input order.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or `None` for an empty vector.
+pub fn parallel_reduce_owned<T>(data: Vec<T>, operation: fn(T, T) -> T) -> Option<T>
+where
+    T: Send,
+{
+    let chunk_size = aligned_chunk_size(&data, available_threads());
+    data.into_par_iter()
+        .with_min_len(chunk_size)
+        .reduce_with(operation)
+}
+
+/// Performs a parallel binary operation on elements that are not `Copy`, reporting
+/// failures as errors.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, [`Error::EmptyInput`] for an
+/// empty vector, or [`Error::OperationPanicked`].
+pub fn try_parallel_reduce_owned<T>(data: Vec<T>, operation: fn(T, T) -> T) -> Result<T, Error>
+where
+    T: Send,
+{
+    catch_panic(|| parallel_reduce_owned(data, operation))?.ok_or(Error::EmptyInput)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test with a heap-allocated, order-sensitive operation
+    #[test]
+    fn test_owned_string_concat() {
+        let data: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+        let expected: String = data.concat();
+        let result = parallel_reduce_owned(data, |a, b| a + &b);
+        assert_eq!(result, Some(expected));
+    }
+
+    // Test with a boxed numeric type
+    #[test]
+    fn test_owned_boxed_product() {
+        let data: Vec<Box<u128>> = (1..=30).map(Box::new).collect();
+        let result = parallel_reduce_owned(data, |a, b| Box::new(*a * *b));
+        assert_eq!(result.map(|r| *r), Some((1..=30u128).product()));
+    }
+
+    // Test for empty vector
+    #[test]
+    fn test_owned_empty() {
+        assert_eq!(
+            parallel_reduce_owned(Vec::<String>::new(), |a, b| a + &b),
+            None
+        );
+        assert_eq!(
+            try_parallel_reduce_owned(Vec::<String>::new(), |a, b| a + &b),
+            Err(Error::EmptyInput)
+        );
+    }
+}
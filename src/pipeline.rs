@@ -0,0 +1,245 @@
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// A lazy map/filter/reduce pipeline over a slice.
+///
+/// Stages are composed into a single function and only run when the pipeline is reduced,
+/// so every element passes through all stages in one parallel pass per chunk, without
+/// intermediate vectors.
+pub struct Pipeline<'a, S, F> {
+    data: &'a [S],
+    stage: F,
+}
+
+impl<'a, S> Pipeline<'a, S, fn(S) -> Option<S>>
+where
+    S: Copy + Sync,
+{
+    /// Starts a pipeline over `data`.
+    pub fn new(data: &'a [S]) -> Self {
+        Pipeline { data, stage: Some }
+    }
+}
+
+impl<'a, S, T, F> Pipeline<'a, S, F>
+where
+    S: Copy + Sync,
+    F: Fn(S) -> Option<T> + Sync,
+{
+    /// Adds a stage that transforms every element that reaches it.
+    pub fn map<U, G>(self, transform: G) -> Pipeline<'a, S, impl Fn(S) -> Option<U> + Sync>
+    where
+        G: Fn(T) -> U + Sync,
+    {
+        let stage = self.stage;
+        Pipeline {
+            data: self.data,
+            stage: move |x| stage(x).map(&transform),
+        }
+    }
+
+    /// Adds a stage that drops every element for which `predicate` returns `false`.
+    pub fn filter<P>(self, predicate: P) -> Pipeline<'a, S, impl Fn(S) -> Option<T> + Sync>
+    where
+        P: Fn(&T) -> bool + Sync,
+    {
+        let stage = self.stage;
+        Pipeline {
+            data: self.data,
+            stage: move |x| stage(x).filter(&predicate),
+        }
+    }
+
+    /// Adds a stage that passes every element that reaches it to `observer`, unchanged.
+    ///
+    /// For debugging: the observer runs on the worker threads, in no particular order.
+    pub fn inspect<G>(self, observer: G) -> Pipeline<'a, S, impl Fn(S) -> Option<T> + Sync>
+    where
+        G: Fn(&T) + Sync,
+    {
+        let stage = self.stage;
+        Pipeline {
+            data: self.data,
+            stage: move |x| stage(x).inspect(&observer),
+        }
+    }
+
+    /// Like [`Pipeline::inspect`], but observes only every `n`th element that reaches
+    /// the stage, to keep logging from large pipelines readable and cheap.
+    ///
+    /// Which elements are observed depends on thread scheduling; how many does not.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0.
+    pub fn inspect_every<G>(
+        self,
+        n: usize,
+        observer: G,
+    ) -> Pipeline<'a, S, impl Fn(S) -> Option<T> + Sync>
+    where
+        G: Fn(&T) + Sync,
+    {
+        assert!(n > 0, "sampling interval must be positive");
+        let seen = AtomicUsize::new(0);
+        self.inspect(move |x| {
+            if seen.fetch_add(1, Ordering::Relaxed).is_multiple_of(n) {
+                observer(x);
+            }
+        })
+    }
+
+    /// Runs the pipeline and reduces the surviving elements.
+    ///
+    /// No identity value is needed: every chunk is folded starting from its first
+    /// surviving element.
+    ///
+    /// # Parameters
+    /// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+    ///
+    /// # Returns
+    /// The result of applying the operation to all surviving elements, or `None` if no
+    /// element survives.
+    pub fn reduce(self, operation: fn(T, T) -> T) -> Option<T>
+    where
+        T: Send,
+    {
+        let chunk_size = aligned_chunk_size(self.data, available_threads());
+        let stage = &self.stage;
+        self.data
+            .par_chunks(chunk_size)
+            .filter_map(|chunk| chunk.iter().filter_map(|&x| stage(x)).reduce(operation))
+            .reduce_with(operation)
+    }
+
+    /// Runs the pipeline and reduces the surviving elements, starting from `identity`.
+    ///
+    /// # Parameters
+    /// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+    /// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+    ///
+    /// # Returns
+    /// The result of applying the operation to all surviving elements, or `identity` if
+    /// no element survives.
+    pub fn reduce_with_identity(self, identity: T, operation: fn(T, T) -> T) -> T
+    where
+        T: Copy + Send + Sync,
+    {
+        self.reduce(operation)
+            .map_or(identity, |result| operation(identity, result))
+    }
+}
+
+/// Passes every element to `observer` in parallel, without modifying it.
+///
+/// For debugging; the observer runs on the worker threads, in no particular order.
+///
+/// # Parameters
+/// - `data`: The elements to observe.
+/// - `observer`: Called once per element.
+///
+/// # Returns
+/// `data`, so the call can wrap the input of another operation.
+pub fn parallel_inspect<T, G>(data: &[T], observer: G) -> &[T]
+where
+    T: Sync,
+    G: Fn(&T) + Sync,
+{
+    data.par_iter().for_each(&observer);
+    data
+}
+
+/// Passes every `n`th element, starting with the first, to `observer` in parallel.
+///
+/// # Parameters
+/// - `data`: The elements to observe.
+/// - `n`: The sampling interval.
+/// - `observer`: Called once per sampled element.
+///
+/// # Returns
+/// `data`, so the call can wrap the input of another operation.
+///
+/// # Panics
+/// Panics if `n` is 0.
+pub fn parallel_inspect_every<T, G>(data: &[T], n: usize, observer: G) -> &[T]
+where
+    T: Sync,
+    G: Fn(&T) + Sync,
+{
+    assert!(n > 0, "sampling interval must be positive");
+    data.par_iter().step_by(n).for_each(&observer);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test a fused map, filter, and reduce
+    #[test]
+    fn test_pipeline_map_filter_reduce() {
+        let data: Vec<i64> = (1..=10_000).collect();
+        let result = Pipeline::new(&data)
+            .map(|x| x * x)
+            .filter(|x| x % 2 == 0)
+            .reduce(|a, b| a + b);
+        let expected: i64 = data.iter().map(|x| x * x).filter(|x| x % 2 == 0).sum();
+        assert_eq!(result, Some(expected));
+    }
+
+    // Test that stages can change the element type
+    #[test]
+    fn test_pipeline_changes_type() {
+        let words = ["a", "bb", "ccc", "dddd"];
+        let longest = Pipeline::new(&words).map(str::len).reduce(usize::max);
+        assert_eq!(longest, Some(4));
+    }
+
+    // Test a pipeline that filters everything out
+    #[test]
+    fn test_pipeline_empty() {
+        let data: Vec<i32> = (1..=100).collect();
+        let pipeline = Pipeline::new(&data).filter(|&x| x > 1_000);
+        assert_eq!(pipeline.reduce_with_identity(0, |a, b| a + b), 0);
+    }
+
+    // Test that inspection stages observe without changing the result
+    #[test]
+    fn test_pipeline_inspect() {
+        let data: Vec<u64> = (1..=10_000).collect();
+        let (observed, sampled) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let result = Pipeline::new(&data)
+            .filter(|x| x % 2 == 0)
+            .inspect(|_| {
+                observed.fetch_add(1, Ordering::Relaxed);
+            })
+            .inspect_every(100, |_| {
+                sampled.fetch_add(1, Ordering::Relaxed);
+            })
+            .reduce(|a, b| a + b);
+        assert_eq!(result, Some(25_005_000));
+        assert_eq!(observed.load(Ordering::Relaxed), 5_000);
+        assert_eq!(sampled.load(Ordering::Relaxed), 50);
+    }
+
+    // Test standalone inspection, full and sampled
+    #[test]
+    fn test_parallel_inspect() {
+        let data: Vec<u32> = (0..1_000).collect();
+        let sum = AtomicUsize::new(0);
+        let passed = parallel_inspect(&data, |&x| {
+            sum.fetch_add(x as usize, Ordering::Relaxed);
+        });
+        assert_eq!(passed, &data[..]);
+        assert_eq!(sum.load(Ordering::Relaxed), 499_500);
+
+        let sampled = AtomicUsize::new(0);
+        parallel_inspect_every(&data, 300, |&x| {
+            assert_eq!(x % 300, 0);
+            sampled.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(sampled.load(Ordering::Relaxed), 4);
+    }
+}
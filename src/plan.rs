@@ -0,0 +1,163 @@
+use std::mem;
+use std::ops::Range;
+
+use crate::platform::parallel_worthwhile;
+use crate::{aligned_chunk_size_for, ParallelConfig};
+
+/// Description of how a reduction over `data_len` elements would be executed.
+///
+/// Produced by [`plan`] without touching any data, so it can be inspected in tests or
+/// used to decide whether parallelizing is worthwhile at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionPlan {
+    /// Number of elements the plan was made for.
+    pub data_len: usize,
+    /// Number of threads the work is split across (deterministic runs fold these chunks on
+    /// one worker).
+    pub threads: usize,
+    /// Number of elements per chunk (the last chunk may be shorter).
+    pub chunk_size: usize,
+    /// Index range covered by each chunk, in order.
+    pub chunks: Vec<Range<usize>>,
+    /// Whether the reduction would run sequentially on the calling thread.
+    pub sequential: bool,
+}
+
+/// Plans a reduction without executing it.
+///
+/// # Parameters
+/// - `data_len`: The number of elements that would be reduced.
+/// - `config`: The configuration the reduction would run with.
+///
+/// # Returns
+/// An [`ExecutionPlan`] with the chunk boundaries, thread count, and whether the
+/// sequential fallback would be chosen.
+pub fn plan(data_len: usize, config: &ParallelConfig) -> ExecutionPlan {
+    plan_with_element_size(data_len, config, 0)
+}
+
+/// Plans a reduction over elements of type `T` without executing it.
+///
+/// Unlike [`plan`], the even split is rounded up so chunk boundaries fall on cache-line
+/// multiples for `T`, which is how the crate's reductions split vectors and slices.
+/// Iterators that are not collected first are split by rayon instead.
+///
+/// # Parameters
+/// - `data_len`: The number of elements that would be reduced.
+/// - `config`: The configuration the reduction would run with.
+///
+/// # Returns
+/// An [`ExecutionPlan`] with the chunk boundaries, thread count, and whether the
+/// sequential fallback would be chosen.
+pub fn plan_for<T>(data_len: usize, config: &ParallelConfig) -> ExecutionPlan {
+    plan_with_element_size(data_len, config, mem::size_of::<T>())
+}
+
+/// Plans a reduction, aligning chunks for elements of `element_size` bytes (0 for none).
+fn plan_with_element_size(
+    data_len: usize,
+    config: &ParallelConfig,
+    element_size: usize,
+) -> ExecutionPlan {
+    let threads = config.resolved_threads();
+    let sequential =
+        !parallel_worthwhile(threads) || data_len < 2 || data_len < config.sequential_threshold;
+    let threads = if sequential { 1 } else { threads };
+    let chunk_size = if sequential {
+        data_len.max(1)
+    } else {
+        config
+            .chunk_size
+            .unwrap_or_else(|| {
+                config.bound_chunk_size(aligned_chunk_size_for(data_len, threads, element_size))
+            })
+            .max(1)
+    };
+
+    let chunks = (0..data_len)
+        .step_by(chunk_size)
+        .map(|start| start..(start + chunk_size).min(data_len))
+        .collect();
+
+    ExecutionPlan {
+        data_len,
+        threads,
+        chunk_size,
+        chunks,
+        sequential,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that the chunks cover the data exactly once
+    #[test]
+    fn test_plan_covers_data() {
+        let plan = plan(10, &ParallelConfig::new().with_threads(3));
+        assert_eq!(plan.chunk_size, 4);
+        assert_eq!(plan.chunks, vec![0..4, 4..8, 8..10]);
+        assert!(!plan.sequential);
+    }
+
+    // Test that short inputs fall back to sequential execution
+    #[test]
+    fn test_plan_sequential_fallback() {
+        let config = ParallelConfig::new().with_sequential_threshold(100);
+        let plan = plan(50, &config);
+        assert!(plan.sequential);
+        assert_eq!(plan.threads, 1);
+        assert_eq!(plan.chunks, vec![0..50]);
+    }
+
+    // Test that typed plans put chunk boundaries on cache-line multiples
+    #[test]
+    fn test_plan_for_aligned() {
+        let config = ParallelConfig::new().with_threads(3);
+        let plan = plan_for::<f64>(1_000, &config);
+        assert_eq!(plan.chunk_size, 336);
+        assert_eq!(plan.chunks, vec![0..336, 336..672, 672..1_000]);
+        assert_eq!(plan_for::<[u8; 128]>(10, &config).chunk_size, 4);
+    }
+
+    // Test that granularity bounds clamp the automatic chunk size only
+    #[test]
+    fn test_plan_granularity() {
+        let config = ParallelConfig::new().with_threads(4).with_min_len(400);
+        assert_eq!(
+            plan(1_000, &config).chunks,
+            vec![0..400, 400..800, 800..1_000]
+        );
+        let config = ParallelConfig::new().with_threads(2).with_max_len(100);
+        assert_eq!(plan(1_000, &config).chunks.len(), 10);
+        let config = config.with_chunk_size(500);
+        assert_eq!(plan(1_000, &config).chunk_size, 500);
+    }
+
+    // Test that a single thread falls back to sequential execution
+    #[test]
+    fn test_plan_single_thread() {
+        let plan = plan(1_000, &ParallelConfig::new().with_threads(1));
+        assert!(plan.sequential);
+        assert_eq!(plan.chunks, vec![0..1_000]);
+    }
+
+    // Test that deterministic plans chunk the same way on every machine
+    #[test]
+    fn test_plan_deterministic() {
+        let config = ParallelConfig::new().with_deterministic();
+        let plan = plan(1_000, &config);
+        assert_eq!(plan.threads, 4);
+        assert_eq!(plan.chunks, vec![0..250, 250..500, 500..750, 750..1_000]);
+        assert_eq!(config.pool_size(), Some(1));
+    }
+
+    // Test for empty input
+    #[test]
+    fn test_plan_empty() {
+        let plan = plan(0, &ParallelConfig::new());
+        assert!(plan.sequential);
+        assert!(plan.chunks.is_empty());
+    }
+}
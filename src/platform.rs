@@ -0,0 +1,127 @@
+//! Target-specific behavior.
+//!
+//! On `wasm32` targets built without the `atomics` target feature there are no threads,
+//! no clock, and no way to sleep. Everything that depends on those goes through this
+//! module, so the rest of the crate compiles unchanged and degrades to sequential
+//! execution there. Browser builds with `atomics` enabled use rayon normally once the
+//! embedder has started a worker pool (e.g. with `wasm-bindgen-rayon`'s `initThreadPool`).
+
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+/// Whether the target can run more than one thread.
+pub(crate) const THREADS_AVAILABLE: bool =
+    !cfg!(all(target_arch = "wasm32", not(target_feature = "atomics")));
+
+/// Whether the target has a monotonic clock (`Instant::now` panics without one).
+const CLOCK_AVAILABLE: bool = !cfg!(all(target_arch = "wasm32", target_os = "unknown"));
+
+/// Returns the number of threads to split work across by default.
+pub(crate) fn available_threads() -> usize {
+    if THREADS_AVAILABLE {
+        num_cpus::get()
+    } else {
+        1
+    }
+}
+
+/// Returns the number of physical cores usable by default, at least 1.
+///
+/// Capped at [`available_threads`], which honors CPU affinity and quotas that the
+/// physical core count does not.
+pub(crate) fn physical_cores() -> usize {
+    num_cpus::get_physical().clamp(1, available_threads())
+}
+
+/// Returns the number of physical performance cores, at least 1.
+///
+/// Hybrid Intel CPUs list their efficiency cores in `/sys/devices/cpu_atom/cpus` on
+/// Linux; efficiency cores have no SMT siblings, so each one listed is one physical core
+/// less. Elsewhere every physical core counts.
+pub(crate) fn performance_cores() -> usize {
+    let efficiency = std::fs::read_to_string("/sys/devices/cpu_atom/cpus")
+        .map_or(0, |list| cpu_list_len(list.trim()));
+    physical_cores().saturating_sub(efficiency).max(1)
+}
+
+/// Counts the CPUs in a kernel CPU list such as `0-7,16,18-19`.
+fn cpu_list_len(list: &str) -> usize {
+    list.split(',')
+        .filter(|range| !range.is_empty())
+        .map(|range| match range.split_once('-') {
+            Some((first, last)) => match (first.parse::<usize>(), last.parse::<usize>()) {
+                (Ok(first), Ok(last)) => (last + 1).saturating_sub(first),
+                _ => 0,
+            },
+            None => usize::from(range.parse::<usize>().is_ok()),
+        })
+        .sum()
+}
+
+/// Returns whether worker threads can actually be started.
+///
+/// Sandboxes and containers can forbid or limit thread creation, in which case rayon
+/// panics the first time its global pool is used. This spawns one probe thread on the
+/// first call and caches the answer, so the reductions can fall back to the sequential
+/// path instead.
+pub(crate) fn threads_usable() -> bool {
+    static USABLE: OnceLock<bool> = OnceLock::new();
+    *USABLE.get_or_init(|| {
+        THREADS_AVAILABLE
+            && thread::Builder::new()
+                .name(String::from("parallel-operations-probe"))
+                .spawn(|| {})
+                .is_ok_and(|probe| probe.join().is_ok())
+    })
+}
+
+/// Returns whether a reduction on `threads` threads should run in parallel at all.
+///
+/// A single thread, or threads that cannot be started, make the parallel path pure
+/// overhead.
+pub(crate) fn parallel_worthwhile(threads: usize) -> bool {
+    threads > 1 && threads_usable()
+}
+
+/// Measures elapsed time, or reports zero on targets without a clock.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stopwatch(Option<std::time::Instant>);
+
+impl Stopwatch {
+    pub(crate) fn start() -> Stopwatch {
+        Stopwatch(CLOCK_AVAILABLE.then(std::time::Instant::now))
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.0.map_or(Duration::ZERO, |start| start.elapsed())
+    }
+}
+
+/// Sleeps the current thread, or returns immediately on targets that cannot sleep.
+pub(crate) fn sleep(duration: Duration) {
+    if THREADS_AVAILABLE && !duration.is_zero() {
+        std::thread::sleep(duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test parsing kernel CPU lists
+    #[test]
+    fn test_cpu_list_len() {
+        assert_eq!(cpu_list_len("16-23"), 8);
+        assert_eq!(cpu_list_len("0-3,8,10-11"), 7);
+        assert_eq!(cpu_list_len(""), 0);
+        assert_eq!(cpu_list_len("x"), 0);
+    }
+
+    // Test that core counts stay within the available threads
+    #[test]
+    fn test_core_counts() {
+        assert!((1..=available_threads()).contains(&physical_cores()));
+        assert!((1..=physical_cores()).contains(&performance_cores()));
+    }
+}
@@ -0,0 +1,263 @@
+//! Minimal SVG line charts for benchmark results.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 400.0;
+const MARGIN: f64 = 60.0;
+const TICKS: usize = 5;
+const COLORS: [&str; 6] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b",
+];
+
+/// A named series of `(x, y)` points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    /// The legend label.
+    pub label: String,
+    /// The points, drawn in order.
+    pub points: Vec<(f64, f64)>,
+}
+
+impl Series {
+    /// Creates a series from a label and its points.
+    pub fn new(label: impl Into<String>, points: Vec<(f64, f64)>) -> Self {
+        Series {
+            label: label.into(),
+            points,
+        }
+    }
+}
+
+/// Axis titles and scaling for a chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    /// The chart title.
+    pub title: String,
+    /// The x-axis title.
+    pub x_label: String,
+    /// The y-axis title.
+    pub y_label: String,
+    /// Whether the x axis is logarithmic (base 10); non-positive x values are skipped.
+    pub log_x: bool,
+}
+
+impl Chart {
+    /// Creates a chart with linear axes.
+    pub fn new(
+        title: impl Into<String>,
+        x_label: impl Into<String>,
+        y_label: impl Into<String>,
+    ) -> Self {
+        Chart {
+            title: title.into(),
+            x_label: x_label.into(),
+            y_label: y_label.into(),
+            log_x: false,
+        }
+    }
+
+    /// Makes the x axis logarithmic.
+    pub fn with_log_x(mut self) -> Self {
+        self.log_x = true;
+        self
+    }
+
+    /// Renders the series as an SVG line chart.
+    ///
+    /// The y axis always starts at zero.
+    ///
+    /// # Returns
+    /// The SVG document.
+    pub fn render(&self, series: &[Series]) -> String {
+        let scale_x = |x: f64| if self.log_x { x.log10() } else { x };
+        let points: Vec<Vec<(f64, f64)>> = series
+            .iter()
+            .map(|s| {
+                s.points
+                    .iter()
+                    .filter(|(x, y)| (!self.log_x || *x > 0.0) && x.is_finite() && y.is_finite())
+                    .map(|&(x, y)| (scale_x(x), y))
+                    .collect()
+            })
+            .collect();
+
+        let xs = points.iter().flatten().map(|p| p.0);
+        let x_min = xs.clone().fold(f64::INFINITY, f64::min);
+        let x_max = xs.fold(f64::NEG_INFINITY, f64::max);
+        let y_max = points.iter().flatten().map(|p| p.1).fold(0.0, f64::max);
+        let (x_min, x_max) = if x_min.is_finite() && x_max > x_min {
+            (x_min, x_max)
+        } else if x_min.is_finite() {
+            (x_min - 1.0, x_min + 1.0)
+        } else {
+            (0.0, 1.0)
+        };
+        let y_max = if y_max > 0.0 { y_max * 1.1 } else { 1.0 };
+
+        let plot_w = WIDTH - 2.0 * MARGIN;
+        let plot_h = HEIGHT - 2.0 * MARGIN;
+        let px = |x: f64| MARGIN + (x - x_min) / (x_max - x_min) * plot_w;
+        let py = |y: f64| HEIGHT - MARGIN - y / y_max * plot_h;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+            w = WIDTH,
+            h = HEIGHT
+        );
+        let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="middle" font-size="16">{}</text>"#,
+            WIDTH / 2.0,
+            MARGIN / 2.0,
+            escape(&self.title)
+        );
+
+        // Axes, ticks, and grid lines
+        let _ = writeln!(
+            svg,
+            r#"<path d="M{l} {t} V{b} H{r}" stroke="black" fill="none"/>"#,
+            l = MARGIN,
+            t = MARGIN,
+            b = HEIGHT - MARGIN,
+            r = WIDTH - MARGIN
+        );
+        for i in 0..=TICKS {
+            let fraction = i as f64 / TICKS as f64;
+            let x = x_min + fraction * (x_max - x_min);
+            let label = if self.log_x { 10f64.powf(x) } else { x };
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                px(x),
+                HEIGHT - MARGIN + 18.0,
+                format_tick(label)
+            );
+            let y = fraction * y_max;
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{l}" y1="{y:.1}" x2="{r}" y2="{y:.1}" stroke="#ddd"/><text x="{tx}" y="{ty:.1}" text-anchor="end">{label}</text>"##,
+                l = MARGIN,
+                r = WIDTH - MARGIN,
+                y = py(y),
+                tx = MARGIN - 6.0,
+                ty = py(y) + 4.0,
+                label = format_tick(y)
+            );
+        }
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+            WIDTH / 2.0,
+            HEIGHT - 15.0,
+            escape(&self.x_label)
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="15" y="{y}" text-anchor="middle" transform="rotate(-90 15 {y})">{}</text>"#,
+            escape(&self.y_label),
+            y = HEIGHT / 2.0
+        );
+
+        // One polyline, markers, and a legend entry per series
+        for (i, (s, points)) in series.iter().zip(&points).enumerate() {
+            let color = COLORS[i % COLORS.len()];
+            let coords: Vec<String> = points
+                .iter()
+                .map(|&(x, y)| format!("{:.1},{:.1}", px(x), py(y)))
+                .collect();
+            let _ = writeln!(
+                svg,
+                r#"<polyline points="{}" stroke="{}" stroke-width="2" fill="none"/>"#,
+                coords.join(" "),
+                color
+            );
+            for &(x, y) in points {
+                let _ = writeln!(
+                    svg,
+                    r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{}"/>"#,
+                    px(x),
+                    py(y),
+                    color
+                );
+            }
+            let ly = MARGIN + 16.0 * i as f64;
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="10" height="10" fill="{}"/><text x="{}" y="{}">{}</text>"#,
+                WIDTH - MARGIN - 120.0,
+                ly,
+                color,
+                WIDTH - MARGIN - 104.0,
+                ly + 9.0,
+                escape(&s.label)
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Renders the series and writes the SVG to `path`.
+    pub fn write<P: AsRef<Path>>(&self, path: P, series: &[Series]) -> io::Result<()> {
+        fs::write(path, self.render(series))
+    }
+}
+
+/// Formats an axis label compactly: integers without decimals, large values with a suffix.
+fn format_tick(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude >= 1e9 {
+        format!("{:.1}G", value / 1e9)
+    } else if magnitude >= 1e6 {
+        format!("{:.1}M", value / 1e6)
+    } else if magnitude >= 1e4 {
+        format!("{:.0}k", value / 1e3)
+    } else if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Escapes text for use inside SVG elements.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that a chart contains one polyline and marker per point
+    #[test]
+    fn test_render_chart() {
+        let series = [
+            Series::new("sum", vec![(1e3, 0.5), (1e5, 1.5), (1e7, 3.0)]),
+            Series::new("max", vec![(1e3, 0.4), (1e7, 2.5)]),
+        ];
+        let svg = Chart::new("Speedup", "elements", "speedup")
+            .with_log_x()
+            .render(&series);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches("<circle").count(), 5);
+    }
+
+    // Test that labels are escaped and empty charts still render
+    #[test]
+    fn test_render_escapes_and_empty() {
+        let svg = Chart::new("a < b & c", "x", "y").render(&[]);
+        assert!(svg.contains("a &lt; b &amp; c"));
+        assert_eq!(svg.matches("<polyline").count(), 0);
+    }
+}
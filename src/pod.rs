@@ -0,0 +1,13 @@
+/// Plain-old-data element types that can be read directly from file bytes.
+///
+/// # Safety
+/// Implementors must be valid for every bit pattern and contain no padding or pointers.
+pub unsafe trait Pod: Copy + Send + Sync + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
@@ -0,0 +1,285 @@
+use rayon::prelude::*;
+use std::sync::{Arc, RwLock};
+
+use crate::log;
+
+/// Callbacks invoked around the chunk folds and combine steps of a reduction.
+///
+/// Install one with [`set_profiler`] to attribute time inside user operations, e.g. to
+/// feed a flamegraph or a tracing system. Every method has an empty default, so an
+/// implementation only overrides the events it cares about. Callbacks run on the
+/// worker threads, and the start and end of one event always run on the same thread.
+pub trait Profiler: Send + Sync {
+    /// Called before chunk `index` of `len` elements is folded.
+    fn chunk_start(&self, _index: usize, _len: usize) {}
+    /// Called after chunk `index` of `len` elements has been folded.
+    fn chunk_end(&self, _index: usize, _len: usize) {}
+    /// Called before two partial results are combined.
+    fn combine_start(&self) {}
+    /// Called after two partial results have been combined.
+    fn combine_end(&self) {}
+}
+
+static PROFILER: RwLock<Option<Arc<dyn Profiler>>> = RwLock::new(None);
+
+/// Installs a profiler for all subsequent reductions, replacing any previous one.
+///
+/// Hooks are called by [`parallel_binary_operation`](crate::parallel_binary_operation),
+/// [`parallel_binary_operation_with_config`](crate::parallel_binary_operation_with_config),
+/// and [`parallel_reduce_with_identity`](crate::parallel_reduce_with_identity).
+pub fn set_profiler(profiler: impl Profiler + 'static) {
+    *PROFILER.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(profiler));
+}
+
+/// Removes the installed profiler, if any.
+pub fn clear_profiler() {
+    *PROFILER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Returns the installed profiler, if any.
+fn current() -> Option<Arc<dyn Profiler>> {
+    PROFILER.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Returns `true` if a profiler is installed.
+pub(crate) fn is_installed() -> bool {
+    PROFILER.read().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// Folds every chunk in parallel and combines the results, reporting to the profiler and
+/// the debug sink.
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `chunk_size`: The number of elements per chunk.
+/// - `initial`: The identity the combine step starts from.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `fold`: Folds one chunk into a partial result.
+///
+/// # Returns
+/// The combined result of all chunks.
+pub(crate) fn reduce_chunks<T, F>(
+    data: &[T],
+    chunk_size: usize,
+    initial: T,
+    operation: fn(T, T) -> T,
+    fold: F,
+) -> T
+where
+    T: Copy + Send + Sync,
+    F: Fn(&[T]) -> T + Sync,
+{
+    let profiler = current();
+    let logger = log::logger();
+    if profiler.is_none() && logger.is_none() {
+        return data
+            .par_chunks(chunk_size)
+            .map(&fold)
+            .reduce(|| initial, operation);
+    }
+
+    // Each partial carries the range of chunk indices it covers, so the debug records can
+    // show the combine order; the identity covers none.
+    data.par_chunks(chunk_size)
+        .enumerate()
+        .map(|(index, chunk)| {
+            if let Some(profiler) = &profiler {
+                profiler.chunk_start(index, chunk.len());
+            }
+            let partial = fold(chunk);
+            if let Some(profiler) = &profiler {
+                profiler.chunk_end(index, chunk.len());
+            }
+            if let Some(logger) = &logger {
+                let start = index * chunk_size;
+                logger(&format!(
+                    "folded chunk {} ({}..{}) on worker {}",
+                    index,
+                    start,
+                    start + chunk.len(),
+                    rayon::current_thread_index().unwrap_or(0)
+                ));
+            }
+            (Some(index..index + 1), partial)
+        })
+        .reduce(
+            || (None, initial),
+            |(left, a), (right, b)| {
+                if let Some(profiler) = &profiler {
+                    profiler.combine_start();
+                }
+                let combined = operation(a, b);
+                if let Some(profiler) = &profiler {
+                    profiler.combine_end();
+                }
+                let covered = match (left, right) {
+                    (Some(left), Some(right)) => {
+                        if let Some(logger) = &logger {
+                            logger(&format!("combined chunks {:?} with {:?}", left, right));
+                        }
+                        Some(left.start..right.end)
+                    }
+                    (left, right) => left.or(right),
+                };
+                (covered, combined)
+            },
+        )
+        .1
+}
+
+/// A profiler that aggregates time into folded stacks for flamegraph tools.
+///
+/// Time spent folding chunks is attributed to `<label>;chunk` and time spent combining to
+/// `<label>;combine`. [`write_folded`](FoldedStacks::write_folded) emits the
+/// `stack count` lines (counts in microseconds) read by `flamegraph.pl` and `inferno`.
+#[cfg(feature = "flamegraph")]
+#[derive(Debug)]
+pub struct FoldedStacks {
+    label: String,
+    chunk_micros: std::sync::atomic::AtomicU64,
+    combine_micros: std::sync::atomic::AtomicU64,
+}
+
+#[cfg(feature = "flamegraph")]
+std::thread_local! {
+    static STARTS: std::cell::RefCell<Vec<std::time::Instant>> = const {
+        std::cell::RefCell::new(Vec::new())
+    };
+}
+
+#[cfg(feature = "flamegraph")]
+impl FoldedStacks {
+    /// Creates a profiler whose stacks are rooted at `label`.
+    pub fn new(label: impl Into<String>) -> Arc<Self> {
+        Arc::new(FoldedStacks {
+            label: label.into(),
+            chunk_micros: Default::default(),
+            combine_micros: Default::default(),
+        })
+    }
+
+    /// Writes the collected stacks in folded format.
+    pub fn write_folded<W: std::io::Write>(&self, mut out: W) -> std::io::Result<()> {
+        use std::sync::atomic::Ordering;
+        writeln!(
+            out,
+            "{};chunk {}",
+            self.label,
+            self.chunk_micros.load(Ordering::Relaxed)
+        )?;
+        writeln!(
+            out,
+            "{};combine {}",
+            self.label,
+            self.combine_micros.load(Ordering::Relaxed)
+        )
+    }
+
+    fn start() {
+        STARTS.with(|starts| starts.borrow_mut().push(std::time::Instant::now()));
+    }
+
+    fn end(total: &std::sync::atomic::AtomicU64) {
+        if let Some(start) = STARTS.with(|starts| starts.borrow_mut().pop()) {
+            let micros = u64::try_from(start.elapsed().as_micros()).unwrap_or(u64::MAX);
+            total.fetch_add(micros, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(feature = "flamegraph")]
+impl Profiler for Arc<FoldedStacks> {
+    fn chunk_start(&self, _index: usize, _len: usize) {
+        FoldedStacks::start();
+    }
+    fn chunk_end(&self, _index: usize, _len: usize) {
+        FoldedStacks::end(&self.chunk_micros);
+    }
+    fn combine_start(&self) {
+        FoldedStacks::start();
+    }
+    fn combine_end(&self) {
+        FoldedStacks::end(&self.combine_micros);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    // Profilers are global, so tests that install one must not overlap
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    #[derive(Default)]
+    struct Counter {
+        chunks: AtomicUsize,
+        elements: AtomicUsize,
+        combines: AtomicUsize,
+    }
+
+    // Other tests reduce concurrently, so only count events from the test's own pool
+    fn on_test_pool() -> bool {
+        std::thread::current()
+            .name()
+            .is_some_and(|name| name.starts_with("profile-test"))
+    }
+
+    impl Profiler for Arc<Counter> {
+        fn chunk_end(&self, _index: usize, len: usize) {
+            if on_test_pool() {
+                self.chunks.fetch_add(1, Ordering::SeqCst);
+                self.elements.fetch_add(len, Ordering::SeqCst);
+            }
+        }
+        fn combine_end(&self) {
+            if on_test_pool() {
+                self.combines.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    // Test that every chunk and combine is reported
+    #[test]
+    fn test_profiler_hooks() {
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let counter = Arc::new(Counter::default());
+        set_profiler(counter.clone());
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .thread_name(|i| format!("profile-test-{}", i))
+            .build()
+            .unwrap();
+        let data: Vec<i64> = (1..=1_000).collect();
+        let result =
+            pool.install(|| reduce_chunks(&data, 100, 0, |a, b| a + b, |c| c.iter().sum()));
+        clear_profiler();
+
+        assert_eq!(result, 500_500);
+        assert_eq!(counter.chunks.load(Ordering::SeqCst), 10);
+        assert_eq!(counter.elements.load(Ordering::SeqCst), 1_000);
+        assert!(counter.combines.load(Ordering::SeqCst) >= 9);
+    }
+
+    // Test the folded-stack output of the built-in profiler
+    #[cfg(feature = "flamegraph")]
+    #[test]
+    fn test_folded_stacks() {
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let stacks = FoldedStacks::new("sum");
+        set_profiler(stacks.clone());
+        let data: Vec<u64> = (1..=100_000).collect();
+        reduce_chunks(&data, 1_000, 0, |a, b| a + b, |c| c.iter().sum());
+        clear_profiler();
+
+        let mut out = Vec::new();
+        stacks.write_folded(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("sum;chunk "));
+        assert!(lines[1].starts_with("sum;combine "));
+    }
+}
@@ -0,0 +1,197 @@
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::OnceLock;
+
+use crate::aligned_chunk_size;
+use crate::config::bound_chunk_size;
+use crate::executor::{reduce_chunks_with_executor, Executor};
+use crate::platform::{available_threads, parallel_worthwhile};
+use crate::ParallelConfig;
+
+/// A reduction prepared once and run many times.
+///
+/// The tuning profile is consulted, the thread count resolved, and any dedicated thread
+/// pool built only once, so repeated calls in a hot loop pay just for the reduction
+/// itself. The functions taking a [`ParallelConfig`] redo that work on every call.
+///
+/// Chunks run on rayon by default; [`Reducer::with_executor`] hands them to any other
+/// [`Executor`] instead, such as [`crate::SequentialExecutor`] in tests. This is the only
+/// way to put a reduction with a [`ParallelConfig`] on another backend: the free
+/// functions always run on rayon.
+pub struct Reducer<T> {
+    operation: fn(T, T) -> T,
+    threads: Option<usize>,
+    chunk_size: Option<usize>,
+    min_len: Option<usize>,
+    max_len: Option<usize>,
+    sequential_threshold: usize,
+    deterministic: bool,
+    pool: OnceLock<Option<ThreadPool>>,
+    executor: Option<Box<dyn Executor + Send + Sync>>,
+}
+
+impl<T> Reducer<T>
+where
+    T: Copy + Send + Sync,
+{
+    /// Creates a reducer for `operation` with the tuned default configuration.
+    pub fn new(operation: fn(T, T) -> T) -> Self {
+        Reducer::with_config(operation, &ParallelConfig::tuned::<T>())
+    }
+
+    /// Creates a reducer for `operation` with an explicit configuration.
+    ///
+    /// A duty-cycle throttle in `config` is not applied; worker limits and deterministic
+    /// execution are.
+    pub fn with_config(operation: fn(T, T) -> T, config: &ParallelConfig) -> Self {
+        let deterministic = config.is_deterministic();
+        Reducer {
+            operation,
+            threads: if deterministic {
+                Some(config.resolved_threads())
+            } else {
+                config.pool_size()
+            },
+            chunk_size: config.chunk_size,
+            min_len: config.min_len,
+            max_len: config.max_len,
+            sequential_threshold: config.sequential_threshold,
+            deterministic,
+            pool: OnceLock::new(),
+            executor: None,
+        }
+    }
+
+    /// Runs on a dedicated pool of `threads` threads (splits the input for `threads`
+    /// threads, if deterministic).
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads.max(1));
+        self.pool = OnceLock::new();
+        self
+    }
+
+    /// Sets a fixed chunk size.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = Some(chunk_size.max(1));
+        self
+    }
+
+    /// Sets the input length below which the reduction runs sequentially.
+    pub fn with_sequential_threshold(mut self, threshold: usize) -> Self {
+        self.sequential_threshold = threshold;
+        self
+    }
+
+    /// Runs the chunks on `executor` instead of rayon.
+    ///
+    /// The input is still split for the configured thread count; dedicated pools and
+    /// deterministic execution do not apply, as the executor decides where jobs run.
+    pub fn with_executor<E>(mut self, executor: E) -> Self
+    where
+        E: Executor + Send + Sync + 'static,
+    {
+        self.executor = Some(Box::new(executor));
+        self
+    }
+
+    /// Reduces `data`.
+    ///
+    /// No identity value is needed: every chunk is folded starting from its first element.
+    ///
+    /// # Parameters
+    /// - `data`: The elements to reduce.
+    ///
+    /// # Returns
+    /// The result of applying the operation to all elements, or `None` for an empty slice.
+    pub fn run(&self, data: &[T]) -> Option<T> {
+        let operation = self.operation;
+        let threads = self.threads.unwrap_or_else(available_threads);
+        if !parallel_worthwhile(threads) || data.len() < 2 || data.len() < self.sequential_threshold
+        {
+            return data.iter().copied().reduce(operation);
+        }
+
+        let chunk_size = self.chunk_size.unwrap_or_else(|| {
+            bound_chunk_size(
+                aligned_chunk_size(data, threads),
+                self.min_len,
+                self.max_len,
+            )
+        });
+        if let Some(executor) = &self.executor {
+            return reduce_chunks_with_executor(data, operation, chunk_size, executor.as_ref());
+        }
+        let work = || {
+            data.par_chunks(chunk_size)
+                .filter_map(|chunk| chunk.iter().copied().reduce(operation))
+                .reduce_with(operation)
+        };
+
+        let pool = self.pool.get_or_init(|| {
+            let size = if self.deterministic {
+                Some(1)
+            } else {
+                self.threads
+            };
+            size.and_then(|threads| ThreadPoolBuilder::new().num_threads(threads).build().ok())
+        });
+        match pool {
+            Some(pool) => pool.install(work),
+            None => work(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test running the same reducer repeatedly on a dedicated pool
+    #[test]
+    fn test_reducer_reuse() {
+        let reducer = Reducer::new(|a: i64, b| a + b).with_threads(2);
+        for n in [10, 1_000, 100_000] {
+            let data: Vec<i64> = (1..=n).collect();
+            assert_eq!(reducer.run(&data), Some(n * (n + 1) / 2));
+        }
+        assert_eq!(reducer.pool.get().map(Option::is_some), Some(true));
+    }
+
+    // Test a reducer with an explicit chunk size and sequential threshold
+    #[test]
+    fn test_reducer_config() {
+        let config = ParallelConfig::new().with_chunk_size(7);
+        let reducer = Reducer::with_config(i32::max, &config).with_sequential_threshold(50);
+        let data: Vec<i32> = (0..1_000).map(|i| (i * 37) % 1_001).collect();
+        assert_eq!(reducer.run(&data), data.iter().copied().max());
+        assert_eq!(reducer.run(&data[..10]), data[..10].iter().copied().max());
+        assert_eq!(reducer.run(&[]), None);
+    }
+
+    // Test a reducer that runs its chunks on a custom executor
+    #[test]
+    fn test_reducer_executor() {
+        use crate::Job;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        struct Counting(Arc<AtomicUsize>);
+        impl Executor for Counting {
+            fn execute<'a>(&self, jobs: Vec<Job<'a>>) {
+                self.0.fetch_add(jobs.len(), Ordering::Relaxed);
+                jobs.into_iter().for_each(|job| job());
+            }
+        }
+
+        let jobs = Arc::new(AtomicUsize::new(0));
+        let data: Vec<u64> = (1..=10_000).collect();
+        let reducer = Reducer::new(|a: u64, b| a + b)
+            .with_threads(4)
+            .with_chunk_size(1_000)
+            .with_sequential_threshold(0)
+            .with_executor(Counting(Arc::clone(&jobs)));
+        assert_eq!(reducer.run(&data), Some(50_005_000));
+        assert_eq!(jobs.load(Ordering::Relaxed), 10);
+        assert!(reducer.pool.get().is_none());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::aligned_chunk_size;
+use crate::elementwise::{build_output, OutputChunk};
+use crate::platform::available_threads;
+
+/// How [`parallel_resample`] derives a value at each point of the new grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// Interpolates linearly between the samples on either side; NaN outside the sampled range.
+    Linear,
+    /// Holds the last sample at or before the grid point; NaN before the first sample.
+    Previous,
+    /// Takes the sample closest in time, the earlier one on ties.
+    Nearest,
+    /// Averages the samples from the grid point up to the next one, downsampling into
+    /// bins; the last bin is open-ended and empty bins are NaN.
+    Mean,
+}
+
+/// Resamples a time series onto a new time grid, in parallel over chunks of the grid.
+///
+/// Each chunk of the output binary-searches the samples for its first grid point and
+/// then walks forward, so chunks need nothing from each other and samples that straddle
+/// a chunk boundary are seen by both neighbours.
+///
+/// # Parameters
+/// - `timestamps`: The sample times, sorted ascending.
+/// - `values`: The sample values, one per timestamp.
+/// - `new_grid`: The times to resample at, sorted ascending.
+/// - `interpolation`: How to derive each output value.
+///
+/// # Returns
+/// One value per point of `new_grid`.
+///
+/// # Panics
+/// Panics if `timestamps` and `values` have different lengths.
+pub fn parallel_resample<T>(
+    timestamps: &[f64],
+    values: &[T],
+    new_grid: &[f64],
+    interpolation: Interpolation,
+) -> Vec<f64>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    assert_eq!(
+        timestamps.len(),
+        values.len(),
+        "timestamps and values differ in length"
+    );
+    if timestamps.is_empty() {
+        return vec![f64::NAN; new_grid.len()];
+    }
+    let chunk_size = aligned_chunk_size(new_grid, available_threads());
+    build_output(new_grid.len(), chunk_size, |start, out| {
+        let series = Series { timestamps, values };
+        match interpolation {
+            Interpolation::Mean => series.bin_means(new_grid, start, out),
+            _ => series.interpolate(&new_grid[start..start + out.len()], interpolation, out),
+        }
+    })
+}
+
+/// A sorted time series.
+struct Series<'a, T> {
+    timestamps: &'a [f64],
+    values: &'a [T],
+}
+
+impl<T: Copy + Into<f64>> Series<'_, T> {
+    fn value(&self, index: usize) -> f64 {
+        self.values[index].into()
+    }
+
+    /// Interpolates at each grid point, tracking the first sample after it.
+    fn interpolate(
+        &self,
+        grid: &[f64],
+        interpolation: Interpolation,
+        out: &mut OutputChunk<'_, f64>,
+    ) {
+        let ts = self.timestamps;
+        let mut after = ts.partition_point(|&t| t <= grid[0]);
+        for &at in grid {
+            while after < ts.len() && ts[after] <= at {
+                after += 1;
+            }
+            // Samples `after - 1` and `after` bracket the grid point, where they exist
+            let before = after.checked_sub(1);
+            out.push(match interpolation {
+                Interpolation::Previous => before.map_or(f64::NAN, |i| self.value(i)),
+                Interpolation::Nearest => match before {
+                    None => self.value(0),
+                    Some(i) if after == ts.len() || at - ts[i] <= ts[after] - at => self.value(i),
+                    Some(_) => self.value(after),
+                },
+                Interpolation::Linear => match before {
+                    Some(i) if ts[i] == at => self.value(i),
+                    Some(i) if after < ts.len() => {
+                        let t = (at - ts[i]) / (ts[after] - ts[i]);
+                        self.value(i) + (self.value(after) - self.value(i)) * t
+                    }
+                    _ => f64::NAN,
+                },
+                Interpolation::Mean => unreachable!("bins are averaged by bin_means"),
+            });
+        }
+    }
+
+    /// Averages the samples in each bin `[grid[i], grid[i + 1])` for the output chunk
+    /// starting at grid index `start`.
+    fn bin_means(&self, grid: &[f64], start: usize, out: &mut OutputChunk<'_, f64>) {
+        let ts = self.timestamps;
+        let mut first = ts.partition_point(|&t| t < grid[start]);
+        for offset in 0..out.len() {
+            let end = grid
+                .get(start + offset + 1)
+                .copied()
+                .unwrap_or(f64::INFINITY);
+            let mut last = first;
+            let mut sum = 0.0;
+            while last < ts.len() && ts[last] < end {
+                sum += self.value(last);
+                last += 1;
+            }
+            out.push(if last > first {
+                sum / (last - first) as f64
+            } else {
+                f64::NAN
+            });
+            first = last;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test each interpolation on a short series
+    #[test]
+    fn test_resample_interpolations() {
+        let timestamps = [1.0, 2.0, 4.0];
+        let values = [10.0, 20.0, 40.0];
+        let grid = [0.0, 1.0, 1.5, 3.4, 4.0, 5.0];
+        let resample =
+            |interpolation| parallel_resample(&timestamps, &values, &grid, interpolation);
+
+        let linear = resample(Interpolation::Linear);
+        assert!(linear[0].is_nan() && linear[5].is_nan());
+        assert_eq!(&linear[1..5], &[10.0, 15.0, 34.0, 40.0]);
+        let previous = resample(Interpolation::Previous);
+        assert!(previous[0].is_nan());
+        assert_eq!(&previous[1..], &[10.0, 10.0, 20.0, 40.0, 40.0]);
+        assert_eq!(
+            resample(Interpolation::Nearest),
+            [10.0, 10.0, 10.0, 40.0, 40.0, 40.0]
+        );
+    }
+
+    // Test downsampling into bins, including empty ones
+    #[test]
+    fn test_resample_mean_bins() {
+        let timestamps = [0.0, 0.5, 1.0, 1.5, 3.0, 3.5];
+        let values = [1u32, 3, 5, 7, 9, 11];
+        let means = parallel_resample(
+            &timestamps,
+            &values,
+            &[0.0, 1.0, 2.0, 3.0],
+            Interpolation::Mean,
+        );
+        assert_eq!(means[0], 2.0);
+        assert_eq!(means[1], 6.0);
+        assert!(means[2].is_nan());
+        assert_eq!(means[3], 10.0);
+    }
+
+    // Test a long linear series, which linear interpolation reproduces exactly
+    #[test]
+    fn test_resample_long_series() {
+        let timestamps: Vec<f64> = (0..100_000).map(|i| f64::from(i) * 2.0).collect();
+        let values: Vec<f64> = timestamps.iter().map(|t| t * 3.0).collect();
+        let grid: Vec<f64> = (0..150_000).map(|i| f64::from(i) * 1.25).collect();
+        let resampled = parallel_resample(&timestamps, &values, &grid, Interpolation::Linear);
+        for (&at, &value) in grid.iter().zip(&resampled) {
+            if at <= 199_998.0 {
+                assert!((value - at * 3.0).abs() < 1e-9, "{} {}", at, value);
+            } else {
+                assert!(value.is_nan());
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use rayon::prelude::*;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Run-length encodes a slice in parallel.
+///
+/// Every chunk is encoded on its own, and the chunk encodings are then stitched together,
+/// merging a run that ends one chunk with an equal run that starts the next. The result
+/// is the same as a sequential encoding, however the data was split.
+///
+/// # Parameters
+/// - `data`: The elements to encode.
+///
+/// # Returns
+/// The runs in order, as `(value, length)` pairs, with adjacent runs always differing.
+pub fn parallel_rle<T>(data: &[T]) -> Vec<(T, usize)>
+where
+    T: Copy + PartialEq + Send + Sync,
+{
+    if data.is_empty() {
+        return Vec::new();
+    }
+    encode_chunked(data, aligned_chunk_size(data, available_threads()))
+}
+
+/// Encodes chunks of `chunk_size` elements in parallel and stitches their runs.
+fn encode_chunked<T>(data: &[T], chunk_size: usize) -> Vec<(T, usize)>
+where
+    T: Copy + PartialEq + Send + Sync,
+{
+    let encoded: Vec<Vec<(T, usize)>> = data.par_chunks(chunk_size).map(encode).collect();
+
+    let mut runs: Vec<(T, usize)> = Vec::with_capacity(encoded.iter().map(Vec::len).sum());
+    for chunk in encoded {
+        let mut chunk = chunk.into_iter();
+        if let (Some(last), Some(first)) = (runs.last_mut(), chunk.as_slice().first()) {
+            if last.0 == first.0 {
+                last.1 += first.1;
+                chunk.next();
+            }
+        }
+        runs.extend(chunk);
+    }
+    runs
+}
+
+/// Run-length encodes one chunk sequentially.
+fn encode<T: Copy + PartialEq>(chunk: &[T]) -> Vec<(T, usize)> {
+    let mut runs: Vec<(T, usize)> = Vec::new();
+    for &x in chunk {
+        match runs.last_mut() {
+            Some((value, len)) if *value == x => *len += 1,
+            _ => runs.push((x, 1)),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that runs spanning chunk boundaries are stitched
+    #[test]
+    fn test_rle_matches_sequential() {
+        let data: Vec<u8> = (0..100_000).map(|i| (i / 997 % 3) as u8).collect();
+        assert_eq!(parallel_rle(&data), encode(&data));
+        for chunk_size in [1, 7, 997, 1_000] {
+            assert_eq!(encode_chunked(&data, chunk_size), encode(&data));
+        }
+    }
+
+    // Test a single long run and alternating values
+    #[test]
+    fn test_rle_runs() {
+        assert_eq!(parallel_rle(&vec![7; 50_000]), vec![(7, 50_000)]);
+        assert_eq!(parallel_rle(&[1, 2, 2, 1]), vec![(1, 1), (2, 2), (1, 1)]);
+    }
+
+    // Test for empty input
+    #[test]
+    fn test_rle_empty() {
+        assert!(parallel_rle::<i32>(&[]).is_empty());
+    }
+}
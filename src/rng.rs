@@ -0,0 +1,47 @@
+/// A xorshift64* pseudo-random generator.
+///
+/// Fast, dependency-free, and statistically adequate for shuffling, sampling, and
+/// simulation, but not cryptographically secure.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a generator from a seed; the same seed always gives the same sequence.
+    pub fn new(seed: u64) -> Self {
+        // Zero is a fixed point of xorshift, so mix the seed first
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    /// Creates the generator for one of many independent streams derived from `seed`.
+    ///
+    /// The stream index is scrambled with the SplitMix64 finalizer, so neighbouring
+    /// streams do not start from correlated states.
+    pub(crate) fn stream(seed: u64, stream: u64) -> Self {
+        let mut z = seed ^ stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        Rng::new(z ^ (z >> 31))
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a value in `0..bound`.
+    ///
+    /// # Panics
+    /// Panics if `bound` is zero.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// Returns a uniformly distributed value in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        // The top 53 bits fill the mantissa exactly
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::VecDeque;
+
+use crate::aligned_chunk_size;
+use crate::elementwise::{build_output, OutputChunk};
+use crate::platform::available_threads;
+
+/// A statistic [`parallel_rolling`] computes over each window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Statistic {
+    /// The arithmetic mean of the window.
+    Mean,
+    /// The sum of the window.
+    Sum,
+    /// The smallest value in the window.
+    Min,
+    /// The largest value in the window.
+    Max,
+}
+
+/// Computes a statistic over a sliding window, in parallel over chunks of the output.
+///
+/// Output `i` covers the trailing window `data[i + 1 - window..=i]`, truncated at the
+/// start of the series, so the output has the same length as the input and the first
+/// `window - 1` values cover fewer elements. Every chunk of the output re-reads the
+/// `window - 1` inputs before it, so chunks are independent of each other.
+///
+/// Sums and means are kept as a running sum that is recomputed from scratch every
+/// `window` steps, which bounds rounding drift over long series. Min and max use a
+/// monotonic queue and ignore values that do not compare (such as NaN); a window with no
+/// comparable value yields NaN.
+///
+/// # Parameters
+/// - `data`: The series.
+/// - `window`: The number of elements per window.
+/// - `statistic`: The statistic to compute.
+///
+/// # Returns
+/// One value per element of `data`.
+///
+/// # Panics
+/// Panics if `window` is zero.
+pub fn parallel_rolling<T>(data: &[T], window: usize, statistic: Statistic) -> Vec<f64>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    assert!(window > 0, "window must be at least 1");
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    build_output(data.len(), chunk_size, |start, out| {
+        let lead = start.min(window - 1);
+        let input = &data[start - lead..start + out.len()];
+        match statistic {
+            Statistic::Sum => rolling_sum(input, lead, window, out, false),
+            Statistic::Mean => rolling_sum(input, lead, window, out, true),
+            Statistic::Min => rolling_extreme(input, lead, window, out, |a, b| a <= b),
+            Statistic::Max => rolling_extreme(input, lead, window, out, |a, b| a >= b),
+        }
+    })
+}
+
+/// Writes the rolling sum (or mean) of `input[lead..]` into `out`.
+///
+/// `input` starts `lead` elements before the first output position.
+fn rolling_sum<T>(
+    input: &[T],
+    lead: usize,
+    window: usize,
+    out: &mut OutputChunk<'_, f64>,
+    mean: bool,
+) where
+    T: Copy + Into<f64>,
+{
+    let mut sum = 0.0;
+    for offset in 0..out.len() {
+        let end = lead + offset;
+        let first = (end + 1).saturating_sub(window);
+        if offset % window == 0 {
+            sum = input[first..=end].iter().map(|&x| x.into()).sum();
+        } else {
+            sum += input[end].into();
+            if end >= window {
+                sum -= input[end - window].into();
+            }
+        }
+        out.push(if mean {
+            sum / (end + 1 - first) as f64
+        } else {
+            sum
+        });
+    }
+}
+
+/// Writes the rolling minimum or maximum of `input[lead..]` into `out`.
+///
+/// `keeps(a, b)` is true if `a` dominates a later `b`, e.g. `a >= b` for the maximum.
+fn rolling_extreme<T>(
+    input: &[T],
+    lead: usize,
+    window: usize,
+    out: &mut OutputChunk<'_, f64>,
+    keeps: fn(f64, f64) -> bool,
+) where
+    T: Copy + Into<f64>,
+{
+    // Indices of the candidates, whose values are ordered by `keeps` front to back
+    let mut queue: VecDeque<usize> = VecDeque::with_capacity(window.min(input.len()));
+    for (i, &x) in input.iter().enumerate() {
+        let x: f64 = x.into();
+        if !x.is_nan() {
+            while queue.back().is_some_and(|&j| !keeps(input[j].into(), x)) {
+                queue.pop_back();
+            }
+            queue.push_back(i);
+        }
+        if i < lead {
+            continue;
+        }
+        while queue.front().is_some_and(|&j| j + window <= i) {
+            queue.pop_front();
+        }
+        out.push(queue.front().map_or(f64::NAN, |&j| input[j].into()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Computes the statistic of every trailing window sequentially.
+    fn naive(data: &[f64], window: usize, statistic: Statistic) -> Vec<f64> {
+        (0..data.len())
+            .map(|i| {
+                let w = &data[(i + 1).saturating_sub(window)..=i];
+                match statistic {
+                    Statistic::Sum => w.iter().sum(),
+                    Statistic::Mean => w.iter().sum::<f64>() / w.len() as f64,
+                    Statistic::Min => w.iter().copied().fold(f64::INFINITY, f64::min),
+                    Statistic::Max => w.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                }
+            })
+            .collect()
+    }
+
+    // Test every statistic against a naive computation across chunk boundaries
+    #[test]
+    fn test_rolling_matches_naive() {
+        let data: Vec<f64> = (0..50_000)
+            .map(|i| ((i * 37) % 101) as f64 - 50.0)
+            .collect();
+        for window in [1, 3, 64, 1_000] {
+            for statistic in [
+                Statistic::Mean,
+                Statistic::Sum,
+                Statistic::Min,
+                Statistic::Max,
+            ] {
+                let expected = naive(&data, window, statistic);
+                let actual = parallel_rolling(&data, window, statistic);
+                assert_eq!(actual.len(), data.len());
+                for (a, e) in actual.iter().zip(&expected) {
+                    assert!(
+                        (a - e).abs() < 1e-9,
+                        "{:?} {}: {} vs {}",
+                        statistic,
+                        window,
+                        a,
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    // Test integer input and a window longer than the series
+    #[test]
+    fn test_rolling_short_series() {
+        let data = [4u32, 1, 7];
+        assert_eq!(
+            parallel_rolling(&data, 5, Statistic::Mean),
+            vec![4.0, 2.5, 4.0]
+        );
+        assert_eq!(
+            parallel_rolling(&data, 2, Statistic::Max),
+            vec![4.0, 4.0, 7.0]
+        );
+        assert!(parallel_rolling::<f32>(&[], 3, Statistic::Sum).is_empty());
+    }
+
+    // Test that NaN is skipped by min and max
+    #[test]
+    fn test_rolling_nan() {
+        let data = [1.0, f64::NAN, 3.0];
+        assert_eq!(parallel_rolling(&data, 2, Statistic::Min)[1], 1.0);
+        assert!(parallel_rolling(&[f64::NAN], 1, Statistic::Max)[0].is_nan());
+    }
+}
@@ -0,0 +1,106 @@
+use rayon::prelude::*;
+use std::collections::BinaryHeap;
+
+use crate::rng::Rng;
+
+/// Number of elements each parallel task draws keys for.
+///
+/// Fixed rather than derived from the thread count, so the sample only depends on the
+/// seed and the length.
+const BLOCK_LEN: usize = 1 << 16;
+
+/// Draws `k` elements uniformly at random, without replacement, in parallel.
+///
+/// Every element gets an independent random key and the `k` elements with the smallest
+/// keys are kept: each block of the input keeps its own `k` smallest in a bounded heap,
+/// and the blocks' candidates are then merged. Every subset of size `k` is equally
+/// likely, and the same seed and length always give the same sample, on any number of
+/// threads.
+///
+/// # Parameters
+/// - `data`: The elements to sample from.
+/// - `k`: The number of elements to draw.
+/// - `seed`: Seed for the random selection.
+///
+/// # Returns
+/// The sampled elements in input order, or a copy of `data` if it has at most `k` elements.
+pub fn parallel_sample<T>(data: &[T], k: usize, seed: u64) -> Vec<T>
+where
+    T: Copy + Send + Sync,
+{
+    if k >= data.len() {
+        return data.to_vec();
+    }
+    if k == 0 {
+        return Vec::new();
+    }
+
+    // Each block's k smallest (key, index) pairs
+    let mut candidates: Vec<(u64, usize)> = data
+        .par_chunks(BLOCK_LEN)
+        .enumerate()
+        .flat_map_iter(|(block, chunk)| {
+            let mut rng = Rng::stream(seed, block as u64);
+            let start = block * BLOCK_LEN;
+            let mut heap = BinaryHeap::with_capacity(k.min(chunk.len()) + 1);
+            for offset in 0..chunk.len() {
+                heap.push((rng.next_u64(), start + offset));
+                if heap.len() > k {
+                    heap.pop();
+                }
+            }
+            heap.into_vec()
+        })
+        .collect();
+
+    if candidates.len() > k {
+        candidates.select_nth_unstable(k - 1);
+        candidates.truncate(k);
+    }
+    let mut indices: Vec<usize> = candidates.into_iter().map(|(_, index)| index).collect();
+    indices.par_sort_unstable();
+    indices.into_iter().map(|index| data[index]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that a sample has k distinct elements and is reproducible
+    #[test]
+    fn test_sample_distinct_and_reproducible() {
+        let data: Vec<u32> = (0..200_000).collect();
+        let sample = parallel_sample(&data, 1_000, 7);
+        assert_eq!(sample.len(), 1_000);
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(parallel_sample(&data, 1_000, 7), sample);
+        assert_ne!(parallel_sample(&data, 1_000, 8), sample);
+    }
+
+    // Test that every element is drawn about equally often
+    #[test]
+    fn test_sample_uniform() {
+        let data: Vec<usize> = (0..10).collect();
+        let mut counts = [0u32; 10];
+        for seed in 0..5_000 {
+            for x in parallel_sample(&data, 3, seed) {
+                counts[x] += 1;
+            }
+        }
+        // Each element is expected 1_500 times
+        assert!(
+            counts.iter().all(|c| (1_350..1_650).contains(c)),
+            "{:?}",
+            counts
+        );
+    }
+
+    // Test the edge cases of k
+    #[test]
+    fn test_sample_edge_cases() {
+        let data = [1, 2, 3];
+        assert_eq!(parallel_sample(&data, 5, 0), vec![1, 2, 3]);
+        assert!(parallel_sample(&data, 0, 0).is_empty());
+        assert!(parallel_sample::<u8>(&[], 2, 0).is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+use rayon::prelude::*;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+use crate::simd::{max_op, min_op};
+use crate::SimdElement;
+
+/// Computes the inclusive scan (running reduction) of a slice in parallel.
+///
+/// Works in two phases: every chunk is first reduced on its own, the chunk totals are
+/// scanned sequentially to give each chunk its starting value, and then every chunk is
+/// scanned in parallel from that value. The operation is applied about twice per element.
+///
+/// # Parameters
+/// - `data`: The elements to scan.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// A vector whose element `i` is the reduction of `data[..=i]`; with `+` this is the
+/// prefix sum.
+pub fn parallel_scan<T>(data: &[T], identity: T, operation: fn(T, T) -> T) -> Vec<T>
+where
+    T: Copy + Send + Sync,
+{
+    let mut out = data.to_vec();
+    if data.is_empty() {
+        return out;
+    }
+    let chunk_size = aligned_chunk_size(data, available_threads());
+
+    let totals: Vec<T> = data
+        .par_chunks(chunk_size)
+        .map(|chunk| chunk.iter().copied().fold(identity, operation))
+        .collect();
+    let starts: Vec<T> = totals
+        .iter()
+        .scan(identity, |acc, &total| {
+            let start = *acc;
+            *acc = operation(*acc, total);
+            Some(start)
+        })
+        .collect();
+
+    out.par_chunks_mut(chunk_size)
+        .zip(starts)
+        .for_each(|(chunk, start)| {
+            let mut acc = start;
+            for x in chunk {
+                acc = operation(acc, *x);
+                *x = acc;
+            }
+        });
+    out
+}
+
+/// Computes the running maximum of a slice in parallel.
+///
+/// NaN elements never compare larger and are therefore ignored.
+///
+/// # Returns
+/// A vector whose element `i` is the largest of `data[..=i]`.
+pub fn parallel_cummax<T: SimdElement>(data: &[T]) -> Vec<T> {
+    parallel_scan(data, T::MAX_IDENTITY, max_op)
+}
+
+/// Computes the running minimum of a slice in parallel.
+///
+/// NaN elements never compare smaller and are therefore ignored.
+///
+/// # Returns
+/// A vector whose element `i` is the smallest of `data[..=i]`.
+pub fn parallel_cummin<T: SimdElement>(data: &[T]) -> Vec<T> {
+    parallel_scan(data, T::MIN_IDENTITY, min_op)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test the prefix sum against a sequential scan
+    #[test]
+    fn test_scan_prefix_sum() {
+        let data: Vec<u64> = (1..=100_000).collect();
+        let sums = parallel_scan(&data, 0, |a, b| a + b);
+        assert!(sums.iter().enumerate().all(|(i, &s)| {
+            let n = i as u64 + 1;
+            s == n * (n + 1) / 2
+        }));
+        assert!(parallel_scan::<u8>(&[], 0, |a, b| a + b).is_empty());
+    }
+
+    // Test running extremes, e.g. for drawdowns
+    #[test]
+    fn test_cummax_cummin() {
+        let prices: Vec<f64> = (0..10_000)
+            .map(|i| f64::from(i % 1_000) - f64::from(i / 1_000))
+            .collect();
+        let peaks = parallel_cummax(&prices);
+        let troughs = parallel_cummin(&prices);
+        let (mut peak, mut trough) = (f64::NEG_INFINITY, f64::INFINITY);
+        for (i, &p) in prices.iter().enumerate() {
+            peak = peak.max(p);
+            trough = trough.min(p);
+            assert_eq!((peaks[i], troughs[i]), (peak, trough));
+        }
+        assert_eq!(parallel_cummax(&[1.0, f32::NAN, 3.0]), vec![1.0, 1.0, 3.0]);
+    }
+}
@@ -0,0 +1,178 @@
+//! A `std::thread::scope` backend for builds without rayon.
+//!
+//! With the `backend-std` feature, [`ScopedThreadExecutor`] runs the chunk jobs of
+//! [`parallel_reduce_with_executor`] on scoped threads spawned per call, so there is no
+//! global pool to share with (or be slowed down by) other rayon users. Without the `std`
+//! feature, the module also provides `parallel_binary_operation` and
+//! `parallel_reduce_with_identity` on top of it, with the same signatures for `Vec` input.
+
+extern crate std;
+
+use alloc::vec::Vec;
+use std::thread;
+
+use crate::executor::{parallel_reduce_with_executor, Executor, Job};
+
+/// Runs jobs on up to a fixed number of threads scoped to each call.
+///
+/// Jobs are dealt round-robin to the threads, and the calling thread runs one share
+/// itself, so a batch of `n` jobs spawns at most `threads - 1` threads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScopedThreadExecutor {
+    threads: usize,
+}
+
+impl ScopedThreadExecutor {
+    /// Creates an executor using one thread per available core.
+    pub fn new() -> Self {
+        Self::with_threads(thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    /// Creates an executor using up to `threads` threads, including the calling one.
+    pub fn with_threads(threads: usize) -> Self {
+        ScopedThreadExecutor {
+            threads: threads.max(1),
+        }
+    }
+
+    /// Returns the number of threads jobs are spread across.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+}
+
+impl Default for ScopedThreadExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Executor for ScopedThreadExecutor {
+    fn execute<'a>(&self, jobs: Vec<Job<'a>>) {
+        let threads = self.threads.min(jobs.len());
+        if threads <= 1 {
+            jobs.into_iter().for_each(|job| job());
+            return;
+        }
+        let mut shares: Vec<Vec<Job<'a>>> = (0..threads).map(|_| Vec::new()).collect();
+        for (index, job) in jobs.into_iter().enumerate() {
+            shares[index % threads].push(job);
+        }
+        thread::scope(|scope| {
+            let mut shares = shares.into_iter();
+            let own = shares.next();
+            for share in shares {
+                scope.spawn(move || share.into_iter().for_each(|job| job()));
+            }
+            own.into_iter().flatten().for_each(|job| job());
+        });
+    }
+}
+
+/// Performs a binary operation over a slice on scoped threads, one chunk per core.
+///
+/// # Parameters
+/// - `data`: The elements to operate on.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or `identity` for an empty slice.
+pub fn parallel_reduce_scoped<T>(data: &[T], identity: T, operation: fn(T, T) -> T) -> T
+where
+    T: Copy + Send + Sync,
+{
+    let executor = ScopedThreadExecutor::new();
+    parallel_reduce_with_executor(data, identity, operation, executor.threads(), &executor)
+}
+
+/// Performs a parallel binary operation on a vector using scoped threads.
+///
+/// The rayon-free counterpart of the `std` feature's `parallel_binary_operation`, for
+/// `Vec` input; the identity is probed from the operation the same way.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` to operate on.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the binary operation to all elements, or `T::default()` for an
+/// empty vector.
+///
+/// # Panics
+/// Panics if the operation's identity cannot be inferred.
+#[cfg(not(feature = "std"))]
+pub fn parallel_binary_operation<T>(data: Vec<T>, operation: fn(T, T) -> T) -> T
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + crate::Bounded,
+{
+    if data.is_empty() {
+        return T::default();
+    }
+    if data.len() == 1 {
+        return data[0];
+    }
+    let identity = crate::get_initial_value(operation);
+    parallel_reduce_scoped(&data, identity, operation)
+}
+
+/// Performs a parallel binary operation with an explicitly supplied identity using
+/// scoped threads.
+///
+/// The rayon-free counterpart of the `std` feature's `parallel_reduce_with_identity`.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or `identity` for an empty vector.
+#[cfg(not(feature = "std"))]
+pub fn parallel_reduce_with_identity<T>(data: Vec<T>, identity: T, operation: fn(T, T) -> T) -> T
+where
+    T: Copy + Send + Sync,
+{
+    parallel_reduce_scoped(&data, identity, operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that jobs are spread over several threads and all run
+    #[test]
+    fn test_scoped_executor_threads() {
+        let data: Vec<u64> = (1..=100_000).collect();
+        let executor = ScopedThreadExecutor::with_threads(4);
+        let result = parallel_reduce_with_executor(&data, 0, |a, b| a + b, 16, &executor);
+        assert_eq!(result, 5_000_050_000);
+        assert_eq!(ScopedThreadExecutor::with_threads(0).threads(), 1);
+    }
+
+    // Test the convenience reduction and empty input
+    #[test]
+    fn test_reduce_scoped() {
+        let data: Vec<i64> = (1..=1_000).collect();
+        assert_eq!(parallel_reduce_scoped(&data, i64::MIN, i64::max), 1_000);
+        assert_eq!(parallel_reduce_scoped(&[], 7, |a: u8, b| a + b), 7);
+    }
+
+    // Test the rayon-free entry points
+    #[cfg(not(feature = "std"))]
+    #[test]
+    fn test_rayon_free_api() {
+        let data: Vec<u32> = (1..=100).collect();
+        assert_eq!(parallel_binary_operation(data.clone(), |a, b| a + b), 5_050);
+        assert_eq!(parallel_binary_operation(data.clone(), u32::min), 1);
+        assert_eq!(parallel_reduce_with_identity(data, 1, |a, b| a.max(b)), 100);
+        assert_eq!(
+            parallel_binary_operation(Vec::from([5i32]), |a, b| a | b),
+            5
+        );
+        assert_eq!(
+            parallel_binary_operation(Vec::<i32>::new(), |a, b| a | b),
+            0
+        );
+    }
+}
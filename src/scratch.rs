@@ -0,0 +1,79 @@
+use rayon::prelude::*;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Maps every element in parallel, giving each worker its own scratch state.
+///
+/// The input is split into one chunk per thread, and `init` runs once per chunk rather
+/// than once per element, so expensive setup such as seeding an RNG or allocating a
+/// buffer is paid a handful of times. The state is never shared between chunks.
+///
+/// # Parameters
+/// - `data`: The elements to map.
+/// - `init`: Creates a worker's state.
+/// - `f`: Maps one element, with mutable access to the worker's state.
+///
+/// # Returns
+/// The mapped elements, in input order.
+pub fn parallel_map_with<T, S, U, I, F>(data: &[T], init: I, f: F) -> Vec<U>
+where
+    T: Copy + Sync,
+    U: Send,
+    I: Fn() -> S + Sync,
+    F: Fn(&mut S, T) -> U + Sync,
+{
+    let f = &f;
+    data.par_chunks(aligned_chunk_size(data, available_threads()))
+        .flat_map_iter(|chunk| {
+            let mut state = init();
+            chunk.iter().map(move |&x| f(&mut state, x))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Test that the state is created once per chunk, not per element
+    #[test]
+    fn test_map_with_init_count() {
+        let inits = AtomicUsize::new(0);
+        let data: Vec<u32> = (0..100_000).collect();
+        let doubled = parallel_map_with(
+            &data,
+            || {
+                inits.fetch_add(1, Ordering::Relaxed);
+                Vec::<u32>::with_capacity(16)
+            },
+            |buffer, x| {
+                buffer.clear();
+                buffer.extend([x, x]);
+                buffer.iter().sum::<u32>()
+            },
+        );
+        assert_eq!(doubled, data.iter().map(|x| x * 2).collect::<Vec<_>>());
+        assert!(inits.load(Ordering::Relaxed) <= available_threads());
+    }
+
+    // Test state carried across the elements of a chunk, and empty input
+    #[test]
+    fn test_map_with_state() {
+        let data = [3u8; 1_000];
+        let counted = parallel_map_with(
+            &data,
+            || 0usize,
+            |seen, x| {
+                *seen += 1;
+                (*seen, x)
+            },
+        );
+        assert_eq!(counted[0], (1, 3));
+        for pair in counted.windows(2) {
+            assert!(pair[1].0 == pair[0].0 + 1 || pair[1].0 == 1);
+        }
+        assert!(parallel_map_with(&[] as &[u8], || (), |_, x| x).is_empty());
+    }
+}
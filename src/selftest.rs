@@ -0,0 +1,486 @@
+use std::fmt::{self, Debug};
+
+use crate::identity::reduce_slice_with_config;
+use crate::rng::Rng;
+use crate::{
+    parallel_binary_operation, parallel_float_max, parallel_float_min, parallel_float_product,
+    parallel_float_sum, parallel_reduce_instrumented, parallel_reduce_owned,
+    parallel_reduce_with_identity, parallel_simd_max, parallel_simd_min, parallel_simd_sum,
+    Bounded, FloatPolicy, ParallelConfig, SimdElement,
+};
+
+/// Input lengths every operation is checked at, covering empty, tiny, and uneven chunking.
+const LENGTHS: [usize; 7] = [0, 1, 2, 7, 1_000, 4_099, 100_003];
+
+/// Number of threads [`verify_reduction`] splits the data across.
+const VERIFY_THREADS: usize = 4;
+
+/// A parallel result that differs from the sequential fold of the same input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// The function that produced the wrong result, e.g. `parallel_simd_sum<i64>`.
+    pub function: String,
+    /// The operation that was reduced, e.g. `sum`.
+    pub operation: &'static str,
+    /// The input length.
+    pub len: usize,
+    /// The seed the input was generated from.
+    pub seed: u64,
+    /// The sequential result.
+    pub expected: String,
+    /// The parallel result.
+    pub actual: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}, len {}, seed {}): expected {}, got {}",
+            self.function, self.operation, self.len, self.seed, self.expected, self.actual
+        )
+    }
+}
+
+/// The outcome of a [`selftest`] run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SelfTestReport {
+    /// Number of parallel results compared.
+    pub checks: usize,
+    /// Every comparison that failed.
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl SelfTestReport {
+    /// Returns `true` if every parallel result matched the sequential one.
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Runs every built-in reduction on random inputs and compares it with a sequential fold.
+///
+/// Sums, products, minima, and maxima of `i32`, `i64`, `u64`, and `f64` vectors are
+/// checked at several lengths through the generic, identity, owned, instrumented, SIMD,
+/// and float entry points. Float sums and products are compared with a relative
+/// tolerance, since the parallel order of additions differs from the sequential one.
+///
+/// # Parameters
+/// - `seed`: Seed for the random inputs; the same seed always checks the same inputs.
+///
+/// # Returns
+/// The number of comparisons made and every mismatch found.
+pub fn selftest(seed: u64) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+    check_type::<i32>(seed, &mut report);
+    check_type::<i64>(seed, &mut report);
+    check_type::<u64>(seed, &mut report);
+    check_type::<f64>(seed, &mut report);
+    report
+}
+
+/// Compares a parallel reduction with a sequential fold of the same data.
+///
+/// Use this in tests of custom operations: a mismatch usually means the operation is
+/// not associative or `identity` is not its identity element. The data is always split
+/// across several threads, even on a single-core machine.
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// `Ok(())` if the results are equal, or the mismatch.
+pub fn verify_reduction<T>(
+    data: &[T],
+    identity: T,
+    operation: fn(T, T) -> T,
+) -> Result<(), Mismatch>
+where
+    T: Copy + Send + Sync + PartialEq + Debug,
+{
+    let expected = data.iter().copied().fold(identity, operation);
+    let config = ParallelConfig::new().with_threads(VERIFY_THREADS);
+    let actual = reduce_slice_with_config(data, identity, operation, &config);
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Mismatch {
+            function: String::from("parallel_reduce_with_identity"),
+            operation: "custom",
+            len: data.len(),
+            seed: 0,
+            expected: format!("{:?}", expected),
+            actual: format!("{:?}", actual),
+        })
+    }
+}
+
+/// A property a reduction operation must have but was shown not to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// `op(op(a, b), c) != op(a, op(b, c))`, so the result depends on how chunks split.
+    NotAssociative {
+        /// The first operand.
+        a: String,
+        /// The second operand.
+        b: String,
+        /// The third operand.
+        c: String,
+    },
+    /// `op(identity, value)` or `op(value, identity)` is not `value`, so every chunk
+    /// adds something extra.
+    NotIdentity {
+        /// The identity that was checked.
+        identity: String,
+        /// The value it failed to preserve.
+        value: String,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::NotAssociative { a, b, c } => write!(
+                f,
+                "operation is not associative: op(op({a}, {b}), {c}) != op({a}, op({b}, {c}))"
+            ),
+            Violation::NotIdentity { identity, value } => write!(
+                f,
+                "{} is not an identity of the operation: it changes {}",
+                identity, value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// Number of samples whose triples [`verify_operation`] checks (the rest are ignored).
+const MAX_VERIFY_SAMPLES: usize = 16;
+
+/// Property-tests an operation for associativity on sampled values.
+///
+/// Parallel reductions regroup the operation across chunk boundaries, so only
+/// associative operations give the same answer as a sequential fold. Every triple of
+/// the first 16 samples is checked with exact equality; float operations such as
+/// addition are only associative on values that round exactly (e.g. small integers).
+///
+/// # Parameters
+/// - `operation`: The operation to check.
+/// - `samples`: Values representative of the data it will reduce.
+///
+/// # Returns
+/// `Ok(())` if no violation was found, or the first one.
+pub fn verify_operation<T>(operation: fn(T, T) -> T, samples: &[T]) -> Result<(), Violation>
+where
+    T: Copy + PartialEq + Debug,
+{
+    match find_violation(operation, None, samples) {
+        Some(found) => Err(found.describe()),
+        None => Ok(()),
+    }
+}
+
+/// Property-tests an operation for associativity and `identity` for being its identity.
+///
+/// # Parameters
+/// - `operation`: The operation to check.
+/// - `identity`: The identity the reduction will start every chunk from.
+/// - `samples`: Values representative of the data it will reduce.
+///
+/// # Returns
+/// `Ok(())` if no violation was found, or the first one.
+pub fn verify_operation_with_identity<T>(
+    operation: fn(T, T) -> T,
+    identity: T,
+    samples: &[T],
+) -> Result<(), Violation>
+where
+    T: Copy + PartialEq + Debug,
+{
+    match find_violation(operation, Some(identity), samples) {
+        Some(found) => Err(found.describe()),
+        None => Ok(()),
+    }
+}
+
+/// A violation found by [`find_violation`], with the offending values.
+pub(crate) enum Found<T> {
+    NotAssociative(T, T, T),
+    NotIdentity(T, T),
+}
+
+impl<T: Debug> Found<T> {
+    fn describe(&self) -> Violation {
+        match self {
+            Found::NotAssociative(a, b, c) => Violation::NotAssociative {
+                a: format!("{:?}", a),
+                b: format!("{:?}", b),
+                c: format!("{:?}", c),
+            },
+            Found::NotIdentity(identity, value) => Violation::NotIdentity {
+                identity: format!("{:?}", identity),
+                value: format!("{:?}", value),
+            },
+        }
+    }
+}
+
+/// Checks `identity` (if given) against every sample, then associativity on every
+/// triple of the first [`MAX_VERIFY_SAMPLES`] samples.
+pub(crate) fn find_violation<T>(
+    operation: fn(T, T) -> T,
+    identity: Option<T>,
+    samples: &[T],
+) -> Option<Found<T>>
+where
+    T: Copy + PartialEq,
+{
+    if let Some(identity) = identity {
+        for &value in samples {
+            if operation(identity, value) != value || operation(value, identity) != value {
+                return Some(Found::NotIdentity(identity, value));
+            }
+        }
+    }
+
+    let samples = &samples[..samples.len().min(MAX_VERIFY_SAMPLES)];
+    for &a in samples {
+        for &b in samples {
+            for &c in samples {
+                if operation(operation(a, b), c) != operation(a, operation(b, c)) {
+                    return Some(Found::NotAssociative(a, b, c));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Picks up to [`MAX_VERIFY_SAMPLES`] evenly spaced elements of `data`.
+pub(crate) fn sample_evenly<T: Copy>(data: &[T]) -> Vec<T> {
+    let step = data.len().div_ceil(MAX_VERIFY_SAMPLES).max(1);
+    data.iter().step_by(step).copied().collect()
+}
+
+/// Element types the self-test covers.
+trait TestNumber: SimdElement + Default + From<u8> + Bounded + PartialEq + Debug + 'static {
+    const NAME: &'static str;
+
+    /// Draws a value small enough that sums of every tested length cannot overflow.
+    fn random(rng: &mut Rng) -> Self;
+    /// Draws a factor for products that keeps float products finite.
+    fn random_factor(rng: &mut Rng) -> Self;
+    fn sum(a: Self, b: Self) -> Self;
+    fn product(a: Self, b: Self) -> Self;
+    fn minimum(a: Self, b: Self) -> Self;
+    fn maximum(a: Self, b: Self) -> Self;
+    const LOWEST: Self;
+    const HIGHEST: Self;
+
+    /// Compares a sequential and a parallel result of reducing `data`.
+    fn matches(_operation: &str, expected: Self, actual: Self, _data: &[Self]) -> bool {
+        expected == actual
+    }
+
+    /// Runs the float-specific entry point for `operation`, if the type has one.
+    fn float_reduce(_operation: &str, _data: &[Self]) -> Option<Self> {
+        None
+    }
+}
+
+macro_rules! impl_test_int {
+    ($($t:ty: $lo:expr),*) => {
+        $(
+            impl TestNumber for $t {
+                const NAME: &'static str = stringify!($t);
+                const LOWEST: Self = <$t>::MIN;
+                const HIGHEST: Self = <$t>::MAX;
+
+                fn random(rng: &mut Rng) -> Self {
+                    (rng.below(2_001) as i64 + $lo) as $t
+                }
+                fn random_factor(rng: &mut Rng) -> Self {
+                    (rng.below(7) as i64 + 1) as $t
+                }
+                fn sum(a: Self, b: Self) -> Self {
+                    a.wrapping_add(b)
+                }
+                fn product(a: Self, b: Self) -> Self {
+                    a.wrapping_mul(b)
+                }
+                fn minimum(a: Self, b: Self) -> Self {
+                    Ord::min(a, b)
+                }
+                fn maximum(a: Self, b: Self) -> Self {
+                    Ord::max(a, b)
+                }
+            }
+        )*
+    };
+}
+
+impl_test_int!(i32: -1_000, i64: -1_000, u64: 0);
+
+impl TestNumber for f64 {
+    const NAME: &'static str = "f64";
+    const LOWEST: Self = f64::NEG_INFINITY;
+    const HIGHEST: Self = f64::INFINITY;
+
+    fn random(rng: &mut Rng) -> Self {
+        (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64 * 2_000.0 - 1_000.0
+    }
+    fn random_factor(rng: &mut Rng) -> Self {
+        0.5 + (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    fn sum(a: Self, b: Self) -> Self {
+        a + b
+    }
+    fn product(a: Self, b: Self) -> Self {
+        a * b
+    }
+    fn minimum(a: Self, b: Self) -> Self {
+        f64::min(a, b)
+    }
+    fn maximum(a: Self, b: Self) -> Self {
+        f64::max(a, b)
+    }
+
+    fn matches(operation: &str, expected: Self, actual: Self, data: &[Self]) -> bool {
+        let scale = match operation {
+            "sum" => data.iter().map(|x| x.abs()).sum::<f64>(),
+            _ => expected.abs().max(actual.abs()),
+        };
+        expected == actual || (expected - actual).abs() <= 1e-9 * scale
+    }
+
+    fn float_reduce(operation: &str, data: &[Self]) -> Option<Self> {
+        let data = data.to_vec();
+        let result = match operation {
+            "sum" => parallel_float_sum(data, FloatPolicy::Propagate),
+            "product" => parallel_float_product(data, FloatPolicy::Propagate),
+            "min" => parallel_float_min(data, FloatPolicy::Propagate),
+            "max" => parallel_float_max(data, FloatPolicy::Propagate),
+            _ => return None,
+        };
+        result.ok()
+    }
+}
+
+/// A named operation with its identity.
+type Operation<T> = (&'static str, T, fn(T, T) -> T);
+
+fn check_type<T: TestNumber>(seed: u64, report: &mut SelfTestReport) {
+    let operations: [Operation<T>; 4] = [
+        ("sum", T::ZERO, T::sum),
+        ("product", T::ONE, T::product),
+        ("min", T::HIGHEST, T::minimum),
+        ("max", T::LOWEST, T::maximum),
+    ];
+
+    for (round, &len) in LENGTHS.iter().enumerate() {
+        let input_seed = seed.wrapping_add(round as u64);
+        let mut rng = Rng::new(input_seed);
+        let values: Vec<T> = (0..len).map(|_| T::random(&mut rng)).collect();
+        let factors: Vec<T> = (0..len).map(|_| T::random_factor(&mut rng)).collect();
+
+        for &(name, identity, operation) in &operations {
+            let data = if name == "product" { &factors } else { &values };
+            let expected = data.iter().copied().fold(identity, operation);
+            let mut check = |function: &str, actual: T| {
+                report.checks += 1;
+                if !T::matches(name, expected, actual, data) {
+                    report.mismatches.push(Mismatch {
+                        function: format!("{}<{}>", function, T::NAME),
+                        operation: name,
+                        len,
+                        seed: input_seed,
+                        expected: format!("{:?}", expected),
+                        actual: format!("{:?}", actual),
+                    });
+                }
+            };
+
+            check(
+                "parallel_reduce_with_identity",
+                parallel_reduce_with_identity(data.clone(), identity, operation),
+            );
+            check(
+                "parallel_reduce_owned",
+                parallel_reduce_owned(data.clone(), operation).unwrap_or(identity),
+            );
+
+            // Empty inputs yield `T::default()` rather than the probed identity
+            if !data.is_empty() {
+                check(
+                    "parallel_binary_operation",
+                    parallel_binary_operation(data.clone(), operation),
+                );
+                check(
+                    "parallel_reduce_instrumented",
+                    parallel_reduce_instrumented(data.clone(), operation).0,
+                );
+            }
+
+            let simd = match name {
+                "sum" => Some(parallel_simd_sum(data)),
+                "min" if !data.is_empty() => Some(parallel_simd_min(data)),
+                "max" if !data.is_empty() => Some(parallel_simd_max(data)),
+                _ => None,
+            };
+            if let Some(actual) = simd {
+                check(&format!("parallel_simd_{}", name), actual);
+            }
+
+            if !data.is_empty() {
+                if let Some(actual) = T::float_reduce(name, data) {
+                    check(&format!("parallel_float_{}", name), actual);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that every built-in reduction agrees with a sequential fold
+    #[test]
+    fn test_selftest_passes() {
+        let report = selftest(42);
+        assert!(report.checks > 200);
+        assert!(report.is_ok(), "{:#?}", report.mismatches);
+    }
+
+    // Test the operation property checks
+    #[test]
+    fn test_verify_operation() {
+        let samples: Vec<i64> = (-5..5).collect();
+        assert_eq!(verify_operation(|a, b| a + b, &samples), Ok(()));
+        assert!(matches!(
+            verify_operation(|a, b| a - b, &samples),
+            Err(Violation::NotAssociative { .. })
+        ));
+        assert_eq!(
+            verify_operation_with_identity(i64::max, i64::MIN, &samples),
+            Ok(())
+        );
+        let err = verify_operation_with_identity(|a, b| a * b, 0, &samples).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "0 is not an identity of the operation: it changes -5"
+        );
+    }
+
+    // Test that a non-associative operation is caught
+    #[test]
+    fn test_verify_reduction_catches_subtraction() {
+        let data: Vec<i64> = (1..=10_000).collect();
+        assert!(verify_reduction(&data, 0, |a, b| a.max(b)).is_ok());
+        let mismatch = verify_reduction(&data, 0, |a, b| a - b).unwrap_err();
+        assert_eq!(mismatch.expected, format!("{}", -50_005_000));
+    }
+}
@@ -0,0 +1,191 @@
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Number of hash partitions the inputs are split into.
+///
+/// Fixed, so the order of the result does not depend on the thread count.
+const PARTITIONS: usize = 64;
+
+/// Returns the partition `value` belongs to.
+fn partition_of<T: Hash>(value: &T) -> usize {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    (hasher.finish() % PARTITIONS as u64) as usize
+}
+
+/// Splits `data` into the hash partitions, in parallel over chunks.
+///
+/// # Returns
+/// One `Vec` per chunk, holding the chunk's elements of each partition in input order.
+fn partition<T>(data: &[T]) -> Vec<Vec<Vec<T>>>
+where
+    T: Hash + Clone + Send + Sync,
+{
+    if data.is_empty() {
+        return Vec::new();
+    }
+    data.par_chunks(aligned_chunk_size(data, available_threads()))
+        .map(|chunk| {
+            let mut parts = vec![Vec::new(); PARTITIONS];
+            for x in chunk {
+                parts[partition_of(x)].push(x.clone());
+            }
+            parts
+        })
+        .collect()
+}
+
+/// Collects partition `p` of every chunk, in input order.
+fn gather<T>(parts: &[Vec<Vec<T>>], p: usize) -> Vec<&T> {
+    parts.iter().flat_map(|chunk| &chunk[p]).collect()
+}
+
+/// Partitions both inputs and merges each partition pair in parallel.
+///
+/// `merge` receives the elements of one partition of `a` and of `b`, each in input order,
+/// and returns that partition's part of the result.
+fn by_partition<T, F>(a: &[T], b: &[T], merge: F) -> Vec<T>
+where
+    T: Hash + Clone + Send + Sync,
+    F: Fn(Vec<&T>, Vec<&T>) -> Vec<T> + Sync,
+{
+    let (a_parts, b_parts) = rayon::join(|| partition(a), || partition(b));
+    let merged: Vec<Vec<T>> = (0..PARTITIONS)
+        .into_par_iter()
+        .map(|p| merge(gather(&a_parts, p), gather(&b_parts, p)))
+        .collect();
+    merged.concat()
+}
+
+/// Computes the union of two vectors in parallel, without duplicates.
+///
+/// Both inputs are hash-partitioned in parallel, and every partition is then
+/// deduplicated on its own, so no lock or shared set is needed. Duplicates within `a` or
+/// `b` are removed as well.
+///
+/// # Parameters
+/// - `a`: The first set of elements.
+/// - `b`: The second set of elements.
+///
+/// # Returns
+/// Every value that occurs in `a` or `b`, once. The order is deterministic but
+/// unspecified: values are grouped by hash partition, and within a partition keep the
+/// order of their first occurrence in `a` followed by `b`.
+pub fn parallel_union<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    by_partition(a, b, |a, b| {
+        let mut seen = HashSet::new();
+        a.into_iter()
+            .chain(b)
+            .filter(|x| seen.insert(*x))
+            .cloned()
+            .collect()
+    })
+}
+
+/// Computes the intersection of two vectors in parallel, without duplicates.
+///
+/// Partitioned like [`parallel_union`], so each partition of `b` is only ever compared
+/// against the same partition of `a`.
+///
+/// # Parameters
+/// - `a`: The first set of elements.
+/// - `b`: The second set of elements.
+///
+/// # Returns
+/// Every value that occurs in both `a` and `b`, once, grouped by hash partition and
+/// otherwise in order of first occurrence in `a`.
+pub fn parallel_intersection<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    by_partition(a, b, |a, b| {
+        let other: HashSet<&T> = b.into_iter().collect();
+        let mut seen = HashSet::new();
+        a.into_iter()
+            .filter(|x| other.contains(x) && seen.insert(*x))
+            .cloned()
+            .collect()
+    })
+}
+
+/// Computes the values of one vector that are missing from another, in parallel.
+///
+/// # Parameters
+/// - `a`: The elements to keep.
+/// - `b`: The elements to remove.
+///
+/// # Returns
+/// Every value that occurs in `a` but not in `b`, once, grouped by hash partition and
+/// otherwise in order of first occurrence in `a`.
+pub fn parallel_difference<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    by_partition(a, b, |a, b| {
+        let other: HashSet<&T> = b.into_iter().collect();
+        let mut seen = HashSet::new();
+        a.into_iter()
+            .filter(|x| !other.contains(x) && seen.insert(*x))
+            .cloned()
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that the union contains every value exactly once
+    #[test]
+    fn test_union_ids() {
+        let a: Vec<u64> = (0..100_000).map(|i| i * 2).collect();
+        let b: Vec<u64> = (0..100_000).map(|i| i * 3).collect();
+        let mut union = parallel_union(&a, &b);
+        union.sort_unstable();
+        let mut expected: Vec<u64> = a.iter().chain(&b).copied().collect();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(union, expected);
+    }
+
+    // Test with duplicates inside one input and owned strings
+    #[test]
+    fn test_union_strings() {
+        let a: Vec<String> = ["x", "y", "x"].map(String::from).to_vec();
+        let b: Vec<String> = ["y", "z"].map(String::from).to_vec();
+        let mut union = parallel_union(&a, &b);
+        union.sort();
+        assert_eq!(union, ["x", "y", "z"]);
+    }
+
+    // Test intersection and difference against sorted set arithmetic
+    #[test]
+    fn test_intersection_and_difference() {
+        let a: Vec<u64> = (0..60_000).map(|i| i * 2).collect();
+        let b: Vec<u64> = (0..60_000).map(|i| i * 3).collect();
+        let mut both = parallel_intersection(&a, &b);
+        both.sort_unstable();
+        assert_eq!(both, (0..20_000).map(|i| i * 6).collect::<Vec<_>>());
+        let mut only_a = parallel_difference(&a, &b);
+        only_a.sort_unstable();
+        let expected: Vec<u64> = a.iter().copied().filter(|x| x % 3 != 0).collect();
+        assert_eq!(only_a, expected);
+    }
+
+    // Test for empty inputs
+    #[test]
+    fn test_union_empty() {
+        assert!(parallel_union::<u8>(&[], &[]).is_empty());
+        assert_eq!(parallel_union(&[], &[5, 5]), vec![5]);
+        assert!(parallel_intersection(&[1, 2], &[]).is_empty());
+        assert_eq!(parallel_difference(&[1, 1], &[]), vec![1]);
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use crate::identity::reduce_slice_with_identity;
+use crate::Reducer;
+
+/// Performs a parallel binary operation on a shared, immutable buffer.
+///
+/// Accepts `Arc<[T]>` or `Arc<Vec<T>>`, so several threads can reduce the same data
+/// concurrently by cloning the `Arc` instead of the elements. No identity value is
+/// needed: every chunk is folded starting from its first element.
+///
+/// # Parameters
+/// - `data`: The shared elements to reduce.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or `None` for an empty buffer.
+pub fn parallel_reduce_shared<T, S>(data: Arc<S>, operation: fn(T, T) -> T) -> Option<T>
+where
+    T: Copy + Send + Sync,
+    S: AsRef<[T]> + ?Sized,
+{
+    Reducer::new(operation).run((*data).as_ref())
+}
+
+/// Performs a parallel binary operation on a shared, immutable buffer with an explicit
+/// identity.
+///
+/// The shared counterpart of
+/// [`parallel_reduce_with_identity`](crate::parallel_reduce_with_identity).
+///
+/// # Parameters
+/// - `data`: The shared elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or `identity` for an empty buffer.
+pub fn parallel_reduce_shared_with_identity<T, S>(
+    data: Arc<S>,
+    identity: T,
+    operation: fn(T, T) -> T,
+) -> T
+where
+    T: Copy + Send + Sync,
+    S: AsRef<[T]> + ?Sized,
+{
+    reduce_slice_with_identity((*data).as_ref(), identity, operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    // Test concurrent reductions over one shared slice
+    #[test]
+    fn test_shared_concurrent() {
+        let data: Arc<[i64]> = (1..=10_000).collect();
+        let handles: Vec<_> = [|a: i64, b| a + b, i64::max, i64::min]
+            .into_iter()
+            .map(|operation| {
+                let data = Arc::clone(&data);
+                thread::spawn(move || parallel_reduce_shared(data, operation))
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(results, vec![Some(50_005_000), Some(10_000), Some(1)]);
+        assert_eq!(Arc::strong_count(&data), 1);
+    }
+
+    // Test a shared vector with an explicit identity
+    #[test]
+    fn test_shared_vec_identity() {
+        let data = Arc::new(vec![1.5f64, 2.5, 4.0]);
+        assert_eq!(
+            parallel_reduce_shared_with_identity(Arc::clone(&data), 1.0, |a, b| a * b),
+            15.0
+        );
+        let empty: Arc<Vec<f64>> = Arc::new(Vec::new());
+        assert_eq!(
+            parallel_reduce_shared_with_identity(empty, 0.0, |a, b| a + b),
+            0.0
+        );
+        assert_eq!(parallel_reduce_shared(data, f64::max), Some(4.0));
+    }
+}
@@ -0,0 +1,188 @@
+use rayon::prelude::*;
+
+use crate::rng::Rng;
+use crate::ParallelConfig;
+
+/// Number of elements each input block deals out to the buckets.
+///
+/// Fixed rather than derived from the thread count, so the permutation only depends on
+/// the seed and the length.
+const BLOCK_LEN: usize = 1 << 16;
+
+/// Upper bound on the number of buckets, which bounds the per-block bookkeeping.
+const MAX_BUCKETS: usize = 256;
+
+/// Shuffles a slice in parallel into a uniformly random order.
+///
+/// Every block of the input deals its elements into randomly chosen buckets, and each
+/// bucket is then shuffled on its own with Fisher–Yates and written back in bucket order.
+/// Because every element lands in a uniformly random bucket and each bucket is uniformly
+/// shuffled, all permutations are equally likely. The same seed and length always give
+/// the same permutation, on any number of threads.
+///
+/// # Parameters
+/// - `data`: The elements to shuffle in place.
+/// - `seed`: Seed for the random permutation.
+pub fn parallel_shuffle<T>(data: &mut [T], seed: u64)
+where
+    T: Copy + Send + Sync,
+{
+    shuffle(data, seed, Vec::with_capacity, drop);
+}
+
+/// Shuffles a slice in parallel using the given configuration.
+///
+/// Produces the same permutation as [`parallel_shuffle`] for the same seed, but takes
+/// the per-bucket scratch vectors from the configuration's buffer pool, if it has one,
+/// and runs on its thread pool.
+///
+/// # Parameters
+/// - `data`: The elements to shuffle in place.
+/// - `seed`: Seed for the random permutation.
+/// - `config`: The thread count and buffer pool to use.
+pub fn parallel_shuffle_with_config<T>(data: &mut [T], seed: u64, config: &ParallelConfig)
+where
+    T: Copy + Send + Sync + 'static,
+{
+    config.install(|| {
+        shuffle(
+            data,
+            seed,
+            |capacity| config.take_buffer(capacity),
+            |buffer| config.give_buffer(buffer),
+        )
+    });
+}
+
+/// Shuffles `data`, allocating scratch vectors with `take` and releasing them with `give`.
+fn shuffle<T, Take, Give>(data: &mut [T], seed: u64, take: Take, give: Give)
+where
+    T: Copy + Send + Sync,
+    Take: Fn(usize) -> Vec<T> + Sync,
+    Give: Fn(Vec<T>) + Sync,
+{
+    if data.len() < 2 {
+        return;
+    }
+    let buckets = data.len().div_ceil(BLOCK_LEN).min(MAX_BUCKETS);
+    // The last streams are reserved for the buckets' Fisher–Yates passes
+    let bucket_stream = |bucket: usize| u64::MAX - bucket as u64;
+
+    // Deal every block's elements into the buckets, keeping block order
+    let dealt: Vec<Vec<Vec<T>>> = data
+        .par_chunks(BLOCK_LEN)
+        .enumerate()
+        .map(|(index, block)| {
+            let mut rng = Rng::stream(seed, index as u64);
+            let mut hands: Vec<Vec<T>> = (0..buckets).map(|_| take(0)).collect();
+            for &x in block {
+                hands[rng.below(buckets as u64) as usize].push(x);
+            }
+            hands
+        })
+        .collect();
+
+    // Gather and shuffle each bucket
+    let shuffled: Vec<Vec<T>> = (0..buckets)
+        .into_par_iter()
+        .map(|bucket| {
+            let len = dealt.iter().map(|hands| hands[bucket].len()).sum();
+            let mut items = take(len);
+            for hands in &dealt {
+                items.extend_from_slice(&hands[bucket]);
+            }
+            fisher_yates(&mut items, &mut Rng::stream(seed, bucket_stream(bucket)));
+            items
+        })
+        .collect();
+
+    // Split the output into one slice per bucket and copy the buckets back
+    let mut rest = data;
+    let mut targets = Vec::with_capacity(buckets);
+    for items in &shuffled {
+        let (target, tail) = rest.split_at_mut(items.len());
+        targets.push(target);
+        rest = tail;
+    }
+    targets
+        .into_par_iter()
+        .zip(&shuffled)
+        .for_each(|(target, items)| target.copy_from_slice(items));
+
+    dealt.into_iter().flatten().chain(shuffled).for_each(give);
+}
+
+/// Shuffles `items` in place with the Fisher–Yates algorithm.
+fn fisher_yates<T>(items: &mut [T], rng: &mut Rng) {
+    for i in (1..items.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        items.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that a shuffle is a permutation and reproducible from its seed
+    #[test]
+    fn test_shuffle_permutation() {
+        let original: Vec<u32> = (0..300_000).collect();
+        let mut data = original.clone();
+        parallel_shuffle(&mut data, 42);
+        assert_ne!(data, original);
+
+        let mut again = original.clone();
+        parallel_shuffle(&mut again, 42);
+        assert_eq!(again, data);
+
+        data.sort_unstable();
+        assert_eq!(data, original);
+    }
+
+    // Test that different seeds give different orders
+    #[test]
+    fn test_shuffle_seeds_differ() {
+        let mut a: Vec<u16> = (0..1_000).collect();
+        let mut b = a.clone();
+        parallel_shuffle(&mut a, 1);
+        parallel_shuffle(&mut b, 2);
+        assert_ne!(a, b);
+    }
+
+    // Test that every element reaches every position about equally often
+    #[test]
+    fn test_shuffle_uniform() {
+        let mut counts = [[0u32; 4]; 4];
+        for seed in 0..4_000 {
+            let mut data = [0usize, 1, 2, 3];
+            parallel_shuffle(&mut data, seed);
+            for (position, &x) in data.iter().enumerate() {
+                counts[x][position] += 1;
+            }
+        }
+        for row in counts {
+            for count in row {
+                assert!((850..1_150).contains(&count), "{:?}", counts);
+            }
+        }
+    }
+
+    // Test that the configured shuffle matches the plain one and recycles its buffers
+    #[test]
+    fn test_shuffle_with_config() {
+        use crate::BufferPool;
+        use std::sync::Arc;
+
+        let pool = Arc::new(BufferPool::new());
+        let config = ParallelConfig::new()
+            .with_threads(2)
+            .with_buffer_pool(Arc::clone(&pool));
+        let mut a: Vec<u32> = (0..200_000).collect();
+        let mut b = a.clone();
+        parallel_shuffle(&mut a, 7);
+        parallel_shuffle_with_config(&mut b, 7, &config);
+        assert_eq!(a, b);
+        assert!(pool.idle() > 0);
+    }
+}
@@ -0,0 +1,262 @@
+use rayon::prelude::*;
+use std::ops::{Add, Mul};
+
+use crate::error::check_lengths;
+use crate::platform::available_threads;
+use crate::{aligned_chunk_size, Error};
+
+/// Number of independent accumulators each kernel keeps.
+///
+/// Eight lanes fill a 256-bit register for 32-bit types and two for 64-bit types,
+/// which is enough for the compiler to emit packed instructions on every common target.
+const LANES: usize = 8;
+
+/// Primitive element types with vectorized chunk kernels.
+///
+/// Implemented for `i32`, `i64`, `u32`, `u64`, `f32`, and `f64`.
+pub trait SimdElement:
+    Copy + Send + Sync + PartialOrd + Add<Output = Self> + Mul<Output = Self>
+{
+    /// The additive identity.
+    const ZERO: Self;
+    /// The multiplicative identity.
+    const ONE: Self;
+    /// The identity for `min`: the largest value of the type.
+    const MIN_IDENTITY: Self;
+    /// The identity for `max`: the smallest value of the type.
+    const MAX_IDENTITY: Self;
+}
+
+macro_rules! impl_simd_int {
+    ($($t:ty),*) => {
+        $(
+            impl SimdElement for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MIN_IDENTITY: Self = <$t>::MAX;
+                const MAX_IDENTITY: Self = <$t>::MIN;
+            }
+        )*
+    };
+}
+
+macro_rules! impl_simd_float {
+    ($($t:ty),*) => {
+        $(
+            impl SimdElement for $t {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+                const MIN_IDENTITY: Self = <$t>::INFINITY;
+                const MAX_IDENTITY: Self = <$t>::NEG_INFINITY;
+            }
+        )*
+    };
+}
+
+impl_simd_int!(i32, i64, u32, u64);
+impl_simd_float!(f32, f64);
+
+/// Folds `xs` lane by lane into `LANES` accumulators, then folds the lanes and the tail.
+#[inline]
+fn lane_fold<T: SimdElement>(xs: &[T], identity: T, operation: impl Fn(T, T) -> T) -> T {
+    let mut acc = [identity; LANES];
+    let mut blocks = xs.chunks_exact(LANES);
+    for block in &mut blocks {
+        for (lane, &x) in acc.iter_mut().zip(block) {
+            *lane = operation(*lane, x);
+        }
+    }
+    let tail = blocks
+        .remainder()
+        .iter()
+        .fold(identity, |a, &b| operation(a, b));
+    acc.into_iter().fold(tail, &operation)
+}
+
+#[inline]
+pub(crate) fn min_op<T: SimdElement>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+#[inline]
+pub(crate) fn max_op<T: SimdElement>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+/// Splits `data` across the available cores and runs a lane kernel on every chunk.
+///
+/// Generic over the operation so that every caller gets its own inlined kernel.
+pub(crate) fn parallel_lane_fold<T, F>(data: &[T], identity: T, operation: F) -> T
+where
+    T: SimdElement,
+    F: Fn(T, T) -> T + Copy + Send + Sync,
+{
+    if data.is_empty() {
+        return identity;
+    }
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    data.par_chunks(chunk_size)
+        .map(|chunk| lane_fold(chunk, identity, operation))
+        .reduce(|| identity, operation)
+}
+
+/// Computes the sum of a slice with vectorized chunk kernels.
+///
+/// Float sums are accumulated in a different order than a sequential loop, so the
+/// result can differ from it in the last bits.
+///
+/// # Returns
+/// The sum of all elements, or zero for an empty slice.
+pub fn parallel_simd_sum<T: SimdElement>(data: &[T]) -> T {
+    parallel_lane_fold(data, T::ZERO, |a, b| a + b)
+}
+
+/// Computes the product of a slice with vectorized chunk kernels.
+///
+/// Float products are accumulated in a different order than a sequential loop, so the
+/// result can differ from it in the last bits.
+///
+/// # Returns
+/// The product of all elements, or one for an empty slice.
+pub fn parallel_simd_product<T: SimdElement>(data: &[T]) -> T {
+    parallel_lane_fold(data, T::ONE, |a, b| a * b)
+}
+
+/// Computes the minimum of a slice with vectorized chunk kernels.
+///
+/// NaN elements never compare smaller and are therefore ignored.
+///
+/// # Returns
+/// The smallest element, or the largest value of `T` for an empty slice.
+pub fn parallel_simd_min<T: SimdElement>(data: &[T]) -> T {
+    parallel_lane_fold(data, T::MIN_IDENTITY, min_op)
+}
+
+/// Computes the maximum of a slice with vectorized chunk kernels.
+///
+/// NaN elements never compare larger and are therefore ignored.
+///
+/// # Returns
+/// The largest element, or the smallest value of `T` for an empty slice.
+pub fn parallel_simd_max<T: SimdElement>(data: &[T]) -> T {
+    parallel_lane_fold(data, T::MAX_IDENTITY, max_op)
+}
+
+/// Computes the dot product of two slices with vectorized chunk kernels.
+///
+/// # Returns
+/// The sum of `a[i] * b[i]` over all indices, or zero for empty slices.
+///
+/// # Panics
+/// Panics if `a` and `b` have different lengths.
+pub fn parallel_simd_dot<T: SimdElement>(a: &[T], b: &[T]) -> T {
+    assert_eq!(a.len(), b.len(), "dot product operands differ in length");
+    if a.is_empty() {
+        return T::ZERO;
+    }
+
+    let chunk_size = aligned_chunk_size(a, available_threads());
+    a.par_chunks(chunk_size)
+        .zip(b.par_chunks(chunk_size))
+        .map(|(xs, ys)| lane_dot(xs, ys))
+        .reduce(|| T::ZERO, |x, y| x + y)
+}
+
+/// Computes the dot product of two equally long slices lane by lane on one thread.
+pub(crate) fn lane_dot<T: SimdElement>(xs: &[T], ys: &[T]) -> T {
+    let mut acc = [T::ZERO; LANES];
+    let mut x_blocks = xs.chunks_exact(LANES);
+    let mut y_blocks = ys.chunks_exact(LANES);
+    for (xb, yb) in (&mut x_blocks).zip(&mut y_blocks) {
+        for ((lane, &x), &y) in acc.iter_mut().zip(xb).zip(yb) {
+            *lane = *lane + x * y;
+        }
+    }
+    let tail = x_blocks
+        .remainder()
+        .iter()
+        .zip(y_blocks.remainder())
+        .fold(T::ZERO, |s, (&x, &y)| s + x * y);
+    acc.into_iter().fold(tail, |s, lane| s + lane)
+}
+
+/// Computes the dot product of two slices with vectorized chunk kernels, reporting a
+/// length mismatch as an error.
+///
+/// # Returns
+/// The sum of `a[i] * b[i]` over all indices, zero for empty slices, or
+/// [`Error::LengthMismatch`].
+pub fn try_parallel_simd_dot<T: SimdElement>(a: &[T], b: &[T]) -> Result<T, Error> {
+    check_lengths(a.len(), b.len())?;
+    Ok(parallel_simd_dot(a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test for integer sum, including a tail shorter than one lane block
+    #[test]
+    fn test_simd_sum() {
+        let data: Vec<i64> = (1..=1_003).collect();
+        assert_eq!(parallel_simd_sum(&data), 1_003 * 1_004 / 2);
+    }
+
+    // Test for float product
+    #[test]
+    fn test_simd_product() {
+        let data: Vec<f64> = (1..=20)
+            .map(|i| if i % 2 == 0 { 2.0 } else { 0.5 })
+            .collect();
+        assert_eq!(parallel_simd_product(&data), 1.0);
+        assert_eq!(parallel_simd_product::<i32>(&[]), 1);
+    }
+
+    // Test for min and max
+    #[test]
+    fn test_simd_min_max() {
+        let data: Vec<i32> = (0..1_000).map(|i| (i * 7919) % 1_009 - 500).collect();
+        assert_eq!(parallel_simd_min(&data), *data.iter().min().unwrap());
+        assert_eq!(parallel_simd_max(&data), *data.iter().max().unwrap());
+    }
+
+    // Test that NaN is ignored by float min
+    #[test]
+    fn test_simd_min_ignores_nan() {
+        let data = vec![3.0f32, f32::NAN, -2.0, 5.0];
+        assert_eq!(parallel_simd_min(&data), -2.0);
+    }
+
+    // Test for dot product
+    #[test]
+    fn test_simd_dot() {
+        let a: Vec<f64> = (0..100).map(f64::from).collect();
+        let b = vec![0.5; 100];
+        assert_eq!(parallel_simd_dot(&a, &b), 2_475.0);
+    }
+
+    // Test that mismatched lengths are reported
+    #[test]
+    fn test_try_simd_dot() {
+        assert_eq!(try_parallel_simd_dot(&[1, 2], &[3, 4]), Ok(11));
+        assert_eq!(
+            try_parallel_simd_dot(&[1.0f32], &[]),
+            Err(Error::LengthMismatch { left: 1, right: 0 })
+        );
+    }
+
+    // Test for empty slices
+    #[test]
+    fn test_simd_empty() {
+        assert_eq!(parallel_simd_sum::<u32>(&[]), 0);
+        assert_eq!(parallel_simd_max::<u64>(&[]), 0);
+    }
+}
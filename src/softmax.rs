@@ -0,0 +1,109 @@
+use rayon::prelude::*;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Computes `ln(sum(exp(x)))` in parallel without overflow.
+///
+/// Each chunk finds its maximum `m` and sums `exp(x - m)`, so no exponent exceeds 1;
+/// chunks are merged by rescaling the smaller maximum's sum to the larger maximum.
+/// Logits in the thousands, whose plain exponentials overflow, are handled exactly.
+///
+/// # Parameters
+/// - `data`: The values, typically logits.
+///
+/// # Returns
+/// The log-sum-exp; negative infinity for an empty slice, NaN if any value is NaN.
+pub fn parallel_logsumexp<T>(data: &[T]) -> f64
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    let (max, sum) = data
+        .par_chunks(aligned_chunk_size(data, available_threads()))
+        .map(|chunk| {
+            let max = chunk
+                .iter()
+                .map(|&x| x.into())
+                .fold(f64::NEG_INFINITY, f64::max);
+            let sum: f64 = chunk.iter().map(|&x| scaled_exp(x.into(), max)).sum();
+            (max, sum)
+        })
+        .reduce(|| (f64::NEG_INFINITY, 0.0), merge);
+    max + sum.ln()
+}
+
+/// Computes the softmax of a slice in parallel.
+///
+/// The log-sum-exp is computed in a first parallel pass with the max-subtraction trick,
+/// and each output is `exp(x - logsumexp)` in a second, so large logits neither overflow
+/// nor lose precision.
+///
+/// # Parameters
+/// - `data`: The values, typically logits.
+///
+/// # Returns
+/// Probabilities summing to 1, in order; empty for an empty slice.
+pub fn parallel_softmax<T>(data: &[T]) -> Vec<f64>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    let lse = parallel_logsumexp(data);
+    data.par_iter().map(|&x| (x.into() - lse).exp()).collect()
+}
+
+/// Returns `exp(x - max)`, defined for infinite `x` as well: the maximum itself
+/// contributes 1 and negative infinity nothing.
+fn scaled_exp(x: f64, max: f64) -> f64 {
+    if x == max {
+        1.0
+    } else if x == f64::NEG_INFINITY {
+        0.0
+    } else {
+        (x - max).exp()
+    }
+}
+
+/// Merges two chunks' `(max, sum of exp(x - max))` pairs.
+fn merge((max1, sum1): (f64, f64), (max2, sum2): (f64, f64)) -> (f64, f64) {
+    let max = max1.max(max2);
+    (
+        max,
+        sum1 * scaled_exp(max1, max) + sum2 * scaled_exp(max2, max),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test log-sum-exp on logits whose exponentials overflow
+    #[test]
+    fn test_logsumexp_large() {
+        let lse = parallel_logsumexp(&[1_000.0, 1_000.0]);
+        assert!((lse - (1_000.0 + 2f64.ln())).abs() < 1e-12);
+        let data: Vec<f32> = (0..100_000).map(|i| (i % 7) as f32 * 100.0).collect();
+        let expected = 600.0 + (100_000f64 / 7.0).floor().ln();
+        assert!((parallel_logsumexp(&data) - expected).abs() < 1e-3);
+    }
+
+    // Test that softmax probabilities sum to one and keep their order
+    #[test]
+    fn test_softmax() {
+        let logits: Vec<f64> = (0..10_000).map(|i| f64::from(i % 100) * 10.0).collect();
+        let probabilities = parallel_softmax(&logits);
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(probabilities[99] > probabilities[98]);
+        let even = parallel_softmax(&[3.0, 3.0]);
+        assert!(even.iter().all(|p| (p - 0.5).abs() < 1e-15));
+    }
+
+    // Test empty input and infinities
+    #[test]
+    fn test_logsumexp_edge_cases() {
+        assert_eq!(parallel_logsumexp::<f64>(&[]), f64::NEG_INFINITY);
+        assert!(parallel_softmax::<f64>(&[]).is_empty());
+        assert_eq!(parallel_logsumexp(&[f64::NEG_INFINITY, 0.0]), 0.0);
+        assert_eq!(parallel_logsumexp(&[1.0, f64::INFINITY]), f64::INFINITY);
+        assert!(parallel_logsumexp(&[1.0, f64::NAN]).is_nan());
+    }
+}
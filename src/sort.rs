@@ -0,0 +1,295 @@
+use rayon::prelude::*;
+use std::cmp::Ordering;
+
+use crate::platform::{available_threads, parallel_worthwhile};
+use crate::ParallelConfig;
+
+/// Length of the runs [`merge_sort`] sorts by insertion before merging.
+const RUN_LEN: usize = 32;
+
+/// A comparison shared by the threads of a sort.
+type Compare<'a, T> = &'a (dyn Fn(&T, &T) -> Ordering + Sync);
+
+/// Sorts a slice by a key in parallel, keeping equal elements in their original order.
+///
+/// Runs a parallel merge sort, so records with equal keys stay in input order, as with
+/// [`slice::sort_by_key`]. The key function is called on every comparison; for expensive
+/// keys, sort a vector of `(key, index)` pairs instead.
+///
+/// # Parameters
+/// - `data`: The elements to sort in place.
+/// - `key_fn`: Extracts the key to sort by.
+pub fn parallel_sort_by_key<T, K, F>(data: &mut [T], key_fn: F)
+where
+    T: Send,
+    K: Ord,
+    F: Fn(&T) -> K + Sync,
+{
+    if parallel_worthwhile(available_threads()) {
+        data.par_sort_by_key(key_fn);
+    } else {
+        data.sort_by_key(key_fn);
+    }
+}
+
+/// Sorts a slice by a key in parallel using the given configuration, keeping equal
+/// elements in their original order.
+///
+/// Unlike [`parallel_sort_by_key`], whose merge sort allocates its own scratch space,
+/// the scratch buffer is taken from the configuration's buffer pool, if it has one.
+///
+/// # Parameters
+/// - `data`: The elements to sort in place.
+/// - `key_fn`: Extracts the key to sort by.
+/// - `config`: The thread count and buffer pool to use.
+pub fn parallel_sort_by_key_with_config<T, K, F>(data: &mut [T], key_fn: F, config: &ParallelConfig)
+where
+    T: Copy + Send + Sync + 'static,
+    K: Ord,
+    F: Fn(&T) -> K + Sync,
+{
+    sort_with_config(data, &|a, b| key_fn(a).cmp(&key_fn(b)), config);
+}
+
+/// One component of a composite sort order, for [`parallel_sort_by_keys`].
+///
+/// Keys of different types can be mixed in one order, e.g. a `String` country followed
+/// by a `u64` timestamp.
+pub struct SortKey<'a, T> {
+    compare: Comparator<'a, T>,
+}
+
+/// A boxed comparison between two elements.
+type Comparator<'a, T> = Box<dyn Fn(&T, &T) -> Ordering + Sync + 'a>;
+
+impl<'a, T> SortKey<'a, T> {
+    /// Orders by the key `key_fn` extracts, smallest first.
+    pub fn asc<K, F>(key_fn: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Sync + 'a,
+    {
+        SortKey {
+            compare: Box::new(move |a, b| key_fn(a).cmp(&key_fn(b))),
+        }
+    }
+
+    /// Orders by the key `key_fn` extracts, largest first.
+    pub fn desc<K, F>(key_fn: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Sync + 'a,
+    {
+        SortKey {
+            compare: Box::new(move |a, b| key_fn(b).cmp(&key_fn(a))),
+        }
+    }
+}
+
+/// Sorts a slice lexicographically by several keys in parallel, keeping equal elements
+/// in their original order.
+///
+/// Elements are compared by the first key, ties are broken by the second, and so on,
+/// so `[SortKey::asc(|r| r.country.clone()), SortKey::asc(|r| r.timestamp)]` sorts by
+/// country and then by time within each country. Later keys are only evaluated for
+/// elements that tie on the earlier ones. Like [`parallel_sort_by_key`], the sort is a
+/// stable parallel merge sort.
+///
+/// # Parameters
+/// - `data`: The elements to sort in place.
+/// - `keys`: The keys to sort by, most significant first.
+pub fn parallel_sort_by_keys<T>(data: &mut [T], keys: &[SortKey<'_, T>])
+where
+    T: Send,
+{
+    let compare = |a: &T, b: &T| {
+        keys.iter()
+            .map(|key| (key.compare)(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    };
+    if parallel_worthwhile(available_threads()) {
+        data.par_sort_by(compare);
+    } else {
+        data.sort_by(compare);
+    }
+}
+
+/// Sorts a slice lexicographically by several keys in parallel using the given
+/// configuration, keeping equal elements in their original order.
+///
+/// Like [`parallel_sort_by_key_with_config`], the scratch buffer is taken from the
+/// configuration's buffer pool, if it has one.
+///
+/// # Parameters
+/// - `data`: The elements to sort in place.
+/// - `keys`: The keys to sort by, most significant first.
+/// - `config`: The thread count and buffer pool to use.
+pub fn parallel_sort_by_keys_with_config<T>(
+    data: &mut [T],
+    keys: &[SortKey<'_, T>],
+    config: &ParallelConfig,
+) where
+    T: Copy + Send + Sync + 'static,
+{
+    let compare = |a: &T, b: &T| {
+        keys.iter()
+            .map(|key| (key.compare)(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    };
+    sort_with_config(data, &compare, config);
+}
+
+/// Runs [`merge_sort`] on the configured pool with a scratch buffer from its buffer pool.
+fn sort_with_config<T>(data: &mut [T], compare: Compare<'_, T>, config: &ParallelConfig)
+where
+    T: Copy + Send + Sync + 'static,
+{
+    if data.len() < 2 {
+        return;
+    }
+    let mut scratch = config.take_buffer(data.len());
+    scratch.extend_from_slice(data);
+    config.install(|| merge_sort(data, &mut scratch, compare));
+    config.give_buffer(scratch);
+}
+
+/// Sorts `data` stably with a bottom-up merge sort, using `scratch` (as long as `data`)
+/// as the other half of every merge pass.
+///
+/// Runs of [`RUN_LEN`] are sorted by insertion, and every pass then merges neighbouring
+/// runs in parallel, alternating between `data` and `scratch`.
+fn merge_sort<T>(data: &mut [T], scratch: &mut [T], compare: Compare<'_, T>)
+where
+    T: Copy + Send + Sync,
+{
+    data.par_chunks_mut(RUN_LEN)
+        .for_each(|run| insertion_sort(run, compare));
+
+    let mut width = RUN_LEN;
+    let mut sorted_in_data = true;
+    while width < data.len() {
+        if sorted_in_data {
+            merge_pass(data, scratch, width, compare);
+        } else {
+            merge_pass(scratch, data, width, compare);
+        }
+        sorted_in_data = !sorted_in_data;
+        width *= 2;
+    }
+    if !sorted_in_data {
+        data.copy_from_slice(scratch);
+    }
+}
+
+/// Merges each pair of neighbouring sorted runs of `width` from `src` into `dst`.
+fn merge_pass<T>(src: &[T], dst: &mut [T], width: usize, compare: Compare<'_, T>)
+where
+    T: Copy + Send + Sync,
+{
+    dst.par_chunks_mut(2 * width)
+        .zip(src.par_chunks(2 * width))
+        .for_each(|(out, pair)| {
+            let (left, right) = pair.split_at(width.min(pair.len()));
+            let (mut i, mut j) = (0, 0);
+            for slot in out.iter_mut() {
+                // Ties take the left element, which keeps the merge stable
+                if j == right.len() || (i < left.len() && compare(&right[j], &left[i]).is_ge()) {
+                    *slot = left[i];
+                    i += 1;
+                } else {
+                    *slot = right[j];
+                    j += 1;
+                }
+            }
+        });
+}
+
+/// Sorts a short run stably in place.
+fn insertion_sort<T: Copy>(run: &mut [T], compare: Compare<'_, T>) {
+    for i in 1..run.len() {
+        let x = run[i];
+        let mut j = i;
+        while j > 0 && compare(&run[j - 1], &x).is_gt() {
+            run[j] = run[j - 1];
+            j -= 1;
+        }
+        run[j] = x;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that records with equal keys keep their input order
+    #[test]
+    fn test_sort_by_key_stable() {
+        let mut records: Vec<(usize, usize)> = (0..50_000).map(|i| ((i * 7_919) % 13, i)).collect();
+        parallel_sort_by_key(&mut records, |&(key, _)| key);
+        for pair in records.windows(2) {
+            assert!(pair[0].0 < pair[1].0 || (pair[0].0 == pair[1].0 && pair[0].1 < pair[1].1));
+        }
+    }
+
+    // Test sorting by a derived key
+    #[test]
+    fn test_sort_by_key_derived() {
+        let mut words = vec!["ccc", "a", "bb", "dd", "e"];
+        parallel_sort_by_key(&mut words, |w| w.len());
+        assert_eq!(words, ["a", "e", "bb", "dd", "ccc"]);
+    }
+
+    // Test a composite order mixing key types and directions, with stable ties
+    #[test]
+    fn test_sort_by_keys() {
+        let mut records: Vec<(String, u64, usize)> = (0..30_000)
+            .map(|i| (format!("c{}", i % 3), (i as u64 * 7_919) % 5, i))
+            .collect();
+        parallel_sort_by_keys(
+            &mut records,
+            &[
+                SortKey::asc(|r: &(String, u64, usize)| r.0.clone()),
+                SortKey::desc(|r: &(String, u64, usize)| r.1),
+            ],
+        );
+        assert_eq!(records[0].0, "c0");
+        assert_eq!(records[0].1, 4);
+        for pair in records.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            let order = a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2));
+            assert_eq!(order, Ordering::Less);
+        }
+    }
+
+    // Test the pooled merge sort against a stable sort, for several lengths and keys
+    #[test]
+    fn test_sort_with_config() {
+        use crate::BufferPool;
+        use std::sync::Arc;
+
+        let pool = Arc::new(BufferPool::new());
+        let config = ParallelConfig::new().with_buffer_pool(Arc::clone(&pool));
+        for len in [0, 1, 31, 33, 1_000, 70_001] {
+            let records: Vec<(u32, usize)> =
+                (0..len).map(|i| (((i * 7_919) % 97) as u32, i)).collect();
+            let mut expected = records.clone();
+            expected.sort_by_key(|&(key, _)| key);
+            let mut sorted = records.clone();
+            parallel_sort_by_key_with_config(&mut sorted, |&(key, _)| key, &config);
+            assert_eq!(sorted, expected);
+
+            expected.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            let mut sorted = records;
+            parallel_sort_by_keys_with_config(
+                &mut sorted,
+                &[SortKey::desc(|r: &(u32, usize)| r.0)],
+                &config,
+            );
+            assert_eq!(sorted, expected);
+        }
+        // Each length needed a larger scratch buffer once, and reused it for the second sort
+        assert_eq!(pool.idle(), 4);
+    }
+}
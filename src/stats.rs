@@ -0,0 +1,522 @@
+use rayon::prelude::*;
+
+use crate::config::{ParallelConfig, VarianceAlgorithm};
+use crate::platform::available_threads;
+use crate::{aligned_chunk_size, plan_for, Error, FloatPolicy, NonFiniteError};
+
+/// Computes the sample variance of a slice in parallel.
+///
+/// Uses the default [`VarianceAlgorithm::OnePass`]; see
+/// [`parallel_variance_with_config`] to choose the two-pass algorithm.
+///
+/// # Parameters
+/// - `data`: The values.
+///
+/// # Returns
+/// The unbiased sample variance (divided by `n - 1`), or `None` for fewer than two values.
+pub fn parallel_variance<T>(data: &[T]) -> Option<f64>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    parallel_variance_with_config(data, &ParallelConfig::tuned::<T>())
+}
+
+/// Computes the sample variance of a slice in parallel using the given configuration.
+///
+/// [`VarianceAlgorithm::OnePass`] reads the data once, accumulating the sum and the sum
+/// of squares; when the mean is large compared with the spread, the two nearly cancel
+/// and the result can lose every significant digit. [`VarianceAlgorithm::TwoPass`]
+/// computes the mean first and then sums the squared deviations from it, which stays
+/// accurate at the cost of reading the data twice.
+///
+/// # Parameters
+/// - `data`: The values.
+/// - `config`: The thread count, chunking, and variance algorithm to use.
+///
+/// # Returns
+/// The unbiased sample variance (divided by `n - 1`), or `None` for fewer than two values.
+pub fn parallel_variance_with_config<T>(data: &[T], config: &ParallelConfig) -> Option<f64>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    variance(data, config, false)
+}
+
+/// Computes the sample variance of a slice in parallel with a policy for non-finite
+/// values.
+///
+/// # Parameters
+/// - `data`: The values.
+/// - `policy`: How NaN and infinite values are handled; [`FloatPolicy::Skip`] computes
+///   the variance of the finite values only.
+///
+/// # Returns
+/// The unbiased sample variance, `None` for fewer than two (included) values, or a
+/// [`NonFiniteError`] with the first offending index under [`FloatPolicy::Error`].
+pub fn parallel_variance_with_policy<T>(
+    data: &[T],
+    policy: FloatPolicy,
+) -> Result<Option<f64>, NonFiniteError>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    let skip = skip_non_finite(data, policy)?;
+    Ok(variance(data, &ParallelConfig::tuned::<T>(), skip))
+}
+
+/// Computes the sample variance, leaving out non-finite values if `skip` is set.
+fn variance<T>(data: &[T], config: &ParallelConfig, skip: bool) -> Option<f64>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    if data.len() < 2 {
+        return None;
+    }
+    let chunk_size = plan_for::<T>(data.len(), config).chunk_size;
+
+    let (n, squared_deviations) = config.install(|| match config.variance {
+        VarianceAlgorithm::OnePass => {
+            let (n, sum, sum_sq) = data
+                .par_chunks(chunk_size)
+                .map(|chunk| {
+                    values(chunk, skip).fold((0.0, 0.0, 0.0), |(n, s, sq), x| {
+                        (n + 1.0, s + x, sq + x * x)
+                    })
+                })
+                .reduce(
+                    || (0.0, 0.0, 0.0),
+                    |(n1, s1, sq1), (n2, s2, sq2)| (n1 + n2, s1 + s2, sq1 + sq2),
+                );
+            (n, sum_sq - sum * sum / n)
+        }
+        VarianceAlgorithm::TwoPass => {
+            let (n, total) = sum(data, chunk_size, skip, |x| x);
+            let mean = total / n;
+            let (_, squares) = sum(data, chunk_size, skip, |x| (x - mean) * (x - mean));
+            (n, squares)
+        }
+    });
+    if n < 2.0 {
+        return None;
+    }
+    // Rounding can leave the one-pass result slightly negative; NaN must stay NaN
+    let squared_deviations = if squared_deviations < 0.0 {
+        0.0
+    } else {
+        squared_deviations
+    };
+    Some(squared_deviations / (n - 1.0))
+}
+
+/// Computes the sample standard deviation of a slice in parallel.
+///
+/// # Returns
+/// The square root of [`parallel_variance`], or `None` for fewer than two values.
+pub fn parallel_std_dev<T>(data: &[T]) -> Option<f64>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    parallel_variance(data).map(f64::sqrt)
+}
+
+/// Computes the sample standard deviation of a slice in parallel with a policy for
+/// non-finite values.
+///
+/// # Returns
+/// The square root of [`parallel_variance_with_policy`].
+pub fn parallel_std_dev_with_policy<T>(
+    data: &[T],
+    policy: FloatPolicy,
+) -> Result<Option<f64>, NonFiniteError>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    parallel_variance_with_policy(data, policy).map(|variance| variance.map(f64::sqrt))
+}
+
+/// Computes the sample variance of a slice in parallel, reporting too-short input as an
+/// error.
+///
+/// # Parameters
+/// - `data`: The values.
+///
+/// # Returns
+/// The unbiased sample variance, or [`Error::TooFewElements`] for fewer than two values.
+pub fn try_parallel_variance<T>(data: &[T]) -> Result<f64, Error>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    parallel_variance(data).ok_or(Error::TooFewElements {
+        required: 2,
+        len: data.len(),
+    })
+}
+
+/// Computes the sample standard deviation of a slice in parallel, reporting too-short
+/// input as an error.
+///
+/// # Returns
+/// The square root of [`try_parallel_variance`], or [`Error::TooFewElements`] for fewer
+/// than two values.
+pub fn try_parallel_std_dev<T>(data: &[T]) -> Result<f64, Error>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    try_parallel_variance(data).map(f64::sqrt)
+}
+
+/// Finds the positions of values more than `threshold` standard deviations from the mean.
+///
+/// One parallel pass computes the mean and sample standard deviation, and a second
+/// flags the outliers.
+///
+/// # Parameters
+/// - `data`: The values.
+/// - `threshold`: The absolute z-score above which a value is an outlier, e.g. `3.0`.
+///
+/// # Returns
+/// The indices of the outliers in ascending order; empty for fewer than two values or if
+/// all values are equal.
+pub fn parallel_zscore_outliers<T>(data: &[T], threshold: f64) -> Vec<usize>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    zscore_outliers(data, threshold, false)
+}
+
+/// Finds the positions of outliers with a policy for non-finite values.
+///
+/// # Parameters
+/// - `data`: The values.
+/// - `threshold`: The absolute z-score above which a value is an outlier, e.g. `3.0`.
+/// - `policy`: How NaN and infinite values are handled; [`FloatPolicy::Skip`] leaves them
+///   out of the mean and standard deviation and never reports them.
+///
+/// # Returns
+/// The indices of the outliers in ascending order, or a [`NonFiniteError`] with the first
+/// offending index under [`FloatPolicy::Error`].
+pub fn parallel_zscore_outliers_with_policy<T>(
+    data: &[T],
+    threshold: f64,
+    policy: FloatPolicy,
+) -> Result<Vec<usize>, NonFiniteError>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    let skip = skip_non_finite(data, policy)?;
+    Ok(zscore_outliers(data, threshold, skip))
+}
+
+/// Finds the outliers, leaving out non-finite values if `skip` is set.
+fn zscore_outliers<T>(data: &[T], threshold: f64, skip: bool) -> Vec<usize>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    if data.len() < 2 {
+        return Vec::new();
+    }
+    let (mean, std_dev) = mean_std_dev(data, skip);
+    if std_dev == 0.0 {
+        return Vec::new();
+    }
+
+    data.par_iter()
+        .enumerate()
+        .filter(|&(_, &x)| {
+            let x: f64 = x.into();
+            (!skip || x.is_finite()) && (x - mean).abs() > threshold * std_dev
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// How [`parallel_normalize`] rescales the values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Normalization {
+    /// Maps the smallest value to 0 and the largest to 1.
+    MinMax,
+    /// Subtracts the mean and divides by the sample standard deviation.
+    ZScore,
+}
+
+/// Normalizes values in parallel.
+///
+/// A first parallel pass computes the statistics the method needs (the minimum and
+/// maximum, or the mean and standard deviation) and a second applies the transform.
+/// NaN values are ignored by [`Normalization::MinMax`] and stay NaN.
+///
+/// # Parameters
+/// - `data`: The values.
+/// - `method`: The normalization to apply.
+///
+/// # Returns
+/// The normalized values, in order. If all values are equal, every output is `0.0`.
+pub fn parallel_normalize<T>(data: &[T], method: Normalization) -> Vec<f64>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    normalize(data, method, false)
+}
+
+/// Normalizes values in parallel with a policy for non-finite values.
+///
+/// # Parameters
+/// - `data`: The values.
+/// - `method`: The normalization to apply.
+/// - `policy`: How NaN and infinite values are handled; [`FloatPolicy::Skip`] leaves them
+///   out of the statistics and maps them to NaN.
+///
+/// # Returns
+/// The normalized values, in order, or a [`NonFiniteError`] with the first offending
+/// index under [`FloatPolicy::Error`].
+pub fn parallel_normalize_with_policy<T>(
+    data: &[T],
+    method: Normalization,
+    policy: FloatPolicy,
+) -> Result<Vec<f64>, NonFiniteError>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    let skip = skip_non_finite(data, policy)?;
+    Ok(normalize(data, method, skip))
+}
+
+/// Normalizes the values, leaving non-finite ones out of the statistics if `skip` is set.
+fn normalize<T>(data: &[T], method: Normalization, skip: bool) -> Vec<f64>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    let (offset, scale) = match method {
+        Normalization::MinMax => {
+            let (min, max) = data
+                .par_iter()
+                .map(|&x| x.into())
+                .filter(|x: &f64| !skip || x.is_finite())
+                .fold(
+                    || (f64::INFINITY, f64::NEG_INFINITY),
+                    |(min, max), x: f64| (min.min(x), max.max(x)),
+                )
+                .reduce(
+                    || (f64::INFINITY, f64::NEG_INFINITY),
+                    |(min1, max1), (min2, max2)| (min1.min(min2), max1.max(max2)),
+                );
+            (min, max - min)
+        }
+        Normalization::ZScore => mean_std_dev(data, skip),
+    };
+
+    data.par_iter()
+        .map(|&x| {
+            let x: f64 = x.into();
+            if skip && !x.is_finite() {
+                f64::NAN
+            } else if scale > 0.0 {
+                (x - offset) / scale
+            } else {
+                // Constant input has no spread to divide by; NaN still stays NaN
+                0.0 * x
+            }
+        })
+        .collect()
+}
+
+/// Computes the mean and sample standard deviation in one parallel pass.
+///
+/// Per-chunk `(count, mean, sum of squared deviations)` triples are merged, so large
+/// means do not cancel out the spread. The standard deviation is NaN for fewer than two
+/// values. Non-finite values are left out if `skip` is set.
+fn mean_std_dev<T>(data: &[T], skip: bool) -> (f64, f64)
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    let (n, mean, m2) = data
+        .par_chunks(chunk_size)
+        .map(|chunk| {
+            values(chunk, skip).fold((0.0, 0.0, 0.0), |(n, mean, m2), x| {
+                let n = n + 1.0;
+                let delta = x - mean;
+                let mean = mean + delta / n;
+                (n, mean, m2 + delta * (x - mean))
+            })
+        })
+        .reduce(|| (0.0, 0.0, 0.0), merge_moments);
+    (mean, (m2 / (n - 1.0)).sqrt())
+}
+
+/// Merges the `(count, mean, sum of squared deviations)` of two disjoint parts.
+fn merge_moments(a: (f64, f64, f64), b: (f64, f64, f64)) -> (f64, f64, f64) {
+    let ((na, mean_a, m2a), (nb, mean_b, m2b)) = (a, b);
+    let n = na + nb;
+    if n == 0.0 {
+        return a;
+    }
+    let delta = mean_b - mean_a;
+    (
+        n,
+        mean_a + delta * nb / n,
+        m2a + m2b + delta * delta * na * nb / n,
+    )
+}
+
+/// Counts the values and sums `f` over them, chunk by chunk.
+fn sum<T>(data: &[T], chunk_size: usize, skip: bool, f: impl Fn(f64) -> f64 + Sync) -> (f64, f64)
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    data.par_chunks(chunk_size)
+        .map(|chunk| values(chunk, skip).fold((0.0, 0.0), |(n, s), x| (n + 1.0, s + f(x))))
+        .reduce(|| (0.0, 0.0), |(n1, s1), (n2, s2)| (n1 + n2, s1 + s2))
+}
+
+/// Converts a chunk's values, leaving out non-finite ones if `skip` is set.
+fn values<T>(chunk: &[T], skip: bool) -> impl Iterator<Item = f64> + '_
+where
+    T: Copy + Into<f64>,
+{
+    chunk
+        .iter()
+        .map(|&x| x.into())
+        .filter(move |x: &f64| !skip || x.is_finite())
+}
+
+/// Applies `policy` before a statistic is computed.
+///
+/// # Returns
+/// Whether non-finite values are to be skipped, or the first one's index under
+/// [`FloatPolicy::Error`].
+fn skip_non_finite<T>(data: &[T], policy: FloatPolicy) -> Result<bool, NonFiniteError>
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    match policy {
+        FloatPolicy::Propagate => Ok(false),
+        FloatPolicy::Skip => Ok(true),
+        FloatPolicy::Error => match data.par_iter().position_first(|&x| !x.into().is_finite()) {
+            Some(index) => Err(NonFiniteError { index }),
+            None => Ok(false),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test both algorithms on a small data set with a known variance
+    #[test]
+    fn test_variance_known() {
+        let data = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        for algorithm in [VarianceAlgorithm::OnePass, VarianceAlgorithm::TwoPass] {
+            let config = ParallelConfig::new().with_variance_algorithm(algorithm);
+            let variance = parallel_variance_with_config(&data, &config).unwrap();
+            assert!((variance - 32.0 / 7.0).abs() < 1e-12);
+        }
+        assert_eq!(parallel_std_dev(&[1u32, 3]), Some(2f64.sqrt()));
+    }
+
+    // Test that the two-pass algorithm survives a large mean that ruins the one-pass sum
+    #[test]
+    fn test_variance_large_mean() {
+        let data: Vec<f64> = (0..100_000).map(|i| 1e9 + f64::from(i % 2)).collect();
+        let two_pass = ParallelConfig::new().with_variance_algorithm(VarianceAlgorithm::TwoPass);
+        let variance = parallel_variance_with_config(&data, &two_pass).unwrap();
+        assert!((variance - 0.250_002_5).abs() < 1e-6, "{}", variance);
+
+        let one_pass = parallel_variance(&data).unwrap();
+        assert!((one_pass - 0.250_002_5).abs() > 1e-3, "{}", one_pass);
+    }
+
+    // Test that planted outliers are flagged on a large offset
+    #[test]
+    fn test_zscore_outliers() {
+        let mut data: Vec<f64> = (0..10_000).map(|i| 1e9 + f64::from(i % 10)).collect();
+        data[123] = 1e9 + 100.0;
+        data[9_000] = 1e9 - 100.0;
+        assert_eq!(parallel_zscore_outliers(&data, 3.0), [123, 9_000]);
+        assert!(parallel_zscore_outliers(&[5u8; 100], 1.0).is_empty());
+        assert!(parallel_zscore_outliers(&[1.0f32], 1.0).is_empty());
+    }
+
+    // Test both normalizations, including constant input
+    #[test]
+    fn test_normalize() {
+        let data = [2u32, 4, 6, 10];
+        assert_eq!(
+            parallel_normalize(&data, Normalization::MinMax),
+            [0.0, 0.25, 0.5, 1.0]
+        );
+        let z = parallel_normalize(&data, Normalization::ZScore);
+        let mean = z.iter().sum::<f64>() / 4.0;
+        let variance = z.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / 3.0;
+        assert!(mean.abs() < 1e-12 && (variance - 1.0).abs() < 1e-12);
+
+        assert_eq!(
+            parallel_normalize(&[7.0; 3], Normalization::ZScore),
+            [0.0; 3]
+        );
+        let with_nan = parallel_normalize(&[1.0, f64::NAN, 3.0], Normalization::MinMax);
+        assert!(with_nan[1].is_nan());
+        assert_eq!((with_nan[0], with_nan[2]), (0.0, 1.0));
+    }
+
+    // Test each float policy on the statistics
+    #[test]
+    fn test_statistics_policy() {
+        let data = [
+            2.0,
+            4.0,
+            f64::NAN,
+            4.0,
+            4.0,
+            5.0,
+            5.0,
+            f64::INFINITY,
+            7.0,
+            9.0,
+        ];
+        let variance = parallel_variance_with_policy(&data, FloatPolicy::Skip).unwrap();
+        assert!((variance.unwrap() - 32.0 / 7.0).abs() < 1e-12);
+        let std_dev = parallel_std_dev_with_policy(&data, FloatPolicy::Propagate).unwrap();
+        assert!(std_dev.unwrap().is_nan());
+        assert_eq!(
+            parallel_variance_with_policy(&data, FloatPolicy::Error),
+            Err(NonFiniteError { index: 2 })
+        );
+
+        let mut outliers: Vec<f64> = (0..1_000).map(|i| f64::from(i % 10)).collect();
+        outliers[10] = f64::INFINITY;
+        outliers[500] = 100.0;
+        assert_eq!(
+            parallel_zscore_outliers_with_policy(&outliers, 3.0, FloatPolicy::Skip),
+            Ok(vec![500])
+        );
+        assert_eq!(
+            parallel_zscore_outliers_with_policy(&outliers, 3.0, FloatPolicy::Error),
+            Err(NonFiniteError { index: 10 })
+        );
+
+        let scaled = parallel_normalize_with_policy(
+            &[1.0, f64::NEG_INFINITY, 3.0],
+            Normalization::MinMax,
+            FloatPolicy::Skip,
+        )
+        .unwrap();
+        assert_eq!((scaled[0], scaled[2]), (0.0, 1.0));
+        assert!(scaled[1].is_nan());
+    }
+
+    // Test inputs too short for a sample variance
+    #[test]
+    fn test_variance_too_short() {
+        assert_eq!(parallel_variance::<f64>(&[]), None);
+        assert_eq!(parallel_variance(&[1.0f32]), None);
+        assert_eq!(
+            try_parallel_std_dev(&[1.0f32]),
+            Err(Error::TooFewElements {
+                required: 2,
+                len: 1
+            })
+        );
+    }
+}
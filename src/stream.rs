@@ -0,0 +1,200 @@
+use std::io::{self, BufRead};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+
+use crate::parallel_reduce_with_identity;
+use crate::platform::available_threads;
+
+/// Reduces newline-delimited numbers from a reader, batch by batch, as they arrive.
+///
+/// Lines are read into batches of `batch_size` on the calling thread; every full batch is
+/// parsed and reduced on the thread pool while the next one is being read. Reading pauses
+/// once two batches per available thread are waiting, so input that arrives faster than
+/// it is reduced is not buffered whole. Blank lines are skipped. The per-batch results
+/// are combined in input order at the end.
+///
+/// # Parameters
+/// - `reader`: The source of newline-delimited values (e.g. locked stdin).
+/// - `batch_size`: The number of lines per batch.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to every value, or an error if reading fails or
+/// a line cannot be parsed. Reading stops at the first error found.
+pub fn parallel_reduce_lines<R, T>(
+    reader: R,
+    batch_size: usize,
+    identity: T,
+    operation: fn(T, T) -> T,
+) -> io::Result<T>
+where
+    R: BufRead,
+    T: FromStr + Copy + Send + Sync,
+{
+    let results: Mutex<Vec<(usize, io::Result<T>)>> = Mutex::new(Vec::new());
+    let batch_size = batch_size.max(1);
+    let in_flight = InFlight::new(2 * available_threads());
+    let failed = AtomicBool::new(false);
+
+    let read_result = rayon::in_place_scope(|scope| -> io::Result<()> {
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut index = 0;
+        let mut lines = reader.lines();
+        while !failed.load(Ordering::Relaxed) {
+            let line = lines.next().transpose()?;
+            let done = line.is_none();
+            if let Some(line) = line {
+                batch.push(line);
+            }
+            if batch.len() == batch_size || (done && !batch.is_empty()) {
+                let lines = std::mem::replace(&mut batch, Vec::with_capacity(batch_size));
+                let (results, in_flight, failed) = (&results, &in_flight, &failed);
+                in_flight.acquire();
+                scope.spawn(move |_| {
+                    if !failed.load(Ordering::Relaxed) {
+                        let reduced = parse_batch(&lines).map(|values| {
+                            parallel_reduce_with_identity(values, identity, operation)
+                        });
+                        if reduced.is_err() {
+                            failed.store(true, Ordering::Relaxed);
+                        }
+                        results.lock().unwrap().push((index, reduced));
+                    }
+                    in_flight.release();
+                });
+                index += 1;
+            }
+            if done {
+                break;
+            }
+        }
+        Ok(())
+    });
+    read_result?;
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results
+        .into_iter()
+        .try_fold(identity, |acc, (_, batch)| Ok(operation(acc, batch?)))
+}
+
+/// Counts the batches spawned but not yet reduced, up to a limit.
+struct InFlight {
+    count: Mutex<usize>,
+    finished: Condvar,
+    limit: usize,
+}
+
+impl InFlight {
+    fn new(limit: usize) -> InFlight {
+        InFlight {
+            count: Mutex::new(0),
+            finished: Condvar::new(),
+            limit: limit.max(1),
+        }
+    }
+
+    /// Waits until fewer than `limit` batches are in flight, then counts one more.
+    fn acquire(&self) {
+        loop {
+            let mut count = self.count.lock().unwrap();
+            if *count < self.limit {
+                *count += 1;
+                return;
+            }
+            drop(count);
+            // On a pool thread the pending batches may be queued behind this one, so
+            // run one of them instead of blocking
+            if rayon::yield_now() == Some(rayon::Yield::Executed) {
+                continue;
+            }
+            let count = self.count.lock().unwrap();
+            drop(
+                self.finished
+                    .wait_while(count, |count| *count >= self.limit)
+                    .unwrap(),
+            );
+        }
+    }
+
+    fn release(&self) {
+        *self.count.lock().unwrap() -= 1;
+        self.finished.notify_one();
+    }
+}
+
+/// Parses the non-blank lines of a batch.
+fn parse_batch<T: FromStr>(lines: &[String]) -> io::Result<Vec<T>> {
+    lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.parse().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid number: {}", line),
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test summing many batches
+    #[test]
+    fn test_reduce_lines_sum() {
+        let input: String = (1..=10_000).map(|i| format!("{}\n", i)).collect();
+        let result = parallel_reduce_lines(input.as_bytes(), 64, 0i64, |a, b| a + b);
+        assert_eq!(result.unwrap(), 50_005_000);
+
+        // On a single pool thread the reader runs the batches it is waiting for
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let result =
+            pool.install(|| parallel_reduce_lines(input.as_bytes(), 16, 0i64, |a, b| a + b));
+        assert_eq!(result.unwrap(), 50_005_000);
+    }
+
+    // Test that blank lines are skipped and floats are supported
+    #[test]
+    fn test_reduce_lines_blank_and_float() {
+        let input = "1.5\n\n  -4.25 \n3\n";
+        let result = parallel_reduce_lines(input.as_bytes(), 2, f64::INFINITY, f64::min);
+        assert_eq!(result.unwrap(), -4.25);
+    }
+
+    // Test that an invalid line is reported
+    #[test]
+    fn test_reduce_lines_invalid() {
+        let result = parallel_reduce_lines("1\ntwo\n3\n".as_bytes(), 1, 0i32, |a, b| a + b);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    // Test that input is read no further than the batches in flight after an error
+    #[test]
+    fn test_reduce_lines_stops_on_error() {
+        let input: String = std::iter::once(String::from("x\n"))
+            .chain((1..=100_000).map(|i| format!("{}\n", i)))
+            .collect();
+        let mut reader = input.as_bytes();
+        let result = parallel_reduce_lines(&mut reader, 1, 0i64, |a, b| a + b);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert!(!reader.is_empty());
+    }
+
+    // Test for empty input
+    #[test]
+    fn test_reduce_lines_empty() {
+        let result = parallel_reduce_lines("".as_bytes(), 8, 1u32, |a, b| a * b);
+        assert_eq!(result.unwrap(), 1);
+    }
+}
@@ -0,0 +1,247 @@
+use rayon::prelude::*;
+use std::f64::consts::PI;
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Compression used by [`parallel_quantile_sketch`].
+pub const DEFAULT_COMPRESSION: f64 = 100.0;
+
+/// A cluster of nearby values, summarized by their mean and count.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// A t-digest: a mergeable sketch of a distribution for approximate quantiles.
+///
+/// Values are kept as weighted centroids that are small near the tails and larger in the
+/// middle, so extreme quantiles such as p99 stay accurate while the digest holds only
+/// on the order of `compression` centroids, however many values it summarizes. Digests
+/// of separate inputs can be [merged](TDigest::merge), e.g. across batches or machines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TDigest {
+    centroids: Vec<Centroid>,
+    compression: f64,
+    count: f64,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    /// Creates an empty digest with the given compression.
+    ///
+    /// Higher compression keeps more centroids and gives more accurate quantiles.
+    ///
+    /// # Panics
+    /// Panics if `compression` is not positive.
+    pub fn new(compression: f64) -> Self {
+        assert!(compression > 0.0, "compression must be positive");
+        TDigest {
+            centroids: Vec::new(),
+            compression,
+            count: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Builds a digest of `values`, skipping NaN.
+    pub fn from_values(values: &[f64], compression: f64) -> Self {
+        let mut digest = TDigest::new(compression);
+        digest.centroids = values
+            .iter()
+            .filter(|x| !x.is_nan())
+            .map(|&mean| Centroid { mean, weight: 1.0 })
+            .collect();
+        digest.compress();
+        digest
+    }
+
+    /// Returns the number of values summarized.
+    pub fn count(&self) -> u64 {
+        self.count as u64
+    }
+
+    /// Returns the smallest value summarized, or `None` if the digest is empty.
+    pub fn min(&self) -> Option<f64> {
+        Some(self.min).filter(|_| self.count > 0.0)
+    }
+
+    /// Returns the largest value summarized, or `None` if the digest is empty.
+    pub fn max(&self) -> Option<f64> {
+        Some(self.max).filter(|_| self.count > 0.0)
+    }
+
+    /// Merges two digests into one summarizing both inputs.
+    ///
+    /// The result keeps the larger of the two compressions.
+    pub fn merge(mut self, other: TDigest) -> TDigest {
+        self.compression = self.compression.max(other.compression);
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.centroids.extend(other.centroids);
+        self.compress();
+        self
+    }
+
+    /// Estimates the value at quantile `q`.
+    ///
+    /// # Parameters
+    /// - `q`: The quantile between 0 and 1 (e.g. `0.99` for p99); clamped to that range.
+    ///
+    /// # Returns
+    /// The estimated value, or `None` if the digest is empty.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        let (first, last) = (self.centroids.first()?, self.centroids.last()?);
+        if self.centroids.len() == 1 {
+            return Some(first.mean);
+        }
+        let target = q.clamp(0.0, 1.0) * self.count;
+
+        // Each centroid's mean is placed at the middle of its weight; beyond the outer
+        // centres, interpolate towards the exact minimum and maximum
+        if target < first.weight / 2.0 {
+            return Some(lerp(self.min, first.mean, target / (first.weight / 2.0)));
+        }
+        if target > self.count - last.weight / 2.0 {
+            let into = target - (self.count - last.weight / 2.0);
+            return Some(lerp(last.mean, self.max, into / (last.weight / 2.0)));
+        }
+        let mut centre = first.weight / 2.0;
+        for pair in self.centroids.windows(2) {
+            let next = centre + (pair[0].weight + pair[1].weight) / 2.0;
+            if target <= next {
+                return Some(lerp(
+                    pair[0].mean,
+                    pair[1].mean,
+                    (target - centre) / (next - centre),
+                ));
+            }
+            centre = next;
+        }
+        Some(last.mean)
+    }
+
+    /// Sorts the centroids and merges neighbours while they fit within the scale
+    /// function's size limit, updating the count and bounds.
+    fn compress(&mut self) {
+        if self.centroids.is_empty() {
+            return;
+        }
+        self.centroids
+            .par_sort_unstable_by(|a, b| a.mean.total_cmp(&b.mean));
+        self.count = self.centroids.iter().map(|c| c.weight).sum();
+        self.min = self.min.min(self.centroids[0].mean);
+        self.max = self.max.max(self.centroids[self.centroids.len() - 1].mean);
+
+        let mut merged: Vec<Centroid> = Vec::with_capacity(self.compression as usize * 2);
+        let mut current = self.centroids[0];
+        let mut before = 0.0;
+        let mut limit = self.k_to_q(self.q_to_k(0.0) + 1.0);
+        for &next in &self.centroids[1..] {
+            let proposed = before + current.weight + next.weight;
+            if proposed <= limit * self.count {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                before += current.weight;
+                limit = self.k_to_q(self.q_to_k(before / self.count) + 1.0);
+                merged.push(current);
+                current = next;
+            }
+        }
+        merged.push(current);
+        self.centroids = merged;
+    }
+
+    /// The k1 scale function, which keeps centroids small near the tails.
+    fn q_to_k(&self, q: f64) -> f64 {
+        self.compression / (2.0 * PI) * (2.0 * q - 1.0).asin()
+    }
+
+    /// The inverse of [`q_to_k`](Self::q_to_k).
+    fn k_to_q(&self, k: f64) -> f64 {
+        let angle = (k * 2.0 * PI / self.compression).min(PI / 2.0);
+        (angle.sin() + 1.0) / 2.0
+    }
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t.clamp(0.0, 1.0)
+}
+
+/// Builds a t-digest of a slice in parallel for approximate quantiles.
+///
+/// Each chunk is summarized in its own digest and the digests are merged, so the result
+/// can be merged again with digests of other batches. NaN values are skipped.
+///
+/// # Parameters
+/// - `data`: The values.
+///
+/// # Returns
+/// A digest with compression [`DEFAULT_COMPRESSION`]; query it with [`TDigest::quantile`].
+pub fn parallel_quantile_sketch<T>(data: &[T]) -> TDigest
+where
+    T: Copy + Send + Sync + Into<f64>,
+{
+    data.par_chunks(aligned_chunk_size(data, available_threads()))
+        .map(|chunk| {
+            let values: Vec<f64> = chunk.iter().map(|&x| x.into()).collect();
+            TDigest::from_values(&values, DEFAULT_COMPRESSION)
+        })
+        .reduce(|| TDigest::new(DEFAULT_COMPRESSION), TDigest::merge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test quantiles of a uniform distribution against the exact values
+    #[test]
+    fn test_quantile_sketch_uniform() {
+        let data: Vec<u32> = (0..1_000_000u64)
+            .map(|i| (i * 7_919 % 1_000_000) as u32)
+            .collect();
+        let digest = parallel_quantile_sketch(&data);
+        assert_eq!(digest.count(), 1_000_000);
+        assert_eq!(digest.min(), Some(0.0));
+        assert_eq!(digest.max(), Some(999_999.0));
+        for q in [0.01, 0.5, 0.95, 0.99, 0.999] {
+            let estimate = digest.quantile(q).unwrap();
+            assert!(
+                (estimate - q * 1e6).abs() < 2_000.0,
+                "q {}: {}",
+                q,
+                estimate
+            );
+        }
+        assert!(digest.centroids.len() <= 2 * DEFAULT_COMPRESSION as usize);
+    }
+
+    // Test that merging digests of two halves matches a digest of the whole
+    #[test]
+    fn test_tdigest_merge() {
+        let low: Vec<f64> = (0..50_000).map(f64::from).collect();
+        let high: Vec<f64> = (50_000..100_000).map(f64::from).collect();
+        let merged = TDigest::from_values(&low, 100.0).merge(TDigest::from_values(&high, 100.0));
+        assert_eq!(merged.count(), 100_000);
+        let median = merged.quantile(0.5).unwrap();
+        assert!((median - 50_000.0).abs() < 500.0, "{}", median);
+    }
+
+    // Test empty, single-value, and NaN inputs
+    #[test]
+    fn test_tdigest_edge_cases() {
+        let empty = parallel_quantile_sketch::<f64>(&[]);
+        assert_eq!(empty.quantile(0.5), None);
+        assert_eq!(empty.min(), None);
+
+        let single = parallel_quantile_sketch(&[f64::NAN, 4.0, f64::NAN]);
+        assert_eq!(single.count(), 1);
+        assert_eq!(single.quantile(0.0), Some(4.0));
+        assert_eq!(single.quantile(1.0), Some(4.0));
+    }
+}
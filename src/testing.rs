@@ -0,0 +1,143 @@
+//! Assertions that guard performance assumptions in tests.
+//!
+//! [`assert_parallel_speedup!`](crate::assert_parallel_speedup) and
+//! [`assert_parallel_not_slower!`](crate::assert_parallel_not_slower) time a parallel
+//! reduction against a sequential fold with the [`bench`](crate::bench) harness,
+//! warmup included, and fail the test if the parallel version is not fast enough. Timings
+//! are only meaningful in optimized builds on an otherwise idle machine, so consider
+//! `#[ignore]`-ing such tests by default or running them with `--release`.
+
+use crate::bench::{benchmark_with_warmup, speedup, DEFAULT_WARMUP};
+use crate::{get_initial_value, Bounded};
+
+/// Number of timed iterations behind each speedup assertion.
+pub const SPEEDUP_ITERATIONS: u32 = 5;
+
+/// Measures how many times faster the parallel reduction is than a sequential fold.
+///
+/// The speedup is taken between the median times of [`SPEEDUP_ITERATIONS`] runs of each
+/// path, after [`DEFAULT_WARMUP`] untimed runs, so a single slow iteration does not
+/// decide the outcome.
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The sequential median time divided by the parallel median time.
+pub fn measure_speedup<T>(data: &[T], identity: T, operation: fn(T, T) -> T) -> f64
+where
+    T: Copy + Send + Sync,
+{
+    let report = benchmark_with_warmup(
+        data,
+        identity,
+        operation,
+        DEFAULT_WARMUP,
+        SPEEDUP_ITERATIONS,
+    );
+    match (report.sequential_summary(), report.parallel_summary()) {
+        (Some(sequential), Some(parallel)) => speedup(sequential.median, parallel.median),
+        _ => report.speedup(),
+    }
+}
+
+/// Panics unless the parallel reduction is at least `min_speedup` times faster than a
+/// sequential fold.
+///
+/// # Parameters
+/// - `data`: The elements to reduce.
+/// - `identity`: A value `e` with `operation(e, x) == x` for every `x`.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `min_speedup`: The required speedup; `1.0` only requires the parallel path not to be slower.
+///
+/// # Panics
+/// Panics with the measured speedup if it is below `min_speedup`.
+#[track_caller]
+pub fn assert_speedup<T>(data: &[T], identity: T, operation: fn(T, T) -> T, min_speedup: f64)
+where
+    T: Copy + Send + Sync,
+{
+    let speedup = measure_speedup(data, identity, operation);
+    assert!(
+        speedup >= min_speedup,
+        "parallel reduction of {} elements was {:.2}x as fast as sequential, expected at least {:.2}x",
+        data.len(),
+        speedup,
+        min_speedup
+    );
+}
+
+/// Like [`assert_speedup`], with the identity probed from the operation as
+/// [`parallel_binary_operation`](crate::parallel_binary_operation) does.
+///
+/// # Panics
+/// Panics with the measured speedup if it is below `min_speedup`.
+#[track_caller]
+pub fn assert_speedup_probed<T>(data: &[T], operation: fn(T, T) -> T, min_speedup: f64)
+where
+    T: Copy + Send + Sync + 'static + Default + PartialEq + From<u8> + Bounded,
+{
+    assert_speedup(data, get_initial_value(operation), operation, min_speedup);
+}
+
+/// Asserts that a parallel reduction is at least a given factor faster than sequential.
+///
+/// `assert_parallel_speedup!(operation, data, min_speedup)` probes the identity like
+/// [`parallel_binary_operation`](crate::parallel_binary_operation);
+/// `assert_parallel_speedup!(operation, data, min_speedup, identity = e)` uses `e`. The
+/// data can be anything that indexes to a slice, such as a `Vec` built by
+/// [`generate_data`](crate::bench::generate_data).
+#[macro_export]
+macro_rules! assert_parallel_speedup {
+    ($operation:expr, $data:expr, $min_speedup:expr $(,)?) => {
+        $crate::testing::assert_speedup_probed(&$data[..], $operation, $min_speedup)
+    };
+    ($operation:expr, $data:expr, $min_speedup:expr, identity = $identity:expr $(,)?) => {
+        $crate::testing::assert_speedup(&$data[..], $identity, $operation, $min_speedup)
+    };
+}
+
+/// Asserts that a parallel reduction is not slower than a sequential fold.
+///
+/// Takes the same arguments as [`assert_parallel_speedup!`] without the speedup.
+#[macro_export]
+macro_rules! assert_parallel_not_slower {
+    ($operation:expr, $data:expr $(,)?) => {
+        $crate::assert_parallel_speedup!($operation, $data, 1.0)
+    };
+    ($operation:expr, $data:expr, identity = $identity:expr $(,)?) => {
+        $crate::assert_parallel_speedup!($operation, $data, 1.0, identity = $identity)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::generate_data;
+
+    // Test that the measured speedup is a positive ratio
+    #[test]
+    fn test_measure_speedup() {
+        let data = generate_data(10_000, |i| i as u64);
+        let speedup = measure_speedup(&data, 0, |a, b| a + b);
+        assert!(speedup > 0.0);
+    }
+
+    // Test that a trivially met requirement passes through both macro forms
+    #[test]
+    fn test_assert_parallel_speedup() {
+        let data = generate_data(10_000, |i| i as u64);
+        crate::assert_parallel_speedup!(|a, b| a + b, data, 0.0);
+        crate::assert_parallel_speedup!(u64::max, data, 0.0, identity = 0);
+    }
+
+    // Test that an unreachable requirement fails with the measured speedup
+    #[test]
+    #[should_panic(expected = "expected at least 1000000000.00x")]
+    fn test_assert_parallel_speedup_fails() {
+        let data = generate_data(1_000, |i| i as u64);
+        crate::assert_parallel_speedup!(|a, b| a + b, data, 1e9);
+    }
+}
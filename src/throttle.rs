@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use crate::platform::{self, Stopwatch};
+
+/// Number of elements folded between duty-cycle checks.
+const DUTY_CYCLE_SLICE: usize = 4096;
+
+/// Limits how much CPU a reduction is allowed to use.
+///
+/// Useful for background aggregation jobs on shared machines, where a reduction
+/// should not starve interactive processes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Throttle {
+    /// Never run more than this many chunk workers at the same time.
+    MaxWorkers(usize),
+    /// Keep each worker busy for at most this fraction of the time, sleeping for the
+    /// remainder. Must be greater than 0 and at most 1; 1 leaves the workers unthrottled.
+    DutyCycle(f64),
+}
+
+impl Throttle {
+    /// Caps a thread count according to this throttle.
+    pub(crate) fn limit_threads(&self, threads: usize) -> usize {
+        match *self {
+            Throttle::MaxWorkers(max) => threads.min(max.max(1)),
+            Throttle::DutyCycle(_) => threads,
+        }
+    }
+}
+
+/// Folds a chunk, pausing between slices when a duty cycle is configured.
+///
+/// # Parameters
+/// - `chunk`: The elements to fold.
+/// - `initial`: The initial value of the fold.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+/// - `throttle`: The throttle to respect, if any.
+///
+/// # Returns
+/// The result of folding the chunk with the operation.
+pub(crate) fn fold_chunk<T>(
+    chunk: &[T],
+    initial: T,
+    operation: fn(T, T) -> T,
+    throttle: Option<Throttle>,
+) -> T
+where
+    T: Copy,
+{
+    let fraction = match throttle {
+        Some(Throttle::DutyCycle(fraction)) if fraction > 0.0 && fraction < 1.0 => fraction,
+        _ => return chunk.iter().copied().fold(initial, operation),
+    };
+
+    let mut acc = initial;
+    for slice in chunk.chunks(DUTY_CYCLE_SLICE) {
+        let start = Stopwatch::start();
+        acc = slice.iter().copied().fold(acc, operation);
+        let busy = start.elapsed();
+        // Sleep long enough that busy / (busy + idle) == fraction
+        let idle = busy.as_secs_f64() * (1.0 - fraction) / fraction;
+        platform::sleep(Duration::from_secs_f64(idle));
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that max workers caps the thread count
+    #[test]
+    fn test_max_workers_limits_threads() {
+        assert_eq!(Throttle::MaxWorkers(2).limit_threads(8), 2);
+        assert_eq!(Throttle::MaxWorkers(16).limit_threads(8), 8);
+        assert_eq!(Throttle::MaxWorkers(0).limit_threads(8), 1);
+    }
+
+    // Test that a duty-cycled fold gives the same result
+    #[test]
+    fn test_duty_cycle_fold() {
+        let data: Vec<i64> = (1..=10_000).collect();
+        let result = fold_chunk(&data, 0, |a, b| a + b, Some(Throttle::DutyCycle(0.5)));
+        assert_eq!(result, 50_005_000);
+    }
+}
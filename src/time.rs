@@ -0,0 +1,91 @@
+use rayon::prelude::*;
+use std::time::{Duration, SystemTime};
+
+use crate::aligned_chunk_size;
+use crate::platform::available_threads;
+
+/// Finds the earliest timestamp in parallel.
+///
+/// Works for any totally ordered timestamp type, e.g. `SystemTime`, `Instant`, or
+/// `chrono::DateTime<Tz>`.
+///
+/// # Returns
+/// The earliest timestamp, or `None` for an empty slice.
+pub fn parallel_earliest<T>(data: &[T]) -> Option<T>
+where
+    T: Ord + Copy + Send + Sync,
+{
+    data.par_iter().copied().min()
+}
+
+/// Finds the latest timestamp in parallel.
+///
+/// # Returns
+/// The latest timestamp, or `None` for an empty slice.
+pub fn parallel_latest<T>(data: &[T]) -> Option<T>
+where
+    T: Ord + Copy + Send + Sync,
+{
+    data.par_iter().copied().max()
+}
+
+/// Finds the earliest and latest timestamps in a single parallel pass.
+///
+/// # Returns
+/// The `(earliest, latest)` pair, or `None` for an empty slice.
+pub fn parallel_time_bounds<T>(data: &[T]) -> Option<(T, T)>
+where
+    T: Ord + Copy + Send + Sync,
+{
+    let chunk_size = aligned_chunk_size(data, available_threads());
+    data.par_iter()
+        .with_min_len(chunk_size)
+        .map(|&t| (t, t))
+        .reduce_with(|(lo1, hi1), (lo2, hi2)| (lo1.min(lo2), hi1.max(hi2)))
+}
+
+/// Computes the span between the earliest and latest `SystemTime` in parallel.
+///
+/// # Returns
+/// The elapsed time from the earliest to the latest timestamp, or `None` for an
+/// empty slice.
+pub fn parallel_time_span(data: &[SystemTime]) -> Option<Duration> {
+    let (earliest, latest) = parallel_time_bounds(data)?;
+    Some(latest.duration_since(earliest).unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn timestamps() -> Vec<SystemTime> {
+        (0..1_000u64)
+            .map(|i| UNIX_EPOCH + Duration::from_secs((i * 7_919) % 1_009))
+            .collect()
+    }
+
+    // Test for earliest and latest
+    #[test]
+    fn test_earliest_latest() {
+        let data = timestamps();
+        assert_eq!(parallel_earliest(&data), Some(UNIX_EPOCH));
+        assert_eq!(parallel_latest(&data), data.iter().copied().max());
+    }
+
+    // Test for the span between earliest and latest
+    #[test]
+    fn test_time_span() {
+        let data = timestamps();
+        let latest = data.iter().max().unwrap();
+        let expected = latest.duration_since(UNIX_EPOCH).unwrap();
+        assert_eq!(parallel_time_span(&data), Some(expected));
+    }
+
+    // Test for empty input
+    #[test]
+    fn test_time_empty() {
+        assert_eq!(parallel_time_bounds::<SystemTime>(&[]), None);
+        assert_eq!(parallel_time_span(&[]), None);
+    }
+}
@@ -0,0 +1,230 @@
+use rayon::prelude::*;
+use std::time::Duration;
+
+use crate::aligned_chunk_size;
+use crate::platform::{self, available_threads};
+
+/// How often, and how patiently, a failing chunk is retried.
+///
+/// The delay before retry `n` (starting at 1) is `backoff * multiplier^(n - 1)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts per chunk, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub backoff: Duration,
+    /// Factor the delay is multiplied by after every retry.
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    /// A single attempt with no retries.
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            backoff: Duration::ZERO,
+            multiplier: 1.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy with `max_attempts` attempts and a constant `backoff` between them.
+    pub fn new(max_attempts: u32, backoff: Duration) -> Self {
+        RetryPolicy {
+            max_attempts,
+            backoff,
+            multiplier: 1.0,
+        }
+    }
+
+    /// Makes the delay grow by `multiplier` after every retry.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Runs `attempt` until it succeeds or the attempts are used up.
+    fn run<T, E>(&self, mut attempt: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+        let mut delay = self.backoff;
+        let mut tries = 1;
+        loop {
+            match attempt() {
+                Ok(value) => return Ok(value),
+                Err(err) if tries >= self.max_attempts => return Err(err),
+                Err(_) => {
+                    platform::sleep(delay);
+                    delay = delay.mul_f64(self.multiplier);
+                    tries += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Folds a chunk starting from its first element, so no identity value is needed.
+fn try_fold_chunk<T, E>(chunk: &[T], operation: fn(T, T) -> Result<T, E>) -> Result<T, E>
+where
+    T: Copy,
+{
+    let (first, rest) = chunk.split_first().expect("chunks are never empty");
+    rest.iter().try_fold(*first, |acc, &b| operation(acc, b))
+}
+
+/// Performs a parallel binary operation whose operation can fail.
+///
+/// Each chunk is folded in parallel and the per-chunk results are combined in order.
+/// The first error encountered fails the whole reduction.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `operation`: A closure that combines two operands, or returns an error.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or the first error.
+/// An empty vector yields `T::default()`.
+pub fn parallel_try_reduce<T, E>(data: Vec<T>, operation: fn(T, T) -> Result<T, E>) -> Result<T, E>
+where
+    T: Copy + Send + Sync + Default,
+    E: Send,
+{
+    parallel_try_reduce_with_retry(data, operation, &RetryPolicy::default())
+}
+
+/// Performs a fallible parallel binary operation, retrying failed chunks.
+///
+/// A chunk whose fold fails is recomputed from scratch according to `policy`; so is a
+/// failing step while combining the per-chunk results. Only when the attempts are used
+/// up does the whole reduction fail.
+///
+/// # Parameters
+/// - `data`: A vector of type `T` that contains the data to operate on.
+/// - `operation`: A closure that combines two operands, or returns an error.
+/// - `policy`: The retry policy applied to each chunk.
+///
+/// # Returns
+/// The result of applying the operation to all elements, or the last error of the
+/// first chunk that ran out of attempts.
+pub fn parallel_try_reduce_with_retry<T, E>(
+    data: Vec<T>,
+    operation: fn(T, T) -> Result<T, E>,
+    policy: &RetryPolicy,
+) -> Result<T, E>
+where
+    T: Copy + Send + Sync + Default,
+    E: Send,
+{
+    if data.is_empty() {
+        return Ok(T::default());
+    }
+
+    let chunk_size = aligned_chunk_size(&data, available_threads());
+
+    let partials = data
+        .par_chunks(chunk_size)
+        .map(|chunk| policy.run(|| try_fold_chunk(chunk, operation)))
+        .collect::<Result<Vec<T>, E>>()?;
+
+    let (first, rest) = partials.split_first().expect("data is not empty");
+    rest.iter()
+        .try_fold(*first, |acc, &b| policy.run(|| operation(acc, b)))
+}
+
+/// Performs a parallel binary operation over elements that may already have failed.
+///
+/// Meant for pipelines where parsing or validation upstream produces a `Result` per
+/// element: the `Ok` values are reduced in order, and as soon as any worker meets an
+/// `Err` the others stop picking up new elements. With several errors in the input, any
+/// one of them may be returned.
+///
+/// # Parameters
+/// - `data`: A vector of fallible elements.
+/// - `operation`: A closure that takes two operands of type `T` and returns a result of type `T`.
+///
+/// # Returns
+/// The result of applying the operation to every element, or an error from the input.
+/// An empty vector yields `Ok(T::default())`.
+pub fn parallel_try_reduce_results<T, E>(
+    data: Vec<Result<T, E>>,
+    operation: fn(T, T) -> T,
+) -> Result<T, E>
+where
+    T: Send + Default,
+    E: Send,
+{
+    let chunk_size = aligned_chunk_size(&data, available_threads());
+    data.into_par_iter()
+        .with_min_len(chunk_size)
+        .try_reduce_with(|a, b| Ok(operation(a, b)))
+        .unwrap_or_else(|| Ok(T::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Test for a fallible operation that always succeeds
+    #[test]
+    fn test_try_reduce_ok() {
+        let data: Vec<i32> = (1..=100).collect();
+        let result = parallel_try_reduce(data, |a: i32, b| a.checked_add(b).ok_or("overflow"));
+        assert_eq!(result, Ok(5050));
+    }
+
+    // Test that an error fails the reduction
+    #[test]
+    fn test_try_reduce_err() {
+        let data = vec![i32::MAX, 1, 2, 3];
+        let result = parallel_try_reduce(data, |a: i32, b| a.checked_add(b).ok_or("overflow"));
+        assert_eq!(result, Err("overflow"));
+    }
+
+    static FAILURES_LEFT: AtomicUsize = AtomicUsize::new(3);
+
+    fn flaky_add(a: i64, b: i64) -> Result<i64, &'static str> {
+        let fail = FAILURES_LEFT
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if fail {
+            Err("transient")
+        } else {
+            Ok(a + b)
+        }
+    }
+
+    // Test that transient failures are retried away
+    #[test]
+    fn test_try_reduce_retry() {
+        let data: Vec<i64> = (1..=1_000).collect();
+        let policy = RetryPolicy::new(5, Duration::from_millis(1)).with_multiplier(2.0);
+        let result = parallel_try_reduce_with_retry(data, flaky_add, &policy);
+        assert_eq!(result, Ok(500_500));
+    }
+
+    // Test reducing parsed elements, with and without a parse error
+    #[test]
+    fn test_try_reduce_results() {
+        let parsed: Vec<Result<u64, String>> = (1..=10_000)
+            .map(|i| i.to_string().parse().map_err(|_| i.to_string()))
+            .collect();
+        assert_eq!(
+            parallel_try_reduce_results(parsed, |a, b| a + b),
+            Ok(50_005_000)
+        );
+
+        let lines = ["1", "2", "x", "4"];
+        let parsed: Vec<Result<i32, String>> = lines
+            .iter()
+            .map(|line| line.parse().map_err(|_| format!("bad line {:?}", line)))
+            .collect();
+        assert_eq!(
+            parallel_try_reduce_results(parsed, |a, b| a + b),
+            Err(String::from("bad line \"x\""))
+        );
+        assert_eq!(
+            parallel_try_reduce_results::<i32, ()>(Vec::new(), |a, b| a + b),
+            Ok(0)
+        );
+    }
+}
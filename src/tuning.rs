@@ -0,0 +1,346 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Once, RwLock};
+
+use crate::platform::available_threads;
+use crate::ParallelConfig;
+
+/// Environment variable naming the tuning profile file.
+pub const PROFILE_ENV: &str = "PARALLEL_OPERATIONS_PROFILE";
+
+/// Tuned parameters for elements of one size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TuningEntry {
+    /// Element size in bytes these parameters were measured for.
+    pub element_size: usize,
+    /// Inputs shorter than this are reduced sequentially.
+    pub sequential_threshold: usize,
+    /// Number of elements per chunk.
+    pub chunk_size: usize,
+}
+
+/// Machine-specific reduction parameters, produced by [`autotune`].
+///
+/// Stored as plain text, one `key=value` setting per field:
+///
+/// ```text
+/// threads=8
+/// element_size=8 sequential_threshold=32768 chunk_size=262144
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TuningProfile {
+    /// Number of worker threads that gave the best throughput.
+    pub threads: usize,
+    /// Parameters per element size.
+    pub entries: Vec<TuningEntry>,
+}
+
+impl TuningProfile {
+    /// Returns the entry measured for the element size closest to `element_size`.
+    pub fn entry_for(&self, element_size: usize) -> Option<&TuningEntry> {
+        self.entries
+            .iter()
+            .min_by_key(|entry| entry.element_size.abs_diff(element_size))
+    }
+
+    /// Builds the configuration this profile recommends for elements of `element_size` bytes.
+    ///
+    /// The thread count is only set when it is below the number of available cores, so
+    /// the global pool keeps being used otherwise.
+    pub fn config_for(&self, element_size: usize) -> ParallelConfig {
+        let mut config = ParallelConfig::new();
+        if self.threads < available_threads() {
+            config = config.with_threads(self.threads);
+        }
+        if let Some(entry) = self.entry_for(element_size) {
+            config = config
+                .with_sequential_threshold(entry.sequential_threshold)
+                .with_chunk_size(entry.chunk_size);
+        }
+        config
+    }
+
+    /// Parses a profile from its text form.
+    ///
+    /// # Returns
+    /// The profile, or an `InvalidData` error naming the offending line.
+    pub fn parse(text: &str) -> io::Result<TuningProfile> {
+        let mut threads = None;
+        let mut entries = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid tuning profile line {}: {}", number + 1, line),
+                )
+            };
+
+            let mut fields = [None; 3];
+            for field in line.split_whitespace() {
+                let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+                let value: usize = value.parse().map_err(|_| invalid())?;
+                let slot = match key {
+                    "threads" => {
+                        threads = Some(value);
+                        continue;
+                    }
+                    "element_size" => 0,
+                    "sequential_threshold" => 1,
+                    "chunk_size" => 2,
+                    _ => return Err(invalid()),
+                };
+                fields[slot] = Some(value);
+            }
+            match fields {
+                [Some(element_size), Some(sequential_threshold), Some(chunk_size)] => {
+                    entries.push(TuningEntry {
+                        element_size,
+                        sequential_threshold,
+                        chunk_size,
+                    })
+                }
+                [None, None, None] => {}
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(TuningProfile {
+            threads: threads.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "tuning profile has no threads")
+            })?,
+            entries,
+        })
+    }
+
+    /// Reads a profile from a file.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<TuningProfile> {
+        TuningProfile::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Writes the profile to a file, creating its directory if needed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for TuningProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# parallel_operations tuning profile")?;
+        writeln!(f, "threads={}", self.threads)?;
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "element_size={} sequential_threshold={} chunk_size={}",
+                entry.element_size, entry.sequential_threshold, entry.chunk_size
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns where the tuning profile is stored.
+///
+/// This is the file named by `PARALLEL_OPERATIONS_PROFILE` if set, and otherwise
+/// `parallel_operations/tuning.profile` under `XDG_CONFIG_HOME` (or `~/.config`).
+pub fn default_profile_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(PROFILE_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(
+        config_dir
+            .join("parallel_operations")
+            .join("tuning.profile"),
+    )
+}
+
+static ACTIVE: RwLock<Option<Arc<TuningProfile>>> = RwLock::new(None);
+static LOAD: Once = Once::new();
+
+/// Returns the profile reductions currently use.
+///
+/// On first use the profile at [`default_profile_path`] is loaded, if one exists and
+/// parses; otherwise no profile is active.
+pub fn active_profile() -> Option<Arc<TuningProfile>> {
+    LOAD.call_once(|| {
+        let profile = default_profile_path().and_then(|path| TuningProfile::load(path).ok());
+        *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = profile.map(Arc::new);
+    });
+    ACTIVE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Replaces the active profile, or deactivates tuning with `None`.
+pub fn set_active_profile(profile: Option<TuningProfile>) {
+    LOAD.call_once(|| {});
+    *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = profile.map(Arc::new);
+}
+
+/// Benchmarks this machine, saves the profile to [`default_profile_path`], and activates it.
+///
+/// Measures the fastest thread count, then for 4-, 8-, and 16-byte elements the fastest
+/// chunk size and the input length from which a parallel sum beats a sequential one.
+/// Takes a few seconds.
+///
+/// # Returns
+/// The measured profile, or an I/O error if no profile location is known or the file
+/// cannot be written.
+#[cfg(feature = "bench")]
+pub fn autotune() -> io::Result<TuningProfile> {
+    let path = default_profile_path().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "no profile location: set PARALLEL_OPERATIONS_PROFILE or HOME",
+        )
+    })?;
+    autotune_to(path)
+}
+
+/// Benchmarks this machine, saves the profile to `path`, and activates it.
+#[cfg(feature = "bench")]
+pub fn autotune_to<P: AsRef<Path>>(path: P) -> io::Result<TuningProfile> {
+    // Measure the untuned behavior, not the effect of a previous profile
+    set_active_profile(None);
+    let profile = measure::profile();
+    profile.save(path)?;
+    set_active_profile(Some(profile.clone()));
+    Ok(profile)
+}
+
+#[cfg(feature = "bench")]
+mod measure {
+    use std::mem;
+
+    use super::{TuningEntry, TuningProfile};
+    use crate::bench::{
+        benchmark_with_warmup, chunk_size_candidates, generate_data, recommend_chunk_size,
+        sweep_chunk_sizes, sweep_threads, thread_counts,
+    };
+    use crate::platform::available_threads;
+
+    /// Number of elements the thread count and chunk size are tuned on.
+    const TUNE_LEN: usize = 1 << 22;
+    /// Largest input length checked for the sequential cutoff.
+    const MAX_THRESHOLD: usize = 1 << 20;
+    const ITERATIONS: u32 = 5;
+
+    pub(super) fn profile() -> TuningProfile {
+        let threads = fastest_thread_count();
+        let entries = match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+            Ok(pool) => pool.install(tune_entries),
+            Err(_) => tune_entries(),
+        };
+        TuningProfile { threads, entries }
+    }
+
+    fn tune_entries() -> Vec<TuningEntry> {
+        vec![
+            tune_element(|i| i as u32, |a, b| a.wrapping_add(b)),
+            tune_element(|i| i as u64, |a, b| a.wrapping_add(b)),
+            tune_element(|i| i as u128, |a, b| a.wrapping_add(b)),
+        ]
+    }
+
+    fn fastest_thread_count() -> usize {
+        let data = generate_data(TUNE_LEN, |i| i as u64);
+        let counts = thread_counts(available_threads());
+        sweep_threads(&data, 0, |a, b| a.wrapping_add(b), &counts, 1, ITERATIONS)
+            .iter()
+            .min_by_key(|result| result.summary.median)
+            .map_or(1, |result| result.threads)
+    }
+
+    fn tune_element<T: Copy + Send + Sync + Default>(
+        generator: fn(usize) -> T,
+        operation: fn(T, T) -> T,
+    ) -> TuningEntry {
+        let data = generate_data(TUNE_LEN, generator);
+        let identity = T::default();
+
+        let candidates = chunk_size_candidates(data.len(), 1_024);
+        let results = sweep_chunk_sizes(&data, identity, operation, &candidates, 1, ITERATIONS);
+        let chunk_size = recommend_chunk_size(&results).unwrap_or(data.len());
+
+        let sequential_threshold = std::iter::successors(Some(256), |&n| Some(n * 2))
+            .take_while(|&n| n <= MAX_THRESHOLD)
+            .find(|&len| {
+                let report =
+                    benchmark_with_warmup(&data[..len], identity, operation, 1, ITERATIONS);
+                match (report.parallel_summary(), report.sequential_summary()) {
+                    (Some(parallel), Some(sequential)) => parallel.median < sequential.median,
+                    _ => false,
+                }
+            })
+            .unwrap_or(MAX_THRESHOLD);
+
+        TuningEntry {
+            element_size: mem::size_of::<T>(),
+            sequential_threshold,
+            chunk_size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> TuningProfile {
+        TuningProfile {
+            threads: 4,
+            entries: vec![
+                TuningEntry {
+                    element_size: 4,
+                    sequential_threshold: 65_536,
+                    chunk_size: 262_144,
+                },
+                TuningEntry {
+                    element_size: 8,
+                    sequential_threshold: 32_768,
+                    chunk_size: 131_072,
+                },
+            ],
+        }
+    }
+
+    // Test that a profile survives a round trip through its text form
+    #[test]
+    fn test_profile_round_trip() {
+        let profile = sample();
+        assert_eq!(TuningProfile::parse(&profile.to_string()).unwrap(), profile);
+    }
+
+    // Test that malformed profiles are rejected
+    #[test]
+    fn test_profile_parse_errors() {
+        let err = TuningProfile::parse("threads=4\nelement_size=8 chunk_size=oops").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("line 2"));
+        assert!(
+            TuningProfile::parse("element_size=8 sequential_threshold=1 chunk_size=1").is_err()
+        );
+    }
+
+    // Test that the closest element size is used for the configuration
+    #[test]
+    fn test_profile_config() {
+        let profile = sample();
+        assert_eq!(profile.entry_for(16).unwrap().element_size, 8);
+        let config = profile.config_for(4);
+        assert_eq!(config.sequential_threshold, 65_536);
+        assert_eq!(config.chunk_size, Some(262_144));
+    }
+}
@@ -0,0 +1,139 @@
+use rayon::prelude::*;
+
+use crate::error::catch_panic;
+use crate::platform::available_threads;
+use crate::{aligned_chunk_size, Error};
+
+/// Reduces a vector of pairs componentwise in a single parallel pass.
+///
+/// Each component has its own operation, e.g. summing the first field while taking the
+/// maximum of the second, without splitting the data into separate vectors.
+///
+/// # Parameters
+/// - `data`: A vector of pairs.
+/// - `op_a`: The operation applied to the first components.
+/// - `op_b`: The operation applied to the second components.
+///
+/// # Returns
+/// The pair of componentwise results, or `None` for an empty vector.
+pub fn parallel_reduce_pairs<A, B>(
+    data: Vec<(A, B)>,
+    op_a: fn(A, A) -> A,
+    op_b: fn(B, B) -> B,
+) -> Option<(A, B)>
+where
+    A: Send,
+    B: Send,
+{
+    let chunk_size = aligned_chunk_size(&data, available_threads());
+    data.into_par_iter()
+        .with_min_len(chunk_size)
+        .reduce_with(|(a1, b1), (a2, b2)| (op_a(a1, a2), op_b(b1, b2)))
+}
+
+/// Reduces a vector of triples componentwise in a single parallel pass.
+///
+/// # Parameters
+/// - `data`: A vector of triples.
+/// - `op_a`: The operation applied to the first components.
+/// - `op_b`: The operation applied to the second components.
+/// - `op_c`: The operation applied to the third components.
+///
+/// # Returns
+/// The triple of componentwise results, or `None` for an empty vector.
+pub fn parallel_reduce_triples<A, B, C>(
+    data: Vec<(A, B, C)>,
+    op_a: fn(A, A) -> A,
+    op_b: fn(B, B) -> B,
+    op_c: fn(C, C) -> C,
+) -> Option<(A, B, C)>
+where
+    A: Send,
+    B: Send,
+    C: Send,
+{
+    let chunk_size = aligned_chunk_size(&data, available_threads());
+    data.into_par_iter()
+        .with_min_len(chunk_size)
+        .reduce_with(|(a1, b1, c1), (a2, b2, c2)| (op_a(a1, a2), op_b(b1, b2), op_c(c1, c2)))
+}
+
+/// Reduces a vector of pairs componentwise, reporting failures as errors.
+///
+/// # Parameters
+/// - `data`: A vector of pairs.
+/// - `op_a`: The operation applied to the first components.
+/// - `op_b`: The operation applied to the second components.
+///
+/// # Returns
+/// The pair of componentwise results, [`Error::EmptyInput`] for an empty vector, or
+/// [`Error::OperationPanicked`].
+pub fn try_parallel_reduce_pairs<A, B>(
+    data: Vec<(A, B)>,
+    op_a: fn(A, A) -> A,
+    op_b: fn(B, B) -> B,
+) -> Result<(A, B), Error>
+where
+    A: Send,
+    B: Send,
+{
+    catch_panic(|| parallel_reduce_pairs(data, op_a, op_b))?.ok_or(Error::EmptyInput)
+}
+
+/// Reduces a vector of triples componentwise, reporting failures as errors.
+///
+/// # Parameters
+/// - `data`: A vector of triples.
+/// - `op_a`: The operation applied to the first components.
+/// - `op_b`: The operation applied to the second components.
+/// - `op_c`: The operation applied to the third components.
+///
+/// # Returns
+/// The triple of componentwise results, [`Error::EmptyInput`] for an empty vector, or
+/// [`Error::OperationPanicked`].
+pub fn try_parallel_reduce_triples<A, B, C>(
+    data: Vec<(A, B, C)>,
+    op_a: fn(A, A) -> A,
+    op_b: fn(B, B) -> B,
+    op_c: fn(C, C) -> C,
+) -> Result<(A, B, C), Error>
+where
+    A: Send,
+    B: Send,
+    C: Send,
+{
+    catch_panic(|| parallel_reduce_triples(data, op_a, op_b, op_c))?.ok_or(Error::EmptyInput)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test summing one field while taking the maximum of another
+    #[test]
+    fn test_reduce_pairs() {
+        let data: Vec<(i64, f64)> = (1..=100).map(|x| (x, (x % 17) as f64)).collect();
+        let result = parallel_reduce_pairs(data, |a, b| a + b, f64::max);
+        assert_eq!(result, Some((5050, 16.0)));
+    }
+
+    // Test for triples
+    #[test]
+    fn test_reduce_triples() {
+        let data: Vec<(u32, i32, u64)> = (1..=10).map(|x| (1, -x, x as u64)).collect();
+        let result = parallel_reduce_triples(data, |a, b| a + b, i32::min, |a, b| a * b);
+        assert_eq!(result, Some((10, -10, 3_628_800)));
+    }
+
+    // Test for empty vector
+    #[test]
+    fn test_reduce_pairs_empty() {
+        let data: Vec<(i32, i32)> = Vec::new();
+        assert_eq!(parallel_reduce_pairs(data, |a, b| a + b, i32::max), None);
+        let data: Vec<(u8, u8)> = vec![(200, 1), (100, 2)];
+        assert!(matches!(
+            try_parallel_reduce_pairs(data, |a, b| a.checked_add(b).unwrap(), u8::max),
+            Err(Error::OperationPanicked(_))
+        ));
+    }
+}